@@ -71,7 +71,7 @@ impl VM {
 
                                                     call.arguments = vec![
                                                         Expr::Literal(Literal::Number(reg.to_string())),
-                                                        Expr::Ident(ident.to_string()),
+                                                        VM::guarded_dependency_expr(ident),
                                                     ];
 
                                                     ProgramPart::Stmt(Stmt::Expr(Expr::Call(call)))
@@ -99,6 +99,28 @@ impl VM {
         })
     }
 
+    /// Host globals do not exist in every environment, e.g. if they are only used after
+    /// a ``typeof`` check. They are thus loaded as ``typeof ident == "undefined" ? void 0 : ident``.
+    fn guarded_dependency_expr(ident: &str) -> Expr {
+        Expr::Conditional(ConditionalExpr {
+            test: Box::new(Expr::Binary(BinaryExpr {
+                operator: BinaryOperator::Equal,
+                left: Box::new(Expr::Unary(UnaryExpr {
+                    operator: UnaryOperator::TypeOf,
+                    prefix: true,
+                    argument: Box::new(Expr::Ident(ident.to_string()))
+                })),
+                right: Box::new(Expr::Literal(Literal::String("\"undefined\"".into())))
+            })),
+            consequent: Box::new(Expr::Unary(UnaryExpr {
+                operator: UnaryOperator::Void,
+                prefix: true,
+                argument: Box::new(Expr::Literal(Literal::Number("0".into())))
+            })),
+            alternate: Box::new(Expr::Ident(ident.to_string()))
+        })
+    }
+
     pub fn strip_unneeded(self) -> CompositionResult<Self> {
         Ok(VM {
             vm_template: self.vm_template.into_iter().filter(|part| {
@@ -216,8 +238,8 @@ fn test_vm_inject_decleration_dependencies() {
               init(bytecode) {
                   this.setReg(255, 0);
 
-                  this.setReg(2, document);
-                  this.setReg(10, window);
+                  this.setReg(2, typeof document == \"undefined\" ? void 0 : document);
+                  this.setReg(10, typeof window == \"undefined\" ? void 0 : window);
               }
         }")).unwrap();
 
@@ -226,8 +248,8 @@ fn test_vm_inject_decleration_dependencies() {
               init(bytecode) {
                   this.setReg(255, 0);
 
-                  this.setReg(10, window);
-                  this.setReg(2, document);
+                  this.setReg(10, typeof window == \"undefined\" ? void 0 : window);
+                  this.setReg(2, typeof document == \"undefined\" ? void 0 : document);
               }
         }")).unwrap();
 
@@ -29,6 +29,8 @@ pub enum Instruction
     LoadLongNum,
     LoadNum,
     LoadArray,
+    CreateObject,
+    LoadRegExp,
 
     PropAccess,
     CallFunc,
@@ -38,13 +40,60 @@ pub enum Instruction
     Copy,
     Exit,
     BytecodeFuncCallback,
+    BytecodeArrowFuncCallback,
     PropertySet,
     Try,
     Throw,
+    DefineProperty,
+    DefineGetter,
+    DefineSetter,
+    Construct,
+    CreateEnvironment,
+    LoadCaptured,
+    StoreCaptured,
+    EndTry,
+    LoadSpilled,
+    StoreSpilled,
+    GetKeysIterator,
+    GetIterator,
+    IteratorNext,
+    DeleteProperty,
+    IteratorRest,
+    ObjectRest,
+    LoadArguments,
+    LoadRestArguments,
+    ArraySpread,
+    CallFuncApply,
+    ConstructApply,
+    TemplateObject,
+    ToString,
+    CheckInitialized,
+
+    BytecodeClassCallback,
+    ExtendClass,
+    DefineMethod,
+    DefineMethodGetter,
+    DefineMethodSetter,
+    SuperCall,
+    SuperPropAccess,
+    LoadNewTarget,
+    ReturnConstructor,
+    DefineClassName,
+
+    BytecodeGeneratorCallback,
+    Yield,
+    YieldDelegate,
+    BytecodeAsyncFuncCallback,
+    BytecodeAsyncArrowFuncCallback,
+    Await,
+    TryFinally,
+    IteratorClose,
+    SetPrototype,
 
     JumpCond,
     Jump,
     JumpCondNeg,
+    JumpTable,
 
     CompEqual,
     CompNotEqual,
@@ -54,18 +103,24 @@ pub enum Instruction
     CompGreaterThan,
     CompLessThanEqual,
     CompGreaterThanEqual,
+    In,
+    InstanceOf,
 
     Add,
     Minus,
     Mul,
     Div,
-    // LeftShift
-    // RightShift
-    // Mod,
-    // Or,
-    // XOr,
-    // And,
-    // In,
+    Mod,
+    PowerOf,
+    LeftShift,
+    RightShift,
+    UnsignedRightShift,
+    BitOr,
+    BitXOr,
+    BitAnd,
+    Not,
+    BitNot,
+    TypeOf,
 }
 
 impl Instruction {
@@ -76,6 +131,8 @@ impl Instruction {
             Instruction::LoadFloatNum => 3,
             Instruction::LoadLongNum => 4,
             Instruction::LoadArray => 5,
+            Instruction::CreateObject => 6,
+            Instruction::LoadRegExp => 7,
 
             Instruction::PropAccess => 10,
             Instruction::CallFunc => 11,
@@ -91,6 +148,32 @@ impl Instruction {
             Instruction::PropertySet => 21,
             Instruction::Try => 22,
             Instruction::Throw => 23,
+            Instruction::DefineProperty => 24,
+            Instruction::DefineGetter => 25,
+            Instruction::DefineSetter => 26,
+            Instruction::Construct => 27,
+            Instruction::BytecodeArrowFuncCallback => 28,
+            Instruction::CreateEnvironment => 29,
+            Instruction::LoadCaptured => 30,
+            Instruction::StoreCaptured => 31,
+            Instruction::EndTry => 32,
+            Instruction::LoadSpilled => 33,
+            Instruction::StoreSpilled => 34,
+            Instruction::JumpTable => 35,
+            Instruction::GetKeysIterator => 36,
+            Instruction::GetIterator => 37,
+            Instruction::IteratorNext => 38,
+            Instruction::DeleteProperty => 39,
+            Instruction::IteratorRest => 40,
+            Instruction::ObjectRest => 41,
+            Instruction::LoadArguments => 42,
+            Instruction::LoadRestArguments => 43,
+            Instruction::ArraySpread => 44,
+            Instruction::CallFuncApply => 45,
+            Instruction::ConstructApply => 46,
+            Instruction::TemplateObject => 47,
+            Instruction::ToString => 48,
+            Instruction::CheckInitialized => 49,
 
             Instruction::CompEqual => 50,
             Instruction::CompNotEqual => 51,
@@ -100,11 +183,45 @@ impl Instruction {
             Instruction::CompGreaterThan => 55,
             Instruction::CompLessThanEqual => 56,
             Instruction::CompGreaterThanEqual => 57,
+            Instruction::In => 58,
+            Instruction::InstanceOf => 59,
+
+            Instruction::BytecodeClassCallback => 60,
+            Instruction::ExtendClass => 61,
+            Instruction::DefineMethod => 62,
+            Instruction::DefineMethodGetter => 63,
+            Instruction::DefineMethodSetter => 64,
+            Instruction::SuperCall => 65,
+            Instruction::SuperPropAccess => 66,
+            Instruction::LoadNewTarget => 67,
+            Instruction::ReturnConstructor => 68,
+            Instruction::DefineClassName => 69,
+
+            Instruction::BytecodeGeneratorCallback => 70,
+            Instruction::Yield => 71,
+            Instruction::YieldDelegate => 72,
+            Instruction::BytecodeAsyncFuncCallback => 73,
+            Instruction::BytecodeAsyncArrowFuncCallback => 74,
+            Instruction::Await => 75,
+            Instruction::TryFinally => 76,
+            Instruction::IteratorClose => 77,
+            Instruction::SetPrototype => 78,
 
             Instruction::Add => 100,
             Instruction::Minus => 102,
             Instruction::Mul => 101,
             Instruction::Div => 103,
+            Instruction::Mod => 104,
+            Instruction::PowerOf => 105,
+            Instruction::LeftShift => 106,
+            Instruction::RightShift => 107,
+            Instruction::UnsignedRightShift => 108,
+            Instruction::BitOr => 109,
+            Instruction::BitXOr => 110,
+            Instruction::BitAnd => 111,
+            Instruction::Not => 112,
+            Instruction::BitNot => 113,
+            Instruction::TypeOf => 114,
         }
     }
 
@@ -115,6 +232,8 @@ impl Instruction {
             Instruction::LoadFloatNum => "LoadFloatNum",
             Instruction::LoadLongNum => "LoadLongNum",
             Instruction::LoadArray => "LoadArray",
+            Instruction::CreateObject => "CreateObject",
+            Instruction::LoadRegExp => "LoadRegExp",
 
             Instruction::PropAccess => "PropAccess",
             Instruction::CallFunc => "CallFunc",
@@ -126,10 +245,36 @@ impl Instruction {
             Instruction::JumpCond => "JumpCond",
             Instruction::Jump => "Jump",
             Instruction::JumpCondNeg => "JumpCondNeg",
+            Instruction::JumpTable => "JumpTable",
             Instruction::BytecodeFuncCallback => "BytecodeFuncCallback",
+            Instruction::BytecodeArrowFuncCallback => "BytecodeArrowFuncCallback",
             Instruction::PropertySet => "PropertySet",
             Instruction::Try => "Try",
             Instruction::Throw => "Throw",
+            Instruction::DefineProperty => "DefineProperty",
+            Instruction::DefineGetter => "DefineGetter",
+            Instruction::DefineSetter => "DefineSetter",
+            Instruction::Construct => "Construct",
+            Instruction::CreateEnvironment => "CreateEnvironment",
+            Instruction::LoadCaptured => "LoadCaptured",
+            Instruction::StoreCaptured => "StoreCaptured",
+            Instruction::EndTry => "EndTry",
+            Instruction::LoadSpilled => "LoadSpilled",
+            Instruction::StoreSpilled => "StoreSpilled",
+            Instruction::GetKeysIterator => "GetKeysIterator",
+            Instruction::GetIterator => "GetIterator",
+            Instruction::IteratorNext => "IteratorNext",
+            Instruction::DeleteProperty => "DeleteProperty",
+            Instruction::IteratorRest => "IteratorRest",
+            Instruction::ObjectRest => "ObjectRest",
+            Instruction::LoadArguments => "LoadArguments",
+            Instruction::LoadRestArguments => "LoadRestArguments",
+            Instruction::ArraySpread => "ArraySpread",
+            Instruction::CallFuncApply => "CallFuncApply",
+            Instruction::ConstructApply => "ConstructApply",
+            Instruction::TemplateObject => "TemplateObject",
+            Instruction::ToString => "ToString",
+            Instruction::CheckInitialized => "CheckInitialized",
 
             Instruction::CompEqual => "CompEqual",
             Instruction::CompNotEqual => "CompNotEqual",
@@ -139,11 +284,45 @@ impl Instruction {
             Instruction::CompGreaterThan => "CompGreaterThan",
             Instruction::CompLessThanEqual => "CompLessThanEqual",
             Instruction::CompGreaterThanEqual => "CompGreaterThanEqual",
+            Instruction::In => "In",
+            Instruction::InstanceOf => "InstanceOf",
+
+            Instruction::BytecodeClassCallback => "BytecodeClassCallback",
+            Instruction::ExtendClass => "ExtendClass",
+            Instruction::DefineMethod => "DefineMethod",
+            Instruction::DefineMethodGetter => "DefineMethodGetter",
+            Instruction::DefineMethodSetter => "DefineMethodSetter",
+            Instruction::SuperCall => "SuperCall",
+            Instruction::SuperPropAccess => "SuperPropAccess",
+            Instruction::LoadNewTarget => "LoadNewTarget",
+            Instruction::ReturnConstructor => "ReturnConstructor",
+            Instruction::DefineClassName => "DefineClassName",
+
+            Instruction::BytecodeGeneratorCallback => "BytecodeGeneratorCallback",
+            Instruction::Yield => "Yield",
+            Instruction::YieldDelegate => "YieldDelegate",
+            Instruction::BytecodeAsyncFuncCallback => "BytecodeAsyncFuncCallback",
+            Instruction::BytecodeAsyncArrowFuncCallback => "BytecodeAsyncArrowFuncCallback",
+            Instruction::Await => "Await",
+            Instruction::TryFinally => "TryFinally",
+            Instruction::IteratorClose => "IteratorClose",
+            Instruction::SetPrototype => "SetPrototype",
 
             Instruction::Add => "Add",
             Instruction::Minus => "Minus",
             Instruction::Mul => "Mul",
             Instruction::Div => "Div",
+            Instruction::Mod => "Mod",
+            Instruction::PowerOf => "PowerOf",
+            Instruction::LeftShift => "LeftShift",
+            Instruction::RightShift => "RightShift",
+            Instruction::UnsignedRightShift => "UnsignedRightShift",
+            Instruction::BitOr => "BitOr",
+            Instruction::BitXOr => "BitXOr",
+            Instruction::BitAnd => "BitAnd",
+            Instruction::Not => "Not",
+            Instruction::BitNot => "BitNot",
+            Instruction::TypeOf => "TypeOf",
         }
     }
 }
@@ -200,8 +379,7 @@ impl ToBytes for FunctionArguments {
 ///
 /// ``var b = 100;`` => ``b => BytecodeLiteral::IntNumber(100)``
 ///
-/// # Note
-/// JavaScript regex literals are not yet supported.
+/// ``var c = /\d+/g;`` => ``c => BytecodeLiteral::RegEx("\d+", "g")``
 #[derive(Clone, Debug, PartialEq)]
 pub enum BytecodeLiteral
 {
@@ -210,7 +388,8 @@ pub enum BytecodeLiteral
     FloatNum(f64),
     IntNumber(i64),
     Bool(bool),
-    // RegEx(ressa::expr::RegEx)
+    /// Pattern and flags of a regex literal
+    RegEx(String, String),
 }
 
 impl BytecodeLiteral {
@@ -256,9 +435,102 @@ impl BytecodeLiteral {
                 }
             },
             Literal::Boolean(b) => Ok(BytecodeLiteral::Bool(b)),
-            Literal::RegEx(_) |
-            Literal::Template(_) => Err(CompilerError::are_unsupported("regex and template literals"))
+            Literal::Template(template) => match template.quasis.as_slice() {
+                [quasi] if template.expressions.is_empty() => BytecodeLiteral::cook_template_element(quasi)
+                    .map(BytecodeLiteral::String)
+                    .ok_or_else(|| CompilerError::Custom(format!("Invalid escape sequence in template '{}'", quasi.raw))),
+                _ => Err(CompilerError::Custom("Templates with substitutions are not literals".into()))
+            },
+            Literal::RegEx(regex) => Ok(BytecodeLiteral::RegEx(regex.pattern, regex.flags))
+        }
+    }
+
+    /// Returns the raw string of a template element as it is passed to tag functions
+    ///
+    /// Escape sequences are kept, only line terminators are normalized.
+    pub fn raw_template_element(element: &TemplateElement) -> String {
+        // The parser passes the source text between the delimiters as the cooked string
+        element.cooked.replace("\r\n", "\n").replace('\r', "\n")
+    }
+
+    /// Returns the string of a template element with all escape sequences being replaced
+    ///
+    /// Tagged templates may contain invalid escape sequences, their cooked string is ``undefined``.
+    /// Thus, ``None`` is returned for them.
+    pub fn cook_template_element(element: &TemplateElement) -> Option<String> {
+        let raw = BytecodeLiteral::raw_template_element(element);
+        let mut chars = raw.chars().peekable();
+        let mut cooked = String::with_capacity(raw.len());
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                cooked.push(c);
+                continue;
+            }
+
+            match chars.next()? {
+                'n' => cooked.push('\n'),
+                't' => cooked.push('\t'),
+                'r' => cooked.push('\r'),
+                'b' => cooked.push('\u{8}'),
+                'f' => cooked.push('\u{c}'),
+                'v' => cooked.push('\u{b}'),
+                '0' if !matches!(chars.peek(), Some(c) if c.is_ascii_digit()) => cooked.push('\0'),
+                '0'..='9' => return None,
+                'x' => {
+                    let code = BytecodeLiteral::read_hex_digits(&mut chars, 2)?;
+                    cooked.push(std::char::from_u32(code)?);
+                },
+                'u' => {
+                    let code = BytecodeLiteral::read_unicode_escape(&mut chars)?;
+                    let code = if (0xD800..0xDC00).contains(&code) {
+                        // A high surrogate has to be followed by an escaped low surrogate
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+                        let low = BytecodeLiteral::read_unicode_escape(&mut chars)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return None;
+                        }
+                        0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                    } else {
+                        code
+                    };
+                    cooked.push(std::char::from_u32(code)?);
+                },
+                // Line continuation
+                '\n' => {},
+                escaped => cooked.push(escaped)
+            }
         }
+
+        Some(cooked)
+    }
+
+    fn read_unicode_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<u32> {
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            let mut code: u32 = 0;
+            let mut digits = 0;
+            loop {
+                match chars.next()? {
+                    '}' if digits > 0 => break,
+                    c => code = code.checked_mul(16)?.checked_add(c.to_digit(16)?)?
+                }
+                digits += 1;
+            }
+            if code > 0x10FFFF { None } else { Some(code) }
+        } else {
+            BytecodeLiteral::read_hex_digits(chars, 4)
+        }
+    }
+
+    fn read_hex_digits(chars: &mut std::iter::Peekable<std::str::Chars>, count: usize) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..count {
+            code = code * 16 + chars.next()?.to_digit(16)?;
+        }
+        Some(code)
     }
 }
 
@@ -270,6 +542,7 @@ impl std::fmt::Display for BytecodeLiteral {
             BytecodeLiteral::FloatNum(float) => write!(f, "Float(){})", float),
             BytecodeLiteral::IntNumber(signed_int) => write!(f, "SignedInt({})", signed_int),
             BytecodeLiteral::Bool(bool) => write!(f, "Bool({})", bool),
+            BytecodeLiteral::RegEx(pattern, flags) => write!(f, "RegEx(/{}/{})", pattern, flags),
         }
     }
 }
@@ -316,7 +589,10 @@ impl Operand {
                         std::i32::MIN, std::i32::MAX)))
                 }
             },
-            BytecodeLiteral::Bool(bool) => Ok(Operand::ShortNum(bool as u8)),
+            BytecodeLiteral::Bool(_) => Err(CompilerError::Custom(
+                "Booleans have no operand and are loaded by negating a number".into())),
+            BytecodeLiteral::RegEx(_, _) => Err(CompilerError::Custom(
+                "Regex literals consist of two operands and can only be loaded by LoadRegExp".into())),
         }
     }
 
@@ -583,7 +859,8 @@ impl Bytecode {
     pub fn last_op_is_return(&self) -> bool {
         match self.elements.last() {
             Some(last_element) => match last_element {
-                BytecodeElement::Operation(cmd) => (cmd.instruction == Instruction::ReturnBytecodeFunc),
+                BytecodeElement::Operation(cmd) => matches!(cmd.instruction, Instruction::ReturnBytecodeFunc |
+                                                                             Instruction::ReturnConstructor),
                 _ => false
             },
             None => false
@@ -663,6 +940,9 @@ fn test_bytecode_literal_from_literal() {
 
     assert_eq!(BytecodeLiteral::from_lit(Literal::Number(".1E2".into())).unwrap(),
                 BytecodeLiteral::FloatNum(10.0));
+
+    assert_eq!(BytecodeLiteral::from_lit(Literal::RegEx(RegEx::new("\\d+", "g"))).unwrap(),
+                BytecodeLiteral::RegEx("\\d+".into(), "g".into()));
 }
 
 #[test]
@@ -1,5 +1,5 @@
 use crate::error::{CompilerError, CompilerResult};
-use crate::jshelper::{JSSourceCode, JSAst};
+use crate::jshelper::{JSSourceCode, JSAst, StaticMembers};
 use crate::bytecode::{Bytecode, BytecodeResult};
 use crate::scope::*;
 use crate::bytecode::{*};
@@ -10,6 +10,52 @@ use std::borrow::Borrow;
 use std::collections::{HashMap};
 use std::rc::Rc;
 
+/// Minimal number of integer cases for which a switch statement is compiled into a jump table
+const JUMP_TABLE_MIN_CASES: usize = 4;
+
+/// Maximal number of elements, properties or arguments whose values are kept in registers at once
+///
+/// Longer array literals, object literals and argument lists are built in chunks.
+const MAX_CHUNK_SIZE: usize = 16;
+
+
+/// A member of a class whose function is compiled
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassMember {
+    /// Methods and accessors, ``super`` refers to the parent's prototype or, if static, to the parent class
+    Method { is_static: bool },
+    /// Derived constructors initialize ``this`` by calling the parent constructor through ``super()``
+    Constructor { is_derived: bool },
+    /// Arrow functions in derived constructors, which cannot call the parent constructor yet
+    DerivedConstructorArrow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionKind {
+    Function,
+    /// Arrow functions do not bind their own ``this``, ``arguments`` and ``super``
+    Arrow,
+    ClassMember(ClassMember),
+}
+
+/// The parts of a function which are compiled
+///
+/// Arrow functions have no ``Function`` node, they are compiled from their own parts instead of a copy,
+/// since the AST must not be copied, see [StaticMembers](../jshelper/struct.StaticMembers.html).
+#[derive(Clone, Copy)]
+struct FunctionRef<'a> {
+    id: Option<&'a Identifier>,
+    params: &'a [FunctionArg],
+    body: &'a [ProgramPart],
+    generator: bool,
+    is_async: bool
+}
+
+impl<'a> From<&'a Function> for FunctionRef<'a> {
+    fn from(func: &'a Function) -> Self {
+        FunctionRef { id: func.id.as_ref(), params: &func.params, body: &func.body, generator: func.generator, is_async: func.is_async }
+    }
+}
 
 #[derive(Debug, Clone)]
 struct BytecodeFunction
@@ -20,26 +66,35 @@ struct BytecodeFunction
     // to allow functions using callbacks to themselves.
     bytecode: Option<Bytecode>,
     arguments: Vec<Register>,
-    // Same explanation as above for 'bytecode'
-    used_decls: Option<Vec<Register>>,
+    kind: FunctionKind,
+    is_generator: bool,
+    is_async: bool,
+    // Depth of the environment the function is defined in
+    env_depth: u8,
 }
 
 impl BytecodeFunction {
-    pub fn new_phantom(ident: Identifier, arg_regs: Vec<Register>) -> Self {
+    fn new_phantom(ident: Identifier, arg_regs: Vec<Register>, kind: FunctionKind, func: FunctionRef, env_depth: u8) -> Self {
         BytecodeFunction {
-            ident: ident,
+            ident,
             bytecode: None,
             arguments: arg_regs,
-            used_decls: None,
+            kind,
+            is_generator: func.generator,
+            is_async: func.is_async,
+            env_depth,
         }
     }
 
-    pub fn from_phantom(phantom: Self, bytecode: Bytecode, used_decls: Vec<Register>) -> Self {
+    pub fn from_phantom(phantom: Self, bytecode: Bytecode) -> Self {
         BytecodeFunction {
             ident: phantom.ident,
             bytecode: Some(bytecode),
             arguments: phantom.arguments,
-            used_decls: Some(used_decls),
+            kind: phantom.kind,
+            is_generator: phantom.is_generator,
+            is_async: phantom.is_async,
+            env_depth: phantom.env_depth,
         }
     }
 }
@@ -47,12 +102,20 @@ impl BytecodeFunction {
 #[derive(Clone)]
 struct LoopBlock {
     start_label: Label,
-    end_label: Label
+    end_label: Label,
+    // Number of try statements the loop is nested in
+    try_depth: usize,
+    // Depth of the environment the loop runs in
+    env_depth: u8,
+    // Number of finally blocks the loop is nested in
+    finally_depth: usize,
+    // Switch blocks can only be left by 'break'
+    is_switch: bool
 }
 
 impl LoopBlock {
-    pub fn new(start_label: Label, end_label: Label) -> Self {
-        LoopBlock {start_label, end_label}
+    pub fn new(start_label: Label, end_label: Label, try_depth: usize, env_depth: u8, finally_depth: usize, is_switch: bool) -> Self {
+        LoopBlock {start_label, end_label, try_depth, env_depth, finally_depth, is_switch}
     }
 
     pub fn start_label(&self) -> Label {
@@ -62,6 +125,51 @@ impl LoopBlock {
     pub fn end_label(&self) -> Label {
         self.end_label
     }
+
+    pub fn is_switch(&self) -> bool {
+        self.is_switch
+    }
+
+    /// The target of a jump to ``label``, which leaves everything the loop is nested in
+    pub fn jump_target(&self, label: Label) -> JumpTarget {
+        JumpTarget { label, try_depth: self.try_depth, env_depth: self.env_depth, finally_depth: self.finally_depth }
+    }
+}
+
+/// The target of a break or continue statement
+#[derive(Clone, Copy, PartialEq)]
+struct JumpTarget {
+    label: Label,
+    try_depth: usize,
+    env_depth: u8,
+    finally_depth: usize,
+}
+
+/// Completion kinds of a try statement, which decide how its finally block is left
+const COMPLETION_NORMAL: u8 = 0;
+const COMPLETION_THROW: u8 = 1;
+const COMPLETION_RETURN: u8 = 2;
+/// Break and continue statements leaving the try statement continue at their own target
+const COMPLETION_FIRST_JUMP: u8 = 3;
+
+/// The finally block of a try statement
+///
+/// Every completion of the try and catch blocks runs the finally block first: the completion
+/// kind is written to ``kind_reg``, a thrown or returned value to ``value_reg``. After the
+/// finally block, the completion is continued according to its kind.
+#[derive(Clone)]
+struct FinallyBlock {
+    label: Label,
+    kind_reg: Reg,
+    value_reg: Reg,
+    // Number of try statements the try statement is nested in
+    try_depth: usize,
+    // Depth of the environment the try statement runs in
+    env_depth: u8,
+    // Whether a return statement leaves the try statement
+    returns: bool,
+    // Targets of the break and continue statements leaving the try statement
+    jumps: Vec<JumpTarget>,
 }
 
 #[derive(Clone)]
@@ -91,17 +199,21 @@ impl LabelGenerator {
         counter
     }
 
-    pub fn generate_loop_label_block(&mut self) -> Rc<LoopBlock> {
-        let block = Rc::new(LoopBlock::new(self.generate_label(), self.generate_label()));
+    pub fn generate_loop_label_block(&mut self, try_depth: usize, env_depth: u8, finally_depth: usize, is_switch: bool) -> Rc<LoopBlock> {
+        let block = Rc::new(LoopBlock::new(self.generate_label(), self.generate_label(), try_depth, env_depth, finally_depth, is_switch));
         self.loop_blocks.push(block.clone());
 
-        if let Some(current_js_label) = &self.current_js_label {
-            self.js_labels.insert(current_js_label.to_string(), block.clone());
+        if let Some(current_js_label) = self.current_js_label.take() {
+            self.js_labels.insert(current_js_label, block.clone());
         }
 
         block
     }
 
+    pub fn leave_loop_label_block(&mut self) {
+        self.loop_blocks.pop();
+    }
+
     pub fn get_js_labled_block(&self, js_label: &Identifier) -> Option<&LoopBlock> {
         self.js_labels.get(js_label).map(|x| x.borrow())
     }
@@ -109,6 +221,10 @@ impl LabelGenerator {
     pub fn get_current_label_block(&self) -> Option<&LoopBlock> {
         self.loop_blocks.last().map(|x| x.borrow())
     }
+
+    pub fn get_current_loop_block(&self) -> Option<&LoopBlock> {
+        self.loop_blocks.iter().rev().find(|block| !block.is_switch()).map(|x| x.borrow())
+    }
 }
 
 /// Represents a set of declaration dependencies
@@ -136,6 +252,24 @@ impl DeclDepencies {
     }
 }
 
+/// A target of a destructuring pattern
+///
+/// Binding patterns (``var {a} = o``) are parsed as ``Pat``, while assignment
+/// patterns (``({a} = o)``) are parsed as ``Expr``.
+#[derive(Clone, Copy)]
+enum PatternTarget<'a> {
+    Pat(&'a Pat),
+    Expr(&'a Expr),
+}
+
+/// Describes how the identifiers of a destructuring pattern are bound
+enum PatternBinding {
+    /// The identifiers are declared with the given type
+    Declaration(DeclarationType),
+    /// The identifiers and members already exist and are assigned to
+    Assignment,
+}
+
 /// Compiles JavaScript source code into bytecode.
 ///
 /// ```
@@ -154,7 +288,20 @@ pub struct BytecodeCompiler {
     functions: Vec<BytecodeFunction>,
     isa: InstructionSet,
     label_generator: LabelGenerator,
-    decl_dependencies: DeclDepencies
+    decl_dependencies: DeclDepencies,
+    funcs_counter: u32,
+    // Number of try blocks the currently compiled code is nested in
+    try_depth: usize,
+    // Finally blocks of the try statements the currently compiled code is nested in
+    finally_blocks: Vec<FinallyBlock>,
+    // The class member the currently compiled code belongs to
+    class_member: Option<ClassMember>,
+    // Whether the currently compiled code is the body of a generator function
+    is_generator: bool,
+    // Whether the currently compiled code is the body of an async function
+    is_async: bool,
+    // The static members of the classes of the compiled code
+    static_members: StaticMembers
 }
 
 // fn testy<'xzy>(s: &'xzy mut BytecodeCompiler<'xzy>, pp: &ProgramPart) -> BytecodeResult {
@@ -170,11 +317,18 @@ impl BytecodeCompiler {
         isa.common_lits().add_to_lit_cache(&mut scopes).unwrap();
 
         BytecodeCompiler{
-            scopes: scopes,
+            scopes,
             functions: vec![],
-            isa: isa,
+            isa,
             label_generator: LabelGenerator::new(),
-            decl_dependencies: DeclDepencies::new()
+            decl_dependencies: DeclDepencies::new(),
+            funcs_counter: 0,
+            try_depth: 0,
+            finally_blocks: vec![],
+            class_member: None,
+            is_generator: false,
+            is_async: false,
+            static_members: StaticMembers::default()
         }
     }
 
@@ -184,7 +338,7 @@ impl BytecodeCompiler {
     /// an external declaration. However, this is not necessary since dependencies are tracked
     /// and can be retrieved after the compilation through [decl_dependencies](struct.BytecodeCompiler.html#method.decl_dependencies).
     pub fn add_var_decl(&mut self, decl: String) ->  CompilerResult<Reg> {
-        self.scopes.add_decl(decl, DeclarationType::Variable(MyVariableKind::Var))
+        self.scopes.add_shared_decl(decl, DeclarationType::Variable(MyVariableKind::Var))
     }
 
     /// Returns all dependencies on external declarations
@@ -210,11 +364,17 @@ impl BytecodeCompiler {
     /// println!("bytecode: {}", bytecode);
     /// ```
     pub fn compile(&mut self, source: &JSSourceCode) -> BytecodeResult {
-        let ast = JSAst::parse(source)?;
-        let mut bytecode = match ast.ast {
+        let JSAst { ast, static_members } = JSAst::parse(source)?;
+        self.static_members = static_members;
+        let mut bytecode = match ast {
             resast::Program::Mod(_) => Err(CompilerError::are_unsupported("ES6 modules")),
             resast::Program::Script(s) => {
+                let needs_env = self.scopes.current_scope_mut()?.capture_decls(captured_identifiers(&[], &s));
+                let env_bc = self.compile_env_creation(needs_env)
+                                 .add_bytecode(self.compile_func_scope_decls(&s, &[])?);
+
                 s.iter().map(|part| self.compile_program_part(part)).collect::<BytecodeResult>()
+                    .map(|bytecode| env_bc.add_bytecode(bytecode))
             },
         }?;
 
@@ -236,41 +396,349 @@ impl BytecodeCompiler {
     }
 
     fn compile_decl(&mut self, decl: &Decl) -> BytecodeResult{
-        match decl {
+        let temp_regs_marker = self.scopes.temp_registers_marker()?;
+        let bytecode = match decl {
             Decl::Variable(var_kind, var_decls) => self.compile_var_decl(var_kind, var_decls),
-            Decl::Function(func) => self.compile_func(func),
-            Decl::Class(_) => Err(CompilerError::are_unsupported("Class declarations")),
+            // Function declarations are compiled when their scope is entered
+            Decl::Function(func) => self.compile_block_func_var_assignment(func),
+            Decl::Class(class) => self.compile_class_decl(class),
             Decl::Import(_) => Err(CompilerError::are_unsupported("Import declarations")),
             Decl::Export(_) => Err(CompilerError::are_unsupported("Export declarations")),
-        }
+        };
+        self.scopes.release_temp_registers(temp_regs_marker)?;
+
+        bytecode
+    }
+
+    fn compile_class_decl(&mut self, class: &Class) -> BytecodeResult {
+        let name = class.id.as_ref().ok_or(CompilerError::Custom("Class declarations require a name".into()))?;
+        let reg = self.declaration_reg(name, &DeclarationType::Variable(MyVariableKind::Let))?;
+        let class_bc = self.compile_class(class, None, reg)?;
+        let store_bc = self.compile_decl_store(name, reg)?;
+        self.scopes.initialize_decl(name)?;
+
+        Ok(class_bc.add_bytecode(store_bc))
     }
 
     fn compile_var_decl(&mut self, kind: &VariableKind, decls: &[VariableDecl]) -> BytecodeResult {
-        match kind {
-            VariableKind::Let => { println!("Warning: 'let' will be treated as 'var'", ); }
-            VariableKind::Const => { println!("Info: 'const' will be treated as 'var'"); }
-            _ => {}
-        }
+        let decl_type = DeclarationType::Variable(MyVariableKind::from(kind));
 
         decls.iter().map(|decl| {
             match &decl.id {
                 Pat::Identifier(ident) => {
-                    let reg = self.scopes.add_decl(ident.to_string(), DeclarationType::Variable(MyVariableKind::from(kind)))?;
-                    match &decl.init {
-                        Some(expr) => Ok(self.maybe_compile_expr(expr, Some(reg))?.0),
-                        None => Ok(Bytecode::new())
-                    }
+                    let reg = self.declaration_reg(ident, &decl_type)?;
+                    let init_bc = match &decl.init {
+                        Some(Expr::Class(class)) => self.compile_class(class, Some(ident), reg)?,
+                        Some(expr) => self.maybe_compile_expr(expr, Some(reg))?.0,
+                        // Unlike 'var', 'let' without initializer initializes the declaration
+                        None if decl_type.is_lexical() => self.compile_operand_assignment(reg,
+                                                              Operand::Reg(self.isa.common_literal_reg(&CommonLiteral::Void0)))?,
+                        None => { return Ok(Bytecode::new()); }
+                    };
+                    let store_bc = self.compile_decl_store(ident, reg)?;
+                    self.scopes.initialize_decl(ident)?;
+
+                    Ok(init_bc.add_bytecode(store_bc))
+                }
+                pat => {
+                    let (init_bc, value_reg) = match &decl.init {
+                        Some(expr) => self.compile_pattern_source(expr)?,
+                        None => (Bytecode::new(), self.isa.common_literal_reg(&CommonLiteral::Void0))
+                    };
+                    let binding = PatternBinding::Declaration(decl_type.clone());
+
+                    Ok(init_bc.add_bytecode(self.compile_pattern(PatternTarget::Pat(pat), value_reg, &binding)?))
                 }
-                Pat::Array(_) => Err(CompilerError::are_unsupported("'Array Patterns'")),
-                Pat::Object(_) => Err(CompilerError::are_unsupported("'Object Patterns'")),
-                Pat::RestElement(_) => Err(CompilerError::are_unsupported("'Rest Elements'")),
-                Pat::Assignment(_) => Err(CompilerError::are_unsupported("'Assignment Patterns'"))
             }
         }).collect()
     }
 
+    /// Compiles the value which is destructured into a new temporary register
+    ///
+    /// Destructuring never reads the register of a declaration directly, because the
+    /// pattern itself might assign to this declaration (``({x: o} = o)``).
+    fn compile_pattern_source(&mut self, expr: &Expr) -> CompilerResult<(Bytecode, Reg)> {
+        let value_reg = self.scopes.reserve_temp_register()?;
+        Ok((self.compile_expr(expr, value_reg)?, value_reg))
+    }
+
+    /// Binds the value in ``value_reg`` to the identifiers and members of the pattern ``target``
+    ///
+    /// Nested values are extracted into temporary registers, thus only a default value of
+    /// ``target`` itself is written to ``value_reg``.
+    fn compile_pattern(&mut self, target: PatternTarget, value_reg: Reg, binding: &PatternBinding) -> BytecodeResult {
+        match target {
+            PatternTarget::Pat(Pat::Identifier(ident)) |
+            PatternTarget::Expr(Expr::Ident(ident)) => self.compile_pattern_ident(ident, value_reg, binding),
+            PatternTarget::Expr(Expr::Member(member)) => match binding {
+                PatternBinding::Assignment => {
+                    let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
+                    Ok(member_bc.add(Operation::new(Instruction::PropertySet,
+                            vec![Operand::Reg(obj_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)])))
+                },
+                PatternBinding::Declaration(_) => Err(CompilerError::Custom("Members cannot be declared".into()))
+            },
+            PatternTarget::Pat(Pat::Assignment(assign)) => {
+                Ok(self.compile_pattern_default(value_reg, &assign.right)?
+                    .add_bytecode(self.compile_pattern(PatternTarget::Pat(&assign.left), value_reg, binding)?))
+            },
+            PatternTarget::Expr(Expr::Assignment(AssignmentExpr { operator: AssignmentOperator::Equal, left, right })) => {
+                let left = match left {
+                    AssignmentLeft::Pat(pat) => PatternTarget::Pat(pat),
+                    AssignmentLeft::Expr(expr) => PatternTarget::Expr(expr)
+                };
+
+                Ok(self.compile_pattern_default(value_reg, right)?
+                    .add_bytecode(self.compile_pattern(left, value_reg, binding)?))
+            },
+            PatternTarget::Pat(Pat::Array(parts)) => {
+                let elements: Vec<Option<PatternTarget>> = parts.iter().map(|part| part.as_ref().map(|part| match part {
+                    ArrayPatPart::Pat(pat) => PatternTarget::Pat(pat),
+                    ArrayPatPart::Expr(expr) => PatternTarget::Expr(expr)
+                })).collect();
+                self.compile_array_pattern(&elements, value_reg, binding)
+            },
+            PatternTarget::Expr(Expr::Array(exprs)) => {
+                let elements: Vec<Option<PatternTarget>> = exprs.iter().map(|expr| expr.as_ref().map(PatternTarget::Expr)).collect();
+                self.compile_array_pattern(&elements, value_reg, binding)
+            },
+            PatternTarget::Pat(Pat::Object(parts)) => {
+                let mut props = vec![];
+                let mut rest = None;
+                for part in parts.iter() {
+                    if rest.is_some() {
+                        return Err(CompilerError::Custom("A rest element must be the last element of a pattern".into()));
+                    }
+                    match part {
+                        ObjectPatPart::Assignment(prop) => props.push(prop),
+                        ObjectPatPart::Rest(pat) => rest = Some(match pat.borrow() {
+                            Pat::RestElement(rest_pat) => PatternTarget::Pat(rest_pat),
+                            rest_pat => PatternTarget::Pat(rest_pat)
+                        })
+                    }
+                }
+                self.compile_object_pattern(&props, rest, value_reg, binding)
+            },
+            PatternTarget::Expr(Expr::Object(obj_props)) => {
+                let mut props = vec![];
+                let mut rest = None;
+                for obj_prop in obj_props.iter() {
+                    if rest.is_some() {
+                        return Err(CompilerError::Custom("A rest element must be the last element of a pattern".into()));
+                    }
+                    match obj_prop {
+                        ObjectProperty::Property(prop) => props.push(prop),
+                        ObjectProperty::Spread(expr) => rest = Some(match expr.borrow() {
+                            Expr::Spread(rest_expr) => PatternTarget::Expr(rest_expr),
+                            rest_expr => PatternTarget::Expr(rest_expr)
+                        })
+                    }
+                }
+                self.compile_object_pattern(&props, rest, value_reg, binding)
+            },
+            PatternTarget::Pat(Pat::RestElement(_)) | PatternTarget::Expr(Expr::Spread(_)) =>
+                Err(CompilerError::Custom("A rest element must be the last element of a pattern".into())),
+            PatternTarget::Expr(_) => Err(CompilerError::are_unsupported("Assignment targets other than identifiers, members and patterns"))
+        }
+    }
+
+    fn compile_pattern_ident(&mut self, ident: &str, value_reg: Reg, binding: &PatternBinding) -> BytecodeResult {
+        match binding {
+            PatternBinding::Declaration(decl_type) => {
+                let reg = self.declaration_reg(ident, decl_type)?;
+                let bytecode = self.compile_operand_assignment(reg, Operand::Reg(value_reg))?
+                                   .add_bytecode(self.compile_decl_store(ident, reg)?);
+                self.scopes.initialize_decl(ident)?;
+                Ok(bytecode)
+            },
+            PatternBinding::Assignment if self.is_decl_in_register(ident) => {
+                let (check_bc, reg) = self.compile_assignment_target(ident)?;
+                Ok(check_bc.add_bytecode(self.compile_operand_assignment(reg, Operand::Reg(value_reg))?))
+            },
+            PatternBinding::Assignment => {
+                self.check_assignable(ident)?;
+                Ok(self.compile_initialized_check(ident)?.add_bytecode(self.compile_decl_store(ident, value_reg)?))
+            }
+        }
+    }
+
+    /// Returns the register of the declaration ``ident``, which is declared with the type ``decl_type``
+    ///
+    /// Lexical declarations were added when their scope was entered and ``var`` declarations when
+    /// their function was entered. All others are added now.
+    fn declaration_reg(&mut self, ident: &str, decl_type: &DeclarationType) -> CompilerResult<Reg> {
+        if decl_type.is_lexical() {
+            return Ok(self.scopes.get_var(ident)?.register);
+        }
+
+        match (decl_type, self.scopes.get_var(ident).map(|decl| (decl.register, decl.spill_slot))) {
+            // The temporary register of a hoisted, spilled declaration was released again
+            (DeclarationType::Variable(_), Ok((_, Some(_)))) => self.scopes.reserve_temp_register(),
+            (DeclarationType::Variable(_), Ok((reg, None))) => Ok(reg),
+            _ => self.scopes.add_decl(ident.to_string(), decl_type.clone())
+        }
+    }
+
+    /// Returns the register which an assignment to ``ident`` writes in place
+    ///
+    /// The declaration has to live in a register. A lexical declaration is checked to be initialized first.
+    fn compile_assignment_target(&mut self, ident: &str) -> CompilerResult<(Bytecode, Reg)> {
+        self.check_assignable(ident)?;
+
+        match self.scopes.get_var(ident).map(|decl| decl.register) {
+            Ok(reg) => Ok((self.compile_initialized_check(ident)?, reg)),
+            Err(_) => self.maybe_compile_expr(&Expr::Ident(ident.to_string()), None)
+        }
+    }
+
+    /// Checks that an assignment can change the value of ``ident``
+    fn check_assignable(&self, ident: &str) -> CompilerResult<()> {
+        match self.scopes.get_var(ident) {
+            Ok(decl) if decl.decl_type.is_const() =>
+                Err(CompilerError::Custom(format!("Assignment to the constant declaration '{}'", ident))),
+            _ => Ok(())
+        }
+    }
+
+    /// Checks that the lexical declaration ``ident`` is initialized, before it is written
+    fn compile_initialized_check(&mut self, ident: &str) -> BytecodeResult {
+        match self.scopes.get_var(ident) {
+            Ok(decl) if decl.tdz != Tdz::Passed => {
+                let tmp_reg = self.scopes.reserve_temp_register()?;
+                self.compile_identifier_expr(&ident.to_string(), tmp_reg)
+            },
+            _ => Ok(Bytecode::new())
+        }
+    }
+
+    /// Throws a ReferenceError if the lexical declaration ``decl``, whose value is in ``value_reg``, is not initialized
+    fn compile_tdz_check(&self, decl: &Declaration, value_reg: Reg) -> Bytecode {
+        let check_reg = match decl.tdz {
+            Tdz::Passed => { return Bytecode::new(); },
+            // The check of the uninitialized value itself always throws
+            Tdz::Definite => self.isa.reserved_reg(&ReservedeRegister::Uninitialized),
+            Tdz::Unknown => value_reg
+        };
+
+        Bytecode::new().add(Operation::new(Instruction::CheckInitialized, vec![Operand::Reg(check_reg)]))
+    }
+
+    /// Replaces an undefined value in ``value_reg`` by the value of ``default``
+    fn compile_pattern_default(&mut self, value_reg: Reg, default: &Expr) -> BytecodeResult {
+        let is_undefined_reg = self.scopes.reserve_temp_register()?;
+        let skip_default_label = self.label_generator.generate_label();
+
+        Ok(Bytecode::new()
+            .add(Operation::new(Instruction::CompStrictEqual, vec![Operand::Reg(is_undefined_reg), Operand::Reg(value_reg),
+                                                                   Operand::Reg(self.isa.common_literal_reg(&CommonLiteral::Void0))]))
+            .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(is_undefined_reg), Operand::branch_addr(skip_default_label)]))
+            .add_bytecode(self.compile_expr(default, value_reg)?)
+            .add_label(skip_default_label))
+    }
+
+    /// Destructures the values of the iterator of ``value_reg`` into ``elements``
+    ///
+    /// ``None`` elements are holes, which skip a value of the iterator.
+    fn compile_array_pattern(&mut self, elements: &[Option<PatternTarget>], value_reg: Reg, binding: &PatternBinding) -> BytecodeResult {
+        let iterator_reg = self.scopes.reserve_temp_register()?;
+        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+        let mut bytecode = Bytecode::new()
+            .add(Operation::new(Instruction::GetIterator, vec![Operand::Reg(iterator_reg), Operand::Reg(value_reg)]));
+
+        for (idx, element) in elements.iter().enumerate() {
+            let rest = match element {
+                Some(PatternTarget::Pat(Pat::RestElement(pat))) => Some(PatternTarget::Pat(pat)),
+                Some(PatternTarget::Expr(Expr::Spread(expr))) => Some(PatternTarget::Expr(expr)),
+                _ => None
+            };
+
+            bytecode = match (element, rest) {
+                (_, Some(rest)) => {
+                    if idx + 1 != elements.len() {
+                        return Err(CompilerError::Custom("A rest element must be the last element of a pattern".into()));
+                    }
+
+                    let rest_reg = self.scopes.reserve_temp_register()?;
+                    bytecode
+                        .add(Operation::new(Instruction::IteratorRest, vec![Operand::Reg(rest_reg), Operand::Reg(iterator_reg)]))
+                        .add_bytecode(self.compile_pattern(rest, rest_reg, binding)?)
+                },
+                (Some(target), None) => {
+                    // A finished iterator does not write the element, thus it is undefined beforehand
+                    let element_reg = self.scopes.reserve_temp_register()?;
+                    bytecode
+                        .add_bytecode(self.compile_operand_assignment(element_reg,
+                                Operand::Reg(self.isa.common_literal_reg(&CommonLiteral::Void0)))?)
+                        .add(Operation::new(Instruction::IteratorNext, vec![Operand::Reg(trash_reg), Operand::Reg(element_reg),
+                                                                           Operand::Reg(iterator_reg)]))
+                        .add_bytecode(self.compile_pattern(*target, element_reg, binding)?)
+                },
+                (None, None) => bytecode
+                    .add(Operation::new(Instruction::IteratorNext, vec![Operand::Reg(trash_reg), Operand::Reg(trash_reg),
+                                                                       Operand::Reg(iterator_reg)]))
+            };
+        }
+
+        Ok(bytecode)
+    }
+
+    /// Destructures the properties of ``value_reg`` into ``props``
+    ///
+    /// The ``rest`` element receives an object with all remaining own enumerable properties.
+    fn compile_object_pattern(&mut self, props: &[&Property], rest: Option<PatternTarget>,
+                              value_reg: Reg, binding: &PatternBinding) -> BytecodeResult {
+        let mut bytecode = Bytecode::new();
+        let mut key_regs = vec![];
+
+        for prop in props.iter() {
+            let (key_bc, key_reg) = match &prop.key {
+                // The rest element needs the key, even if a target reassigns the declaration it was computed from
+                PropertyKey::Expr(key_expr) if prop.computed && rest.is_some() => self.compile_pattern_source(key_expr)?,
+                key => self.compile_property_key(key, prop.computed)?
+            };
+
+            let (target, default) = match (&prop.value, prop.short_hand, &prop.key) {
+                (PropertyValue::Pat(pat), _, _) => (PatternTarget::Pat(pat), None),
+                (PropertyValue::None, true, PropertyKey::Expr(key_expr)) => (PatternTarget::Expr(key_expr), None),
+                // In assignment patterns, a shorthand property with a default is an assignment to the key
+                (PropertyValue::Expr(Expr::Assignment(assign)), true, PropertyKey::Expr(key_expr))
+                    if matches!(&assign.left, AssignmentLeft::Expr(left) if left.as_ref() == key_expr) =>
+                    (PatternTarget::Expr(key_expr), Some(assign.right.borrow())),
+                (PropertyValue::Expr(default), true, PropertyKey::Expr(key_expr)) => (PatternTarget::Expr(key_expr), Some(default)),
+                (PropertyValue::Expr(expr), false, _) => (PatternTarget::Expr(expr), None),
+                _ => { return Err(CompilerError::is_unsupported("Object pattern property", prop)); }
+            };
+
+            let prop_reg = self.scopes.reserve_temp_register()?;
+            let default_bc = match default {
+                Some(default) => self.compile_pattern_default(prop_reg, default)?,
+                None => Bytecode::new()
+            };
+
+            bytecode = bytecode
+                .add_bytecode(key_bc)
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(prop_reg), Operand::Reg(value_reg), Operand::Reg(key_reg)]))
+                .add_bytecode(default_bc)
+                .add_bytecode(self.compile_pattern(target, prop_reg, binding)?);
+            key_regs.push(key_reg);
+        }
+
+        match rest {
+            Some(rest) => {
+                let rest_reg = self.scopes.reserve_temp_register()?;
+                Ok(bytecode
+                    .add(Operation::new(Instruction::ObjectRest, vec![Operand::Reg(rest_reg), Operand::Reg(value_reg),
+                                                                     Operand::RegistersArray(key_regs)]))
+                    .add_bytecode(self.compile_pattern(rest, rest_reg, binding)?))
+            },
+            None => Ok(bytecode)
+        }
+    }
+
     fn compile_stmt(&mut self, stmt: &Stmt) -> BytecodeResult {
-        match stmt {
+        // Temporary values are not used beyond the statement computing them
+        let temp_regs_marker = self.scopes.temp_registers_marker()?;
+        let bytecode = match stmt {
             Stmt::Expr(expr) => self.compile_expr(&expr, self.isa.reserved_reg(&ReservedeRegister::TrashRegister)),
             Stmt::Block(block_stmt) => self.compile_block_stmt(block_stmt),
             Stmt::Empty => Ok(Bytecode::new()),
@@ -281,30 +749,202 @@ impl BytecodeCompiler {
             Stmt::Break(break_stmt) => self.compile_break_stmt(break_stmt),
             Stmt::Continue(continue_stmt) => self.compile_continue_stmt(continue_stmt),
             Stmt::If(if_stmt) => self.compile_if_stmt(if_stmt),
-            Stmt::Switch(_) => Err(CompilerError::are_unsupported("'switch' statements")),
+            Stmt::Switch(switch_stmt) => self.compile_switch_stmt(switch_stmt),
             Stmt::Throw(throw_expr) => self.compile_throw_stmt(throw_expr),
             Stmt::Try(try_stmt) => self.compile_try_stmt(try_stmt),
             Stmt::While(while_stmt) => self.compile_while_stmt(while_stmt),
             Stmt::DoWhile(dowhile_stmt) => self.compile_dowhile_stmt(dowhile_stmt),
             Stmt::For(for_stmt) => self.compile_for_stmt(for_stmt),
-            Stmt::ForIn(_) => Err(CompilerError::are_unsupported("for-in statements")),
-            Stmt::ForOf(_) => Err(CompilerError::are_unsupported("for-of statements")),
+            Stmt::ForIn(for_in_stmt) => self.compile_for_in_stmt(for_in_stmt),
+            Stmt::ForOf(for_of_stmt) => self.compile_for_of_stmt(for_of_stmt),
             Stmt::Var(decls) => self.compile_var_decl(&VariableKind::Var, &decls),
-        }
+        };
+        self.scopes.release_temp_registers(temp_regs_marker)?;
+
+        bytecode
     }
 
     fn compile_block_stmt(&mut self, block_stmt: &BlockStmt) -> BytecodeResult {
         self.scopes.enter_new_block_scope()?;
-        let maybe_bc = block_stmt.iter().map(|part| self.compile_program_part(part)).collect();
+        let (entry_bc, has_env) = self.compile_lexical_scope_entry(&lexical_declarations(block_stmt.iter()))?;
+        let maybe_bc = self.compile_hoisted_funcs(block_stmt.iter()).and_then(|funcs_bc| {
+            block_stmt.iter().map(|part| self.compile_program_part(part)).collect::<BytecodeResult>()
+                .map(|bytecode| funcs_bc.add_bytecode(bytecode))
+        });
         self.scopes.leave_current_block_scope()?;
 
-        maybe_bc
+        Ok(entry_bc
+            .add_bytecode(maybe_bc?)
+            .add_bytecode(self.compile_leave_envs(has_env as u8)))
     }
 
-    fn compile_return_stmt(&mut self, ret: &Option<Expr>) -> BytecodeResult {
-        let used_decl_regs: Vec<Reg> = self.scopes.current_scope()?.used_decls.iter()
-                                            .map(|used_decl| used_decl.register).collect();
+    /// Enters a scope of lexical declarations, i.e. a block, a loop head, a catch clause or a switch statement
+    ///
+    /// If closures capture any of the declarations, the scope gets its own environment.
+    /// # Returns
+    /// The bytecode entering the scope and whether it created an environment, which has to be left again.
+    fn compile_lexical_scope_entry(&mut self, decls: &[(String, MyVariableKind)]) -> CompilerResult<(Bytecode, bool)> {
+        let scope = self.scopes.current_scope()?;
+        let needs_env = decls.iter().any(|(name, _)| scope.is_captured(name));
+        if needs_env {
+            self.scopes.create_block_env()?;
+        }
+
+        Ok((self.compile_env_creation(needs_env).add_bytecode(self.compile_lexical_decls(decls)?), needs_env))
+    }
+
+    /// Adds the ``let`` and ``const`` declarations of the current scope before any of its code is compiled
+    ///
+    /// Declarations whose initialization is checked at runtime start with the uninitialized value.
+    fn compile_lexical_decls(&mut self, decls: &[(String, MyVariableKind)]) -> BytecodeResult {
+        let uninitialized_reg = self.isa.reserved_reg(&ReservedeRegister::Uninitialized);
+
+        decls.iter().map(|(name, kind)| {
+            // Spilled declarations only need a temporary register while a value is assigned to them
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let reg = self.scopes.add_decl(name.to_string(), DeclarationType::Variable(kind.clone()))?;
+            let (tdz, is_in_register) = self.scopes.get_var(name).map(|decl| (decl.tdz, decl.is_in_register()))?;
+
+            let bytecode = match (tdz, is_in_register) {
+                (Tdz::Unknown, true) => self.compile_operand_assignment(reg, Operand::Reg(uninitialized_reg)),
+                (Tdz::Unknown, false) => self.compile_decl_store(name, uninitialized_reg),
+                _ => Ok(Bytecode::new())
+            }?;
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+            Ok(bytecode)
+        }).collect()
+    }
+
+    /// Adds the declarations of a function body or a script before any of its code is compiled
+    ///
+    /// Functions declared in blocks are also assigned to a ``var`` declaration of the whole function,
+    /// unless a parameter or a lexical declaration of the body has the same name.
+    /// # Returns
+    /// The bytecode initializing the declarations.
+    fn compile_func_scope_decls(&mut self, body: &[ProgramPart], params: &[FunctionArg]) -> BytecodeResult {
+        let param_idents = parameter_identifiers(params);
+        let lexical_decls = lexical_declarations(body.iter());
+        let block_funcs: Vec<String> = block_function_declarations(body.iter()).into_iter()
+            .filter(|name| !param_idents.contains(name) && !lexical_decls.iter().any(|(decl, _)| decl == name))
+            .collect();
+
+        self.hoist_var_decls(body, &param_idents, &block_funcs)?;
+        let bytecode = self.compile_lexical_decls(&lexical_decls)?
+                           .add_bytecode(self.compile_hoisted_funcs(body.iter())?);
+        block_funcs.iter().try_for_each(|name| self.scopes.add_block_func_var(name))?;
+
+        Ok(bytecode)
+    }
+
+    /// Adds the ``var`` declarations of a function body or a script
+    ///
+    /// Thus, they can be accessed before their definition and hold ``undefined`` until then.
+    /// Declarations which are also parameters keep the value of their argument. Declarations of
+    /// homonymous functions are added with the functions.
+    fn hoist_var_decls(&mut self, body: &[ProgramPart], param_idents: &[String], block_funcs: &[String]) -> CompilerResult<()> {
+        let is_func_decl = |name: &String| body.iter().any(|part| matches!(part,
+            ProgramPart::Decl(Decl::Function(Function { id: Some(id), .. })) if id == name));
+        let mut names = var_declarations(body.iter());
+        names.extend(block_funcs.iter().filter(|&name| !names.contains(name)).cloned().collect::<Vec<String>>());
+
+        names.into_iter()
+            .filter(|name| !param_idents.contains(name) && !is_func_decl(name))
+            .try_for_each(|name| {
+                // Spilled declarations only need a temporary register while a value is assigned to them
+                let temp_regs_marker = self.scopes.temp_registers_marker()?;
+                self.scopes.add_var_decl(name)?;
+                self.scopes.release_temp_registers(temp_regs_marker)
+            })
+    }
+
+    /// Compiles the function declarations of a scope before any of its other code
+    ///
+    /// All functions are declared before the first one is compiled, thus they can call each other
+    /// regardless of the order of their definitions. Functions which are used as values are
+    /// bound to their declaration once, when the scope is entered. Calls jump to the bytecode
+    /// functions directly.
+    /// # Returns
+    /// The bytecode binding the functions to their declarations.
+    fn compile_hoisted_funcs<'a, I>(&mut self, parts: I) -> BytecodeResult
+        where I: IntoIterator<Item = &'a ProgramPart>
+    {
+        let parts: Vec<&ProgramPart> = parts.into_iter().collect();
+        let funcs: Vec<&Function> = parts.iter().filter_map(|part| match part {
+            ProgramPart::Decl(Decl::Function(func)) => Some(func),
+            _ => None
+        }).collect();
+        if funcs.is_empty() {
+            return Ok(Bytecode::new());
+        }
+
+        let values = value_references(parts.iter().cloned());
+        let assigned = assigned_identifiers(parts.iter().cloned());
+        let var_names = var_declarations(parts.iter().cloned());
+        let mut bytecode = Bytecode::new();
+        let mut func_indices = vec![];
+        for func in funcs.iter() {
+            let name = func.id.as_ref().ok_or(CompilerError::are_unsupported("anonymous functions"))?;
+            let func_ident = self.generate_func_decl_ident(name);
+            func_indices.push(self.declare_bytecode_func(func_ident.clone(), (*func).into(), FunctionKind::Function)?);
+
+            // A function declared in a block is only assigned to the var declaration of its function, if it is visible
+            let has_block_func_var = match self.scopes.get_block_func_var(name) {
+                Some(var_decl) if self.scopes.get_var(name).ok() == Some(var_decl) => true,
+                Some(_) => { self.scopes.remove_block_func_var(name)?; false },
+                None => false
+            };
+
+            // Generator and async functions are called through their value
+            if values.contains(name) || assigned.contains(name) || var_names.contains(name) ||
+               has_block_func_var || func.generator || func.is_async {
+                bytecode = bytecode.add_bytecode(self.compile_func_decl_binding(name, &func_ident)?);
+            }
+            // Calls of reassigned functions use the current value of their declaration
+            if !assigned.contains(name) {
+                self.scopes.add_func_decl(name.to_string(), func_ident)?;
+            }
+        }
+
+        funcs.into_iter().zip(func_indices).try_for_each(|(func, func_idx)| self.compile_declared_bytecode_func(func_idx, func.into(), false))?;
+        Ok(bytecode)
+    }
+
+    /// Assigns the function ``func``, which is declared in a block, to the ``var`` declaration of its function
+    ///
+    /// As in sloppy mode, the function is visible outside of the block once its declaration was evaluated.
+    fn compile_block_func_var_assignment(&mut self, func: &Function) -> BytecodeResult {
+        let name = match &func.id {
+            Some(name) => name,
+            None => { return Ok(Bytecode::new()); }
+        };
+        let var_decl = match self.scopes.get_block_func_var(name) {
+            // A function declared in the function body itself is bound to the var declaration already
+            Some(var_decl) if self.scopes.get_var(name).ok() != Some(var_decl) => var_decl.clone(),
+            _ => { return Ok(Bytecode::new()); }
+        };
+
+        let (load_bc, func_reg) = self.maybe_compile_expr(&Expr::Ident(name.to_string()), None)?;
+        let store_bc = match (var_decl.env_slot, var_decl.spill_slot) {
+            (None, None) => self.compile_operand_assignment(var_decl.register, Operand::Reg(func_reg))?,
+            (env_slot, spill_slot) => self.compile_slot_store(env_slot, spill_slot, func_reg)?
+        };
+
+        Ok(load_bc.add_bytecode(store_bc))
+    }
+
+    /// Adds the declaration ``name`` and assigns the bytecode function ``func_ident`` to it
+    fn compile_func_decl_binding(&mut self, name: &str, func_ident: &str) -> BytecodeResult {
+        // Spilled declarations only need a temporary register while the function is assigned to them
+        let temp_regs_marker = self.scopes.temp_registers_marker()?;
+        let reg = self.scopes.add_decl(name.to_string(), DeclarationType::Variable(MyVariableKind::Var))?;
+        let bytecode = self.compile_bytecode_func_callback(func_ident, reg)?
+                           .add_bytecode(self.compile_decl_store(name, reg)?);
+        self.scopes.release_temp_registers(temp_regs_marker)?;
 
+        Ok(bytecode)
+    }
+
+    fn compile_return_stmt(&mut self, ret: &Option<Expr>) -> BytecodeResult {
         let (bytecode, ret_reg) = match ret {
             Some(ret_expr) => {
                 let (bytecode, ret_reg) = self.maybe_compile_expr(ret_expr, None)?;
@@ -313,10 +953,26 @@ impl BytecodeCompiler {
             None => (Bytecode::new(), self.isa.common_literal_reg(&CommonLiteral::Void0))
         };
 
-        Ok(bytecode
-            .add(Operation::new(Instruction::ReturnBytecodeFunc,
-                                vec![Operand::Reg(ret_reg), Operand::RegistersArray(used_decl_regs)]))
-        )
+        Ok(bytecode.add_bytecode(self.compile_return(ret_reg)?))
+    }
+
+    /// Returns the value of ``ret_reg``, after running the finally blocks the return leaves
+    fn compile_return(&mut self, ret_reg: Reg) -> BytecodeResult {
+        if let Some(finally) = self.finally_blocks.last_mut() {
+            finally.returns = true;
+            let value_reg = finally.value_reg;
+
+            return Ok(self.compile_operand_assignment(value_reg, Operand::Reg(ret_reg))?
+                .add_bytecode(self.compile_enter_finally(COMPLETION_RETURN)?));
+        }
+
+        // Constructors return their instance, unless they return another object
+        let instruction = match self.class_member {
+            Some(ClassMember::Constructor { .. }) => Instruction::ReturnConstructor,
+            _ => Instruction::ReturnBytecodeFunc
+        };
+
+        Ok(Bytecode::new().add(Operation::new(instruction, vec![Operand::Reg(ret_reg)])))
     }
 
     fn compile_label_stmt(&mut self, labeled: &LabeledStmt) -> BytecodeResult {
@@ -325,17 +981,24 @@ impl BytecodeCompiler {
         self.compile_stmt(labeled.body.borrow())
     }
 
-    fn try_get_block_with_maybe_js_label(&self, js_label: &Option<Identifier>) -> CompilerResult<&LoopBlock> {
+    fn try_get_block_with_maybe_js_label(&self, js_label: &Option<Identifier>, is_continue: bool) -> CompilerResult<&LoopBlock> {
         match js_label {
             Some(label) => {
-                if let Some(loop_block) = self.label_generator.get_js_labled_block(label) {
-                    Ok(loop_block)
-                } else {
-                    Err(CompilerError::Custom("Used to unknown label".into()))
+                match self.label_generator.get_js_labled_block(label) {
+                    Some(loop_block) if is_continue && loop_block.is_switch() =>
+                        Err(CompilerError::Custom("Used continue with the label of a switch statement".into())),
+                    Some(loop_block) => Ok(loop_block),
+                    None => Err(CompilerError::Custom("Used to unknown label".into()))
                 }
             },
             None => {
-                if let Some(loop_block) = self.label_generator.get_current_label_block() {
+                let maybe_block = if is_continue {
+                    self.label_generator.get_current_loop_block()
+                } else {
+                    self.label_generator.get_current_label_block()
+                };
+
+                if let Some(loop_block) = maybe_block {
                     Ok(loop_block)
                 } else {
                     Err(CompilerError::Custom("Used break/continue while not in a loop-block".into()))
@@ -345,17 +1008,74 @@ impl BytecodeCompiler {
     }
 
     fn compile_break_stmt(&mut self, break_stmt: &Option<Identifier>) -> BytecodeResult {
-        let maybe_block = self.try_get_block_with_maybe_js_label(break_stmt);
+        let block = self.try_get_block_with_maybe_js_label(break_stmt, false)?;
+        let target = block.jump_target(block.end_label());
 
-        maybe_block.map(|block| Bytecode::new().add(Operation::new(Instruction::Jump, vec![
-            Operand::branch_addr(block.end_label())])))
+        self.compile_jump(target)
     }
 
     fn compile_continue_stmt(&mut self, continue_stmt: &Option<Identifier>) -> BytecodeResult {
-        let maybe_block = self.try_get_block_with_maybe_js_label(continue_stmt);
+        let block = self.try_get_block_with_maybe_js_label(continue_stmt, true)?;
+        let target = block.jump_target(block.start_label());
+
+        self.compile_jump(target)
+    }
+
+    /// Jumps to ``target``, leaving the try blocks and environments in between
+    ///
+    /// A jump out of a try statement with a finally block continues in this finally block.
+    fn compile_jump(&mut self, target: JumpTarget) -> BytecodeResult {
+        if self.finally_blocks.len() > target.finally_depth {
+            let finally = self.finally_blocks.last_mut().unwrap();
+            let jump_idx = match finally.jumps.iter().position(|&jump| jump == target) {
+                Some(jump_idx) => jump_idx,
+                None => {
+                    finally.jumps.push(target);
+                    finally.jumps.len() - 1
+                }
+            };
+            if jump_idx >= usize::from(u8::MAX - COMPLETION_FIRST_JUMP) {
+                return Err(CompilerError::Custom("Too many jumps leave a try statement".into()));
+            }
+
+            return self.compile_enter_finally(COMPLETION_FIRST_JUMP + jump_idx as u8);
+        }
+
+        let env_depth = self.scopes.current_scope()?.env_depth();
+        Ok(Self::compile_leave_try_blocks(self.try_depth - target.try_depth)
+            .add_bytecode(self.compile_leave_envs(env_depth - target.env_depth))
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(target.label)])))
+    }
+
+    /// Leaves the try block of the innermost finally block with the completion ``kind``
+    fn compile_enter_finally(&self, kind: u8) -> BytecodeResult {
+        let finally = self.finally_blocks.last().ok_or(CompilerError::Custom("No finally block".into()))?;
+        let env_depth = self.scopes.current_scope()?.env_depth();
+
+        Ok(self.compile_operand_assignment(finally.kind_reg, Operand::ShortNum(kind))?
+            .add_bytecode(Self::compile_leave_try_blocks(self.try_depth - finally.try_depth))
+            .add_bytecode(self.compile_leave_envs(env_depth - finally.env_depth))
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(finally.label)])))
+    }
 
-        maybe_block.map(|block| Bytecode::new().add(Operation::new(Instruction::Jump, vec![
-            Operand::branch_addr(block.start_label())])))
+    /// Removes the exception handlers of try blocks which are left by a jump
+    fn compile_leave_try_blocks(count: usize) -> Bytecode {
+        (0..count).fold(Bytecode::new(), |bc, _| bc.add(Operation::new(Instruction::EndTry, vec![])))
+    }
+
+    /// Restores the environment which was current before ``count`` blocks created their own environments
+    fn compile_leave_envs(&self, count: u8) -> Bytecode {
+        let env_reg = self.isa.reserved_reg(&ReservedeRegister::Environment);
+        let num0_reg = self.isa.common_literal_reg(&CommonLiteral::Num0);
+
+        // The first slot of an environment holds its parent
+        (0..count).fold(Bytecode::new(), |bc, _| bc.add(Operation::new(Instruction::PropAccess,
+                vec![Operand::Reg(env_reg), Operand::Reg(env_reg), Operand::Reg(num0_reg)])))
+    }
+
+    fn generate_loop_label_block(&mut self, is_switch: bool) -> CompilerResult<Rc<LoopBlock>> {
+        let env_depth = self.scopes.current_scope()?.env_depth();
+        Ok(self.label_generator.generate_loop_label_block(self.try_depth, env_depth, self.finally_blocks.len(), is_switch))
     }
 
     fn compile_if_stmt(&mut self, if_stmt: &IfStmt) -> BytecodeResult {
@@ -386,6 +1106,105 @@ impl BytecodeCompiler {
         }
     }
 
+    fn compile_switch_stmt(&mut self, switch_stmt: &SwitchStmt) -> BytecodeResult {
+        let (discriminant_bc, discriminant_reg) = self.maybe_compile_expr(&switch_stmt.discriminant, None)?;
+
+        // Cases share one block scope and fall through to the next case. The case tests are already part of it.
+        self.scopes.enter_new_switch_scope()?;
+        let decls = lexical_declarations(switch_stmt.cases.iter().flat_map(|case| case.consequent.iter()));
+        let (mut entry_bc, has_env) = self.compile_lexical_scope_entry(&decls)?;
+        entry_bc = entry_bc.add_bytecode(self.compile_hoisted_funcs(switch_stmt.cases.iter().flat_map(|case| case.consequent.iter()))?);
+
+        let switch_block = self.generate_loop_label_block(true)?;
+        let switch_end_label = switch_block.end_label();
+        let case_labels: Vec<Label> = switch_stmt.cases.iter().map(|_| self.label_generator.generate_label()).collect();
+        // If no case matches, the default case is executed or the switch statement is left
+        let default_label = switch_stmt.cases.iter().position(|case| case.test.is_none())
+                                .map_or(switch_end_label, |idx| case_labels[idx]);
+
+        let dispatch_bc = match Self::compile_switch_jump_table(discriminant_reg, &switch_stmt.cases, &case_labels, default_label) {
+            Some(jump_table_bc) => jump_table_bc,
+            None => self.compile_switch_case_tests(discriminant_reg, &switch_stmt.cases, &case_labels)?
+        };
+
+        let maybe_cases_bc: BytecodeResult = switch_stmt.cases.iter().zip(case_labels.iter())
+            .map(|(case, &case_label)| -> BytecodeResult {
+                let consequent_bc: Bytecode = case.consequent.iter()
+                    .map(|part| self.compile_program_part(part)).collect::<BytecodeResult>()?;
+
+                Ok(Bytecode::new().add_label(case_label).add_bytecode(consequent_bc))
+            }).collect();
+        self.scopes.leave_current_block_scope()?;
+        self.label_generator.leave_loop_label_block();
+
+        Ok(discriminant_bc
+            .add_bytecode(entry_bc)
+            .add_bytecode(dispatch_bc)
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(default_label)]))
+            .add_bytecode(maybe_cases_bc?)
+            .add_label(switch_end_label)
+            .add_bytecode(self.compile_leave_envs(has_env as u8)))
+    }
+
+    fn compile_switch_case_tests(&mut self, discriminant_reg: Reg, cases: &[SwitchCase], case_labels: &[Label]) -> BytecodeResult {
+        let cmp_reg = self.scopes.reserve_temp_register()?;
+
+        cases.iter().zip(case_labels.iter()).filter_map(|(case, &case_label)| {
+            case.test.as_ref().map(|test| (test, case_label))
+        }).map(|(test, case_label)| -> BytecodeResult {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let (test_bc, test_reg) = self.maybe_compile_expr(test, None)?;
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+
+            Ok(test_bc
+                .add(Operation::new(Instruction::CompStrictEqual, vec![Operand::Reg(cmp_reg),
+                                                                      Operand::Reg(discriminant_reg),
+                                                                      Operand::Reg(test_reg)]))
+                .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(cmp_reg), Operand::branch_addr(case_label)])))
+        }).collect()
+    }
+
+    /// Compiles the case tests into a jump table if all of them are dense integer literals
+    fn compile_switch_jump_table(discriminant_reg: Reg, cases: &[SwitchCase], case_labels: &[Label],
+                                 default_label: Label) -> Option<Bytecode> {
+        let mut int_cases: Vec<(i64, Label)> = vec![];
+
+        for (case, &case_label) in cases.iter().zip(case_labels.iter()) {
+            match &case.test {
+                Some(Expr::Literal(lit @ Literal::Number(_))) => match BytecodeLiteral::from_lit(lit.clone()) {
+                    Ok(BytecodeLiteral::IntNumber(int)) => int_cases.push((int, case_label)),
+                    _ => return None
+                },
+                Some(_) => return None,
+                None => {}
+            }
+        }
+
+        let min = int_cases.iter().map(|&(int, _)| int).min()?;
+        let max = int_cases.iter().map(|&(int, _)| int).max()?;
+        let table_size = (max - min + 1) as usize;
+
+        if int_cases.len() < JUMP_TABLE_MIN_CASES || table_size > 2 * int_cases.len()
+            || table_size > u8::MAX as usize || max > i32::MAX.into() {
+            return None;
+        }
+
+        // Values without a case jump to the default case
+        let mut table = vec![None; table_size];
+        for &(int, case_label) in int_cases.iter() {
+            let entry = &mut table[(int - min) as usize];
+            // The first matching case is taken
+            if entry.is_none() {
+                *entry = Some(case_label);
+            }
+        }
+
+        let mut operands = vec![Operand::Reg(discriminant_reg), Operand::LongNum(min as i32), Operand::ShortNum(table_size as u8)];
+        operands.extend(table.into_iter().map(|entry| Operand::branch_addr(entry.unwrap_or(default_label))));
+
+        Some(Bytecode::new().add(Operation::new(Instruction::JumpTable, operands)))
+    }
+
     fn compile_throw_stmt(&mut self, throw_expr: &Expr) -> BytecodeResult {
         let (bc, reg) = self.maybe_compile_expr(throw_expr, None)?;
 
@@ -393,91 +1212,202 @@ impl BytecodeCompiler {
     }
 
     fn compile_try_stmt(&mut self, try_stmt: &TryStmt) -> BytecodeResult {
-        let try_block_bc = self.compile_block_stmt(&try_stmt.block)?;
-        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
-        let (catch_block_bc, catch_reg) = try_stmt.handler.as_ref()
-                                            .map(|h| self.compile_catch_clause(&h))
-                                            .unwrap_or_else(|| Ok((Bytecode::new(), trash_reg)))?;
-        let final_block_bc = try_stmt.finalizer.as_ref()
-                                            .map(|b| self.compile_block_stmt(&b))
-                                            .unwrap_or_else(|| Ok(Bytecode::new()))?;
+        match (&try_stmt.handler, &try_stmt.finalizer) {
+            (Some(handler), None) => self.compile_try_catch(&|compiler| compiler.compile_block_stmt(&try_stmt.block),
+                                                            &|compiler| compiler.compile_catch_clause(handler)),
+            (None, Some(finalizer)) => self.compile_try_finally(&|compiler| compiler.compile_block_stmt(&try_stmt.block),
+                                                                &|compiler, _| compiler.compile_block_stmt(finalizer)),
+            (Some(handler), Some(finalizer)) => self.compile_try_finally(&|compiler| {
+                compiler.compile_try_catch(&|compiler| compiler.compile_block_stmt(&try_stmt.block),
+                                           &|compiler| compiler.compile_catch_clause(handler))
+            }, &|compiler, _| compiler.compile_block_stmt(finalizer)),
+            (None, None) => {
+                let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+                self.compile_try_catch(&|compiler| compiler.compile_block_stmt(&try_stmt.block),
+                                       &|_| Ok((Bytecode::new(), trash_reg)))
+            }
+        }
+    }
 
-        let catch_block_label = self.label_generator.generate_label();
-        let finally_start_label = self.label_generator.generate_label();
+    /// Compiles a try block with an exception handler
+    ///
+    /// While the try block runs, an exception jumps to the catch block and writes the exception to its register.
+    fn compile_try_catch(&mut self, compile_try_block: &dyn Fn(&mut Self) -> BytecodeResult,
+                         compile_catch_block: &dyn Fn(&mut Self) -> CompilerResult<(Bytecode, Register)>) -> BytecodeResult {
+        self.compile_try_handler(Instruction::Try, compile_try_block, compile_catch_block)
+    }
+
+    /// Compiles a try block whose exception handler is installed by ``try_instruction``
+    ///
+    /// Handlers installed by 'TryFinally' are also run when a suspended generator is returned.
+    fn compile_try_handler(&mut self, try_instruction: Instruction, compile_try_block: &dyn Fn(&mut Self) -> BytecodeResult,
+                           compile_catch_block: &dyn Fn(&mut Self) -> CompilerResult<(Bytecode, Register)>) -> BytecodeResult {
+        self.try_depth += 1;
+        let try_block_bc = compile_try_block(self);
+        self.try_depth -= 1;
+        let try_block_bc = try_block_bc?;
+        let (catch_block_bc, catch_reg) = compile_catch_block(self)?;
 
-        let stop_prog_flow_op = Operation::new(Instruction::LoadLongNum, vec![
-            Operand::Reg(self.isa.reserved_reg(&ReservedeRegister::BytecodePointer)),
-            Operand::BytecodeEnd]);
+        let catch_block_label = self.label_generator.generate_label();
+        let try_end_label = self.label_generator.generate_label();
 
         Ok(Bytecode::new()
-            .add(Operation::new(Instruction::Try, vec![
+            .add(Operation::new(try_instruction, vec![
                 Operand::Reg(catch_reg),
                 Operand::branch_addr(catch_block_label),
-                Operand::branch_addr(finally_start_label),
             ]))
             .add_bytecode(try_block_bc)
-            .add(stop_prog_flow_op.clone())
+            .add(Operation::new(Instruction::EndTry, vec![]))
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(try_end_label)]))
             .add_label(catch_block_label)
             .add_bytecode(catch_block_bc)
-            .add(stop_prog_flow_op.clone())
-            .add_label(finally_start_label)
-            .add_bytecode(final_block_bc)
-            .add(stop_prog_flow_op)
+            .add_label(try_end_label)
         )
     }
 
+    /// Compiles a try block whose finally block runs however the try block is left
+    ///
+    /// Exceptions, return, break and continue statements leaving the try block jump to the
+    /// finally block, which continues them afterwards. A completion of the finally block itself,
+    /// e.g. a return statement, replaces them. The finally block gets the register of the completion kind.
+    fn compile_try_finally(&mut self, compile_try_block: &dyn Fn(&mut Self) -> BytecodeResult,
+                           compile_finalizer: &dyn Fn(&mut Self, Reg) -> BytecodeResult) -> BytecodeResult {
+        let kind_reg = self.scopes.reserve_temp_register()?;
+        let value_reg = self.scopes.reserve_temp_register()?;
+        let finally_label = self.label_generator.generate_label();
+
+        self.finally_blocks.push(FinallyBlock {
+            label: finally_label,
+            kind_reg,
+            value_reg,
+            try_depth: self.try_depth,
+            env_depth: self.scopes.current_scope()?.env_depth(),
+            returns: false,
+            jumps: vec![]
+        });
+        let try_catch_bc = self.compile_try_handler(Instruction::TryFinally, compile_try_block, &|compiler| {
+            Ok((compiler.compile_operand_assignment(kind_reg, Operand::ShortNum(COMPLETION_THROW))?, value_reg))
+        });
+        let finally = self.finally_blocks.pop().unwrap();
+
+        Ok(self.compile_operand_assignment(kind_reg, Operand::ShortNum(COMPLETION_NORMAL))?
+            .add_bytecode(try_catch_bc?)
+            .add_label(finally_label)
+            .add_bytecode(compile_finalizer(self, kind_reg)?)
+            .add_bytecode(self.compile_finally_dispatch(&finally)?))
+    }
+
+    /// Continues the completion which entered ``finally``, after the finally block ran
+    fn compile_finally_dispatch(&mut self, finally: &FinallyBlock) -> BytecodeResult {
+        let end_label = self.label_generator.generate_label();
+        let throw_label = self.label_generator.generate_label();
+        let return_label = if finally.returns { self.label_generator.generate_label() } else { end_label };
+        let jump_labels: Vec<Label> = finally.jumps.iter().map(|_| self.label_generator.generate_label()).collect();
+
+        // Normal completions are not part of the table and continue after the try statement
+        let mut operands = vec![Operand::Reg(finally.kind_reg), Operand::LongNum(i32::from(COMPLETION_THROW)),
+                                Operand::ShortNum(2 + jump_labels.len() as u8),
+                                Operand::branch_addr(throw_label), Operand::branch_addr(return_label)];
+        operands.extend(jump_labels.iter().map(|&label| Operand::branch_addr(label)));
+
+        let mut bytecode = Bytecode::new()
+            .add(Operation::new(Instruction::JumpTable, operands))
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(end_label)]))
+            .add_label(throw_label)
+            .add(Operation::new(Instruction::Throw, vec![Operand::Reg(finally.value_reg)]));
+        if finally.returns {
+            bytecode = bytecode.add_label(return_label).add_bytecode(self.compile_return(finally.value_reg)?);
+        }
+        for (&target, &label) in finally.jumps.iter().zip(jump_labels.iter()) {
+            bytecode = bytecode.add_label(label).add_bytecode(self.compile_jump(target)?);
+        }
+
+        Ok(bytecode.add_label(end_label))
+    }
+
     fn compile_catch_clause(&mut self, catch_clause: &CatchClause) -> CompilerResult<(Bytecode, Register)> {
-        self.scopes.enter_new_scope()?;
+        self.scopes.enter_new_block_scope()?;
+
+        let decls: Vec<(String, MyVariableKind)> = catch_clause.param.iter().flat_map(bound_identifiers)
+                                                              .map(|name| (name, MyVariableKind::Let)).collect();
+        let (entry_bc, has_env) = self.compile_lexical_scope_entry(&decls)?;
 
-        let reg = if let Some(param) = &catch_clause.param {
+        let (param_bc, reg) = if let Some(param) = &catch_clause.param {
+            let decl_type = DeclarationType::Variable(MyVariableKind::Let);
             if let Pat::Identifier(ident) = param {
-                self.scopes.add_decl(ident.to_string(),
-                        DeclarationType::Variable(MyVariableKind::from(&VariableKind::Let)))?
+                let reg = self.declaration_reg(ident, &decl_type)?;
+                let store_bc = self.compile_decl_store(ident, reg)?;
+                self.scopes.initialize_decl(ident)?;
+                (store_bc, reg)
             } else {
-                return Err(CompilerError::are_unsupported("Catch patterns other than an identifier".into()));
+                let exception_reg = self.scopes.reserve_temp_register()?;
+                (self.compile_pattern(PatternTarget::Pat(param), exception_reg, &PatternBinding::Declaration(decl_type))?,
+                 exception_reg)
             }
         } else {
-            self.isa.reserved_reg(&ReservedeRegister::TrashRegister)
+            (Bytecode::new(), self.isa.reserved_reg(&ReservedeRegister::TrashRegister))
         };
 
         let body_bc = self.compile_block_stmt(&catch_clause.body)?;
 
-        self.scopes.leave_current_scope()?;
+        self.scopes.leave_current_block_scope()?;
 
-        Ok((body_bc, reg))
+        Ok((entry_bc
+                .add_bytecode(param_bc)
+                .add_bytecode(body_bc)
+                .add_bytecode(self.compile_leave_envs(has_env as u8)),
+            reg))
     }
 
     fn compile_while_stmt(&mut self, while_stmt: &WhileStmt) -> BytecodeResult {
-        let (test_bc, test_reg) = self.maybe_compile_expr(&while_stmt.test, None)?;
-
-        let while_block = self.label_generator.generate_loop_label_block();
+        let while_block = self.generate_loop_label_block(false)?;
         let while_cond_label = while_block.start_label();
         let while_end_label = while_block.end_label();
 
-        Ok(test_bc
+        let (test_bc, test_reg) = self.maybe_compile_expr(&while_stmt.test, None)?;
+        let body_bc = self.compile_stmt(while_stmt.body.borrow())?;
+        self.label_generator.leave_loop_label_block();
+
+        Ok(Bytecode::new()
             .add_label(while_cond_label)
+            .add_bytecode(test_bc)
             .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(test_reg), Operand::branch_addr(while_end_label)]))
-            .add_bytecode(self.compile_stmt(while_stmt.body.borrow())?)
+            .add_bytecode(body_bc)
             .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(while_cond_label)]))
             .add_label(while_end_label))
     }
 
     fn compile_dowhile_stmt(&mut self, dowhile_stmt: &DoWhileStmt) -> BytecodeResult {
+        let dowhile_block = self.generate_loop_label_block(false)?;
+        // 'continue' jumps to the condition
+        let dowhile_cond_label = dowhile_block.start_label();
+        let dowhile_body_label = self.label_generator.generate_label();
+
         let body_bc = self.compile_stmt(dowhile_stmt.body.borrow())?;
         let (test_bc, test_reg) = self.maybe_compile_expr(&dowhile_stmt.test, None)?;
-
-        let dowhile_block = self.label_generator.generate_loop_label_block();
-        let dowhile_start_label = dowhile_block.start_label();
+        self.label_generator.leave_loop_label_block();
 
         Ok(Bytecode::new()
-            .add_label(dowhile_start_label)
+            .add_label(dowhile_body_label)
             .add_bytecode(body_bc)
+            .add_label(dowhile_cond_label)
             .add_bytecode(test_bc)
-            .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(test_reg), Operand::branch_addr(dowhile_start_label)]))
+            .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(test_reg), Operand::branch_addr(dowhile_body_label)]))
             .add_label(dowhile_block.end_label()))
     }
 
     fn compile_for_stmt(&mut self, for_stmt: &ForStmt) -> BytecodeResult {
+        // Lexical declarations of the loop head are scoped to the loop, 'var' declarations are not
+        let decls = match &for_stmt.init {
+            Some(LoopInit::Variable(kind, decls)) => lexical_variable_declarations(kind, decls.iter()),
+            _ => vec![]
+        };
+        let has_scope = !decls.is_empty();
+        if has_scope {
+            self.scopes.enter_new_block_scope()?;
+        }
+        let (entry_bc, has_env) = self.compile_lexical_scope_entry(&decls)?;
+
         let init_bc = match &for_stmt.init {
             Some(loop_init) => match loop_init {
                 LoopInit::Variable(kind, decls) => self.compile_var_decl(&kind, &decls)?,
@@ -486,9 +1416,22 @@ impl BytecodeCompiler {
             None => Bytecode::new()
         };
 
-        let for_block = self.label_generator.generate_loop_label_block();
-        let loop_start_label = for_block.start_label();
+        // Closures capture the declarations of one iteration, thus each iteration
+        // copies their current values into a new environment
+        let renewal_bc = if has_env {
+            let scope = self.scopes.current_scope()?;
+            let captured_decls: Vec<String> = decls.into_iter().map(|(name, _)| name)
+                                                   .filter(|name| scope.is_captured(name)).collect();
+            self.compile_env_renewal(&captured_decls)?
+        } else {
+            Bytecode::new()
+        };
+
+        let for_block = self.generate_loop_label_block(false)?;
+        // 'continue' jumps to the update expression
+        let loop_update_label = for_block.start_label();
         let loop_end_label = for_block.end_label();
+        let loop_start_label = self.label_generator.generate_label();
 
         let test_bc = match &for_stmt.test {
             Some(test_expr) => {
@@ -502,33 +1445,208 @@ impl BytecodeCompiler {
         };
 
         let update_bc = match &for_stmt.update {
-            Some(update_expr) => self.maybe_compile_expr(&update_expr, None)?.0,
+            Some(update_expr) => self.compile_expr(&update_expr, self.isa.reserved_reg(&ReservedeRegister::TrashRegister))?,
             None => Bytecode::new()
         };
 
         let body_bc = self.compile_stmt(&for_stmt.body)?;
+        self.label_generator.leave_loop_label_block();
+        if has_scope {
+            self.scopes.leave_current_block_scope()?;
+        }
 
-        Ok(init_bc
+        Ok(entry_bc
+            .add_bytecode(init_bc)
+            .add_bytecode(renewal_bc.clone())
             .add_label(loop_start_label)
             .add_bytecode(test_bc)
             .add_bytecode(body_bc)
+            .add_label(loop_update_label)
+            .add_bytecode(renewal_bc)
             .add_bytecode(update_bc)
             .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(loop_start_label)]))
-            .add_label(loop_end_label))
+            .add_label(loop_end_label)
+            .add_bytecode(self.compile_leave_envs(has_env as u8)))
+    }
+
+    /// Replaces the environment of a loop head by a new one
+    ///
+    /// The captured declarations ``copied_decls`` keep their current values in the new environment.
+    fn compile_env_renewal(&mut self, copied_decls: &[String]) -> BytecodeResult {
+        let mut load_bc = Bytecode::new();
+        let mut store_bc = Bytecode::new();
+
+        for name in copied_decls.iter() {
+            let env_slot = self.scopes.get_var(name)?.env_slot.ok_or(
+                CompilerError::Custom(format!("The declaration '{}' is not captured", name))
+            )?;
+            let value_reg = self.scopes.reserve_temp_register()?;
+            load_bc = load_bc.add_bytecode(self.compile_load_captured(env_slot, value_reg)?);
+            store_bc = store_bc.add_bytecode(self.compile_decl_store(name, value_reg)?);
+        }
+
+        Ok(load_bc
+            .add_bytecode(self.compile_leave_envs(1))
+            .add_bytecode(self.compile_env_creation(true))
+            .add_bytecode(store_bc))
+    }
+
+    fn compile_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) -> BytecodeResult {
+        self.compile_iterator_loop(&for_in_stmt.left, &for_in_stmt.right, &for_in_stmt.body, Instruction::GetKeysIterator)
+    }
+
+    fn compile_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) -> BytecodeResult {
+        if for_of_stmt.is_await {
+            return Err(CompilerError::are_unsupported("for-await-of statements"));
+        }
+
+        self.compile_iterator_loop(&for_of_stmt.left, &for_of_stmt.right, &for_of_stmt.body, Instruction::GetIterator)
+    }
+
+    /// Compiles a loop over the values of the iterator, which ``get_iterator_instr`` returns for ``right``
+    fn compile_iterator_loop(&mut self, left: &LoopLeft, right: &Expr, body: &Stmt,
+                             get_iterator_instr: Instruction) -> BytecodeResult {
+        // Lexical declarations of the loop head are scoped to the loop, they are uninitialized while ``right`` is evaluated
+        let decls = match left {
+            LoopLeft::Variable(kind, decl) => lexical_variable_declarations(kind, std::iter::once(decl)),
+            _ => vec![]
+        };
+        let has_scope = !decls.is_empty();
+        if has_scope {
+            self.scopes.enter_new_block_scope()?;
+        }
+        let (entry_bc, has_env) = self.compile_lexical_scope_entry(&decls)?;
+
+        let (right_bc, right_reg) = self.maybe_compile_expr(right, None)?;
+        let iterator_reg = self.scopes.reserve_temp_register()?;
+        let done_reg = self.scopes.reserve_temp_register()?;
+        let (value_reg, store_bc) = self.compile_loop_left(left)?;
+        // Each iteration binds its values in a new environment
+        let renewal_bc = if has_env { self.compile_env_renewal(&[])? } else { Bytecode::new() };
+
+        let compile_loop = |compiler: &mut Self| -> BytecodeResult {
+            let loop_block = compiler.generate_loop_label_block(false)?;
+            let loop_next_label = loop_block.start_label();
+            let loop_end_label = loop_block.end_label();
+
+            let body_bc = compiler.compile_stmt(body);
+            compiler.label_generator.leave_loop_label_block();
+
+            Ok(Bytecode::new()
+                .add_label(loop_next_label)
+                .add(Operation::new(Instruction::IteratorNext, vec![Operand::Reg(done_reg), Operand::Reg(value_reg),
+                                                                   Operand::Reg(iterator_reg)]))
+                .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(done_reg), Operand::branch_addr(loop_end_label)]))
+                .add_bytecode(renewal_bc.clone())
+                .add_bytecode(store_bc.clone())
+                .add_bytecode(body_bc?)
+                .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(loop_next_label)]))
+                .add_label(loop_end_label))
+        };
+        // Iterators of for-of loops which are left before they are done are closed,
+        // 'break' leaves the loop regularly with an iterator which is not done
+        let loop_bc = match get_iterator_instr {
+            Instruction::GetIterator => self.compile_try_finally(&compile_loop, &|compiler, kind_reg| {
+                compiler.compile_iterator_close(iterator_reg, done_reg, kind_reg)
+            }),
+            _ => compile_loop(self)
+        };
+        if has_scope {
+            self.scopes.leave_current_block_scope()?;
+        }
+
+        Ok(entry_bc
+            .add_bytecode(right_bc)
+            .add(Operation::new(get_iterator_instr, vec![Operand::Reg(iterator_reg), Operand::Reg(right_reg)]))
+            .add_bytecode(loop_bc?)
+            .add_bytecode(self.compile_leave_envs(has_env as u8)))
+    }
+
+    /// Closes the iterator of a for-of loop unless it is done
+    ///
+    /// Errors of closing the iterator are ignored if the loop is left by an exception.
+    fn compile_iterator_close(&mut self, iterator_reg: Reg, done_reg: Reg, kind_reg: Reg) -> BytecodeResult {
+        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+        let close_end_label = self.label_generator.generate_label();
+        let throw_label = self.label_generator.generate_label();
+        let close_bc = Bytecode::new().add(Operation::new(Instruction::IteratorClose, vec![Operand::Reg(iterator_reg)]));
+
+        Ok(Bytecode::new()
+            .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(done_reg), Operand::branch_addr(close_end_label)]))
+            .add(Operation::new(Instruction::JumpTable, vec![Operand::Reg(kind_reg), Operand::LongNum(i32::from(COMPLETION_THROW)),
+                                                             Operand::ShortNum(1), Operand::branch_addr(throw_label)]))
+            .add_bytecode(close_bc.clone())
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(close_end_label)]))
+            .add_label(throw_label)
+            .add_bytecode(self.compile_try_catch(&|_| Ok(close_bc.clone()), &|_| Ok((Bytecode::new(), trash_reg)))?)
+            .add_label(close_end_label))
+    }
+
+    /// Returns the register receiving the value of each iteration and the bytecode
+    /// which stores this value into the loop variable.
+    fn compile_loop_left(&mut self, left: &LoopLeft) -> CompilerResult<(Reg, Bytecode)> {
+        match left {
+            LoopLeft::Variable(kind, VariableDecl { id: Pat::Identifier(ident), init: None }) => {
+                let reg = self.declaration_reg(ident, &DeclarationType::Variable(MyVariableKind::from(kind)))?;
+                let store_bc = self.compile_decl_store(ident, reg)?;
+                self.scopes.initialize_decl(ident)?;
+                Ok((reg, store_bc))
+            },
+            LoopLeft::Variable(_, VariableDecl { init: Some(_), .. }) =>
+                Err(CompilerError::are_unsupported("Initializers in for-in and for-of statements")),
+            LoopLeft::Expr(Expr::Ident(ident)) | LoopLeft::Pat(Pat::Identifier(ident)) => {
+                let is_checked = matches!(self.scopes.get_var(ident), Ok(decl) if decl.tdz != Tdz::Passed || decl.decl_type.is_const());
+
+                if self.is_decl_in_register(ident) && !is_checked {
+                    let (_, reg) = self.maybe_compile_expr(&Expr::Ident(ident.clone()), None)?;
+                    Ok((reg, Bytecode::new()))
+                } else {
+                    let reg = self.scopes.reserve_temp_register()?;
+                    Ok((reg, self.compile_pattern_ident(ident, reg, &PatternBinding::Assignment)?))
+                }
+            },
+            LoopLeft::Expr(Expr::Member(member)) => {
+                let value_reg = self.scopes.reserve_temp_register()?;
+                let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
+                Ok((value_reg, member_bc.add(Operation::new(Instruction::PropertySet,
+                                    vec![Operand::Reg(obj_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)]))))
+            },
+            LoopLeft::Variable(kind, VariableDecl { id: pat, init: None }) => {
+                let value_reg = self.scopes.reserve_temp_register()?;
+                let binding = PatternBinding::Declaration(DeclarationType::Variable(MyVariableKind::from(kind)));
+                Ok((value_reg, self.compile_pattern(PatternTarget::Pat(pat), value_reg, &binding)?))
+            },
+            LoopLeft::Expr(expr) => {
+                let value_reg = self.scopes.reserve_temp_register()?;
+                Ok((value_reg, self.compile_pattern(PatternTarget::Expr(expr), value_reg, &PatternBinding::Assignment)?))
+            },
+            LoopLeft::Pat(pat) => {
+                let value_reg = self.scopes.reserve_temp_register()?;
+                Ok((value_reg, self.compile_pattern(PatternTarget::Pat(pat), value_reg, &PatternBinding::Assignment)?))
+            }
+        }
     }
 
     fn maybe_compile_expr(&mut self, expr: &Expr, target_reg: Option<Register>) -> CompilerResult<(Bytecode, Register)> {
         let opt_reg = match expr {
             Expr::Ident(ident) => match self.scopes.get_var(ident) {
+                // Captured and spilled declarations have to be loaded first, lexical declarations might have to be checked
+                Ok(var) if !var.is_in_register() || var.tdz != Tdz::Passed => None,
                 Ok(var) => Some(var.register),
-                Err(_) => self.decl_dependencies.try_get_dep(ident).map(|&reg| reg)
+                Err(_) => if self.scopes.get_func_decl(ident).is_some() {
+                    None
+                } else {
+                    Some(self.get_or_add_decl_dep(ident)?)
+                }
             },
+            Expr::Literal(Literal::Template(template)) if !template.expressions.is_empty() => None,
             Expr::Literal(lit) => {
                 match self.scopes.get_lit_decl(&BytecodeLiteral::from_lit(lit.clone())?) {
                     Ok(lit_decl) => Some(lit_decl.register),
                     Err(_) => None
                 }
             }
+            Expr::This if !self.is_in_derived_constructor() => Some(self.isa.reserved_reg(&ReservedeRegister::This)),
             // TODO: Check test_member_expr
             // Expr::Member(member) => match member.object.borrow() {
             //         Expr::Ident(obj_ident) => match member.property.borrow() {
@@ -552,13 +1670,19 @@ impl BytecodeCompiler {
             },
             None => match target_reg {
                 Some(tar_reg) => (None, tar_reg),
-                None => (None, self.scopes.reserve_register()?)
+                None => (None, self.scopes.reserve_temp_register()?)
             }
         };
 
         let bytecode = match opt_bytecode {
             Some(bc) => bc,
-            None => self.compile_expr(expr, target_reg)?
+            None => {
+                // The operands of the expression are not needed anymore once its value is computed
+                let temp_regs_marker = self.scopes.temp_registers_marker()?;
+                let bytecode = self.compile_expr(expr, target_reg)?;
+                self.scopes.release_temp_registers(temp_regs_marker)?;
+                bytecode
+            }
         };
 
         Ok((bytecode, target_reg))
@@ -567,147 +1691,498 @@ impl BytecodeCompiler {
     fn compile_expr(&mut self, expr: &Expr, target_reg: Reg) -> BytecodeResult {
         match expr {
             Expr::Array(array_exprs) => self.compile_array_expr(array_exprs, target_reg),
-            Expr::ArrowFunction(_) => Err(CompilerError::are_unsupported("Arrow functions")),
+            Expr::ArrowFunction(arrow) => self.compile_arrow_func_expr(arrow, target_reg),
             Expr::ArrowParamPlaceHolder(_,_) => Err(CompilerError::are_unsupported("Arrow parameter placeholder")),
             Expr::Assignment(assignment) => self.compile_assignment_expr(assignment, target_reg),
-            Expr::Await(_) => Err(CompilerError::are_unsupported("'await' expressions")),
+            Expr::Await(argument) => self.compile_await_expr(argument, target_reg),
             Expr::Binary(bin) => self.compile_binary_expr(bin, target_reg),
-            Expr::Class(_) => Err(CompilerError::are_unsupported("'class' expressions")),
+            Expr::Class(class) => self.compile_class(class, None, target_reg),
             Expr::Call(call) => self.compile_call_expr(call, target_reg),
             Expr::Conditional(cond) => self.compile_conditional_expr(cond, target_reg),
-            Expr::Function(_) => Err(CompilerError::are_unsupported("function expressions")),
+            Expr::Function(func) => self.compile_func_expr(func, target_reg),
             Expr::Ident(ident) => self.compile_identifier_expr(ident, target_reg),
+            Expr::Literal(Literal::Template(template)) if !template.expressions.is_empty() =>
+                self.compile_template_literal(template, target_reg),
             Expr::Literal(lit) => self.compile_literal_expr(lit, target_reg),
             Expr::Logical(logical) => self.compile_logical_expr(logical, target_reg),
             Expr::Member(member) => self.compile_member_expr_access(member, target_reg),
-            Expr::MetaProperty(_) => Err(CompilerError::are_unsupported("meta properties")),
-            Expr::New(_) => Err(CompilerError::are_unsupported("object related expressions (new, this, {})")),
-            Expr::Object(_) => Err(CompilerError::are_unsupported("object related expressions (new, this, {})")),
-            Expr::Sequence(_) => Err(CompilerError::are_unsupported("seqeunce expressions")),
-            Expr::Spread(_) => Err(CompilerError::are_unsupported("spread expressions")),
-            Expr::Super => Err(CompilerError::are_unsupported("'super' expressions")),
-            Expr::TaggedTemplate(_) => Err(CompilerError::are_unsupported("tagged template expressions")),
-            Expr::This => Err(CompilerError::are_unsupported("object related expressions (new, this, {})")),
+            Expr::MetaProperty(meta) if meta.meta == "new" && meta.property == "target" =>
+                Ok(Bytecode::new().add(Operation::new(Instruction::LoadNewTarget, vec![Operand::Reg(target_reg)]))),
+            Expr::MetaProperty(_) => Err(CompilerError::are_unsupported("meta properties other than 'new.target'")),
+            Expr::New(new) => self.compile_new_expr(new, target_reg),
+            Expr::Object(object) => self.compile_object_expr(object, target_reg),
+            Expr::Sequence(exprs) => self.compile_sequence_expr(exprs, target_reg),
+            Expr::Spread(_) => Err(CompilerError::Custom("Spread elements are only allowed in array literals and arguments".into())),
+            Expr::Super => Err(CompilerError::Custom("'super' is only valid in property accesses and calls".into())),
+            Expr::TaggedTemplate(tagged) => self.compile_tagged_template_expr(tagged, target_reg),
+            Expr::This => self.compile_this_expr(target_reg),
             Expr::Update(update) => self.compile_update_expr(update, target_reg),
             Expr::Unary(unary) => self.compile_unary_expr(unary, target_reg),
-            Expr::Yield(_) => Err(CompilerError::are_unsupported("'yield' expressions")),
+            Expr::Yield(yield_expr) => self.compile_yield_expr(yield_expr, target_reg),
+        }
+    }
+
+    fn compile_this_expr(&self, target_reg: Reg) -> BytecodeResult {
+        let this_reg = self.isa.reserved_reg(&ReservedeRegister::This);
+        let bytecode = self.compile_operand_assignment(target_reg, Operand::Reg(this_reg))?;
+
+        // Derived constructors have no 'this' until they called the parent constructor
+        if self.is_in_derived_constructor() {
+            Ok(bytecode.add(Operation::new(Instruction::CheckInitialized, vec![Operand::Reg(target_reg)])))
+        } else {
+            Ok(bytecode)
         }
     }
 
+    fn is_in_derived_constructor(&self) -> bool {
+        self.class_member == Some(ClassMember::Constructor { is_derived: true })
+    }
+
     fn compile_array_expr(&mut self, array: &ArrayExpr, target_reg: Reg) -> BytecodeResult {
-        let (bytecodes, regs): (Vec<Bytecode>, Vec<Reg>) = array.iter().map(|opt_expr| {
-            match opt_expr {
-                Some(expr) => self.maybe_compile_expr(expr, None),
-                None => Err(CompilerError::are_unsupported("'null' array fields"))
+        let elements: Vec<Option<&Expr>> = array.iter().map(|opt_expr| opt_expr.as_ref()).collect();
+
+        if !Self::has_spread_or_hole(&elements) && elements.len() <= MAX_CHUNK_SIZE {
+            let (bytecodes, regs): (Vec<Bytecode>, Vec<Reg>) = elements.iter().flatten().enumerate().map(|(i, expr)| {
+                self.maybe_compile_operand(expr, Self::has_side_effects(elements[i + 1..].iter().flatten().copied()))
+            }).collect::<CompilerResult<Vec<(Bytecode, Reg)>>>()?.into_iter().unzip();
+
+            return Ok(bytecodes.into_iter().collect::<Bytecode>()
+                .add(Operation::new(Instruction::LoadArray, vec![Operand::Reg(target_reg), Operand::RegistersArray(regs)])));
+        }
+
+        // The array is built in a temporary register, since its elements might read 'target_reg'
+        let array_reg = self.scopes.reserve_temp_register()?;
+        Ok(self.compile_array_elements(&elements, array_reg)?
+            .add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(array_reg))?))
+    }
+
+    fn has_spread_or_hole(elements: &[Option<&Expr>]) -> bool {
+        elements.iter().any(|element| matches!(element, Some(Expr::Spread(_)) | None))
+    }
+
+    /// Builds an array of ``elements`` in ``array_reg``, ``None`` elements are holes
+    ///
+    /// Runs of up to ``MAX_CHUNK_SIZE`` plain elements are loaded with a single ``LoadArray``,
+    /// while spread elements and holes are appended to the array afterwards.
+    fn compile_array_elements(&mut self, elements: &[Option<&Expr>], array_reg: Reg) -> BytecodeResult {
+        let mut bytecode = Bytecode::new();
+        let mut run_regs = vec![];
+        let mut is_loaded = false;
+        let temp_regs_marker = self.scopes.temp_registers_marker()?;
+
+        for (i, element) in elements.iter().enumerate() {
+            if let Some(Expr::Spread(_)) | None = element {
+                bytecode = bytecode.add_bytecode(self.compile_array_run(&mut run_regs, &mut is_loaded, array_reg)?);
             }
-        }).collect::<CompilerResult<Vec<(Bytecode, Reg)>>>()?.into_iter().unzip();
 
-        Ok(bytecodes.into_iter().collect::<Bytecode>()
-            .add(Operation::new(Instruction::LoadArray, vec![Operand::Reg(target_reg), Operand::RegistersArray(regs)]))
-        )
+            bytecode = match element {
+                Some(Expr::Spread(spread)) => {
+                    let (spread_bc, spread_reg) = self.maybe_compile_expr(spread, None)?;
+                    bytecode
+                        .add_bytecode(spread_bc)
+                        .add(Operation::new(Instruction::ArraySpread, vec![Operand::Reg(array_reg), Operand::Reg(spread_reg)]))
+                },
+                Some(expr) => {
+                    let (expr_bc, expr_reg) = self.maybe_compile_operand(expr, Self::has_side_effects(elements[i + 1..].iter().flatten().copied()))?;
+                    run_regs.push(expr_reg);
+                    bytecode.add_bytecode(expr_bc)
+                },
+                None => {
+                    // A hole only increases the length of the array
+                    let (length_bc, length_key_reg) = self.maybe_compile_expr(&Expr::Literal(Literal::String("\"length\"".into())), None)?;
+                    let length_reg = self.scopes.reserve_temp_register()?;
+                    bytecode
+                        .add_bytecode(length_bc)
+                        .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(length_reg), Operand::Reg(array_reg), Operand::Reg(length_key_reg)]))
+                        .add(self.isa.update_op(&UpdateOperator::Increment, length_reg, length_reg))
+                        .add(Operation::new(Instruction::PropertySet, vec![Operand::Reg(array_reg), Operand::Reg(length_key_reg), Operand::Reg(length_reg)]))
+                }
+            };
+
+            if run_regs.len() >= MAX_CHUNK_SIZE {
+                bytecode = bytecode.add_bytecode(self.compile_array_run(&mut run_regs, &mut is_loaded, array_reg)?);
+            }
+            // The values of elements which are part of the array are not needed anymore
+            if run_regs.is_empty() {
+                self.scopes.release_temp_registers(temp_regs_marker)?;
+            }
+        }
+
+        Ok(bytecode.add_bytecode(self.compile_array_run(&mut run_regs, &mut is_loaded, array_reg)?))
     }
 
-    fn compile_assignment_expr(&mut self, assign: &AssignmentExpr, _target_reg: Reg) -> BytecodeResult {
+    /// Loads the pending plain elements ``run_regs`` into the array or appends them to it
+    fn compile_array_run(&mut self, run_regs: &mut Vec<Reg>, is_loaded: &mut bool, array_reg: Reg) -> BytecodeResult {
+        let regs = std::mem::take(run_regs);
+
+        if !*is_loaded {
+            *is_loaded = true;
+            Ok(Bytecode::new().add(Operation::new(Instruction::LoadArray, vec![Operand::Reg(array_reg), Operand::RegistersArray(regs)])))
+        } else if !regs.is_empty() {
+            let run_reg = self.scopes.reserve_temp_register()?;
+            Ok(Bytecode::new()
+                .add(Operation::new(Instruction::LoadArray, vec![Operand::Reg(run_reg), Operand::RegistersArray(regs)]))
+                .add(Operation::new(Instruction::ArraySpread, vec![Operand::Reg(array_reg), Operand::Reg(run_reg)])))
+        } else {
+            Ok(Bytecode::new())
+        }
+    }
+
+    /// Compiles the arguments of a call with spread elements into an array
+    fn compile_spread_arguments(&mut self, args: &[Expr]) -> CompilerResult<(Bytecode, Reg)> {
+        let elements: Vec<Option<&Expr>> = args.iter().map(Some).collect();
+        let args_reg = self.scopes.reserve_temp_register()?;
+
+        Ok((self.compile_array_elements(&elements, args_reg)?, args_reg))
+    }
+
+    /// Whether arguments are passed as an array, since their number is unknown or they do not fit into registers
+    fn has_arguments_array(args: &[Expr]) -> bool {
+        args.len() > MAX_CHUNK_SIZE || args.iter().any(|arg| matches!(arg, Expr::Spread(_)))
+    }
+
+    /// Compiles all expressions of a sequence, only the value of the last one is kept
+    fn compile_sequence_expr(&mut self, exprs: &[Expr], target_reg: Reg) -> BytecodeResult {
+        let (last, others) = exprs.split_last().ok_or(CompilerError::Custom("Empty sequence expression".into()))?;
+
+        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+
+        let bytecode = others.iter()
+            .filter(|expr| !Self::is_free_of_side_effects(expr))
+            .map(|expr| self.compile_expr(expr, trash_reg))
+            .collect::<BytecodeResult>()?;
+        Ok(bytecode.add_bytecode(self.compile_expr(last, target_reg)?))
+    }
+
+    /// Compiles a yield expression, which suspends the generator until it is resumed
+    ///
+    /// The value the generator is resumed with is written to ``target_reg``.
+    /// ``yield*`` yields the values of an iterator until it is done and results in its return value.
+    fn compile_yield_expr(&mut self, yield_expr: &YieldExpr, target_reg: Reg) -> BytecodeResult {
+        if !self.is_generator {
+            return Err(CompilerError::Custom("'yield' is only valid in generator functions".into()));
+        }
+
+        let (argument_bc, argument_reg) = match &yield_expr.argument {
+            Some(argument) => self.maybe_compile_expr(argument, None)?,
+            None => (Bytecode::new(), self.isa.common_literal_reg(&CommonLiteral::Void0))
+        };
+        let instruction = if yield_expr.delegate { Instruction::YieldDelegate } else { Instruction::Yield };
+
+        Ok(argument_bc.add(Operation::new(instruction, vec![Operand::Reg(target_reg), Operand::Reg(argument_reg)])))
+    }
+
+    /// Compiles an await expression, which suspends the async function until ``argument`` is settled
+    ///
+    /// A rejection is thrown where the function was suspended, just like an exception.
+    fn compile_await_expr(&mut self, argument: &Expr, target_reg: Reg) -> BytecodeResult {
+        if !self.is_async {
+            return Err(CompilerError::Custom("'await' is only valid in async functions".into()));
+        }
+
+        let (argument_bc, argument_reg) = self.maybe_compile_expr(argument, None)?;
+
+        Ok(argument_bc.add(Operation::new(Instruction::Await, vec![Operand::Reg(target_reg), Operand::Reg(argument_reg)])))
+    }
+
+    fn compile_assignment_expr(&mut self, assign: &AssignmentExpr, target_reg: Reg) -> BytecodeResult {
+        let mut captured_ident = None;
         let ((left_bc, left_reg), maybe_prop_reg) = match &assign.left {
-            AssignmentLeft::Pat(_) => { return Err(CompilerError::are_unsupported("Patterns in assignments")); },
+            AssignmentLeft::Pat(pat) => { return self.compile_destructuring_assignment(PatternTarget::Pat(pat), assign, target_reg); },
             AssignmentLeft::Expr(expr) => match expr.borrow() {
+                Expr::Array(_) | Expr::Object(_) => {
+                    return self.compile_destructuring_assignment(PatternTarget::Expr(expr), assign, target_reg);
+                },
                 Expr::Member(member) => {
-                    let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
+                    // The object and the key are evaluated only once, even if the right side changes their declarations
+                    let is_followed_by_side_effects = !Self::is_free_of_side_effects(assign.right.borrow());
+                    let (member_bc, obj_reg, prop_reg) = self.compile_member_operands(member, is_followed_by_side_effects)?;
                     ((member_bc, obj_reg), Some(prop_reg))
                 },
+                Expr::Ident(ident) if !self.is_decl_in_register(ident) => {
+                    // The new value is computed in a temporary register and then stored
+                    self.check_assignable(ident)?;
+                    captured_ident = Some(ident);
+                    let tmp_reg = self.scopes.reserve_temp_register()?;
+                    let load_bc = match assign.operator {
+                        AssignmentOperator::Equal => self.compile_initialized_check(ident)?,
+                        _ => self.compile_identifier_expr(ident, tmp_reg)?
+                    };
+                    ((load_bc, tmp_reg), None)
+                },
+                Expr::Ident(ident) => (self.compile_assignment_target(ident)?, None),
                 _ => (self.maybe_compile_expr(&expr, None)?, None)
             }
         };
 
-        match assign.operator {
-            AssignmentOperator::Equal => {
-                if let Some(prop_reg) = maybe_prop_reg {
-                    let (value_bc, value_reg) = self.maybe_compile_expr(assign.right.borrow(), None)?;
-                    Ok(left_bc
-                        .add_bytecode(value_bc)
-                        .add(Operation::new(Instruction::PropertySet,
-                                vec![Operand::Reg(left_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)])))
-                } else {
-                    Ok(left_bc.add_bytecode(self.compile_expr(assign.right.borrow(), left_reg)?))
-                }
-            }
-            _ => {
+        // The register holding the assigned value
+        let (bytecode, value_reg) = match (&assign.operator, maybe_prop_reg) {
+            (AssignmentOperator::Equal, Some(prop_reg)) => {
+                let (value_bc, value_reg) = self.maybe_compile_expr(assign.right.borrow(), None)?;
+                (left_bc
+                    .add_bytecode(value_bc)
+                    .add(Operation::new(Instruction::PropertySet,
+                            vec![Operand::Reg(left_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)])),
+                 value_reg)
+            },
+            (AssignmentOperator::Equal, None) => {
+                let value_bc = match (&assign.left, assign.right.borrow()) {
+                    (AssignmentLeft::Expr(left), Expr::Class(class)) => match left.borrow() {
+                        Expr::Ident(ident) => self.compile_class(class, Some(ident), left_reg)?,
+                        _ => self.compile_class(class, None, left_reg)?
+                    },
+                    (_, right) => self.compile_expr(right, left_reg)?
+                };
+                (left_bc.add_bytecode(value_bc), left_reg)
+            },
+            (operator, Some(prop_reg)) => {
+                let value_reg = self.scopes.reserve_temp_register()?;
+                let (right_bc, right_reg) = self.maybe_compile_expr(assign.right.borrow(), None)?;
+                (left_bc
+                    .add(Operation::new(Instruction::PropAccess,
+                            vec![Operand::Reg(value_reg), Operand::Reg(left_reg), Operand::Reg(prop_reg)]))
+                    .add_bytecode(right_bc)
+                    .add(self.isa.assignment_op(operator, value_reg, right_reg))
+                    .add(Operation::new(Instruction::PropertySet,
+                            vec![Operand::Reg(left_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)])),
+                 value_reg)
+            },
+            (operator, None) if captured_ident.is_none() && !Self::is_free_of_side_effects(assign.right.borrow()) => {
+                // The old value is read before the right side might assign the declaration
+                let value_reg = self.scopes.reserve_temp_register()?;
+                let (right_bc, right_reg) = self.maybe_compile_expr(assign.right.borrow(), None)?;
+                (left_bc
+                    .add_bytecode(self.compile_operand_assignment(value_reg, Operand::Reg(left_reg))?)
+                    .add_bytecode(right_bc)
+                    .add(self.isa.assignment_op(operator, value_reg, right_reg))
+                    .add_bytecode(self.compile_operand_assignment(left_reg, Operand::Reg(value_reg))?),
+                 value_reg)
+            },
+            (_, None) => {
                 let (right_bc, right_reg) = self.maybe_compile_expr(assign.right.borrow(), None)?;
-                Ok(left_bc.add_bytecode(right_bc)
-                    .add(self.isa.assignment_op(&assign.operator, left_reg, right_reg)))
+                (left_bc.add_bytecode(right_bc)
+                    .add(self.isa.assignment_op(&assign.operator, left_reg, right_reg)),
+                 left_reg)
             }
+        };
+
+        let bytecode = match captured_ident {
+            Some(ident) => bytecode.add_bytecode(self.compile_decl_store(ident, left_reg)?),
+            None => bytecode
+        };
+
+        // Assignment expressions result in the assigned value
+        if target_reg == value_reg || target_reg == self.isa.reserved_reg(&ReservedeRegister::TrashRegister) {
+            Ok(bytecode)
+        } else {
+            Ok(bytecode.add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(value_reg))?))
         }
     }
 
+    fn compile_destructuring_assignment(&mut self, target: PatternTarget, assign: &AssignmentExpr, target_reg: Reg) -> BytecodeResult {
+        if assign.operator != AssignmentOperator::Equal {
+            return Err(CompilerError::Custom("Destructuring patterns can only be assigned with '='".into()));
+        }
+
+        let (right_bc, value_reg) = self.compile_pattern_source(assign.right.borrow())?;
+        let bytecode = right_bc.add_bytecode(self.compile_pattern(target, value_reg, &PatternBinding::Assignment)?);
+
+        // The assignment results in the destructured value
+        if target_reg == self.isa.reserved_reg(&ReservedeRegister::TrashRegister) {
+            Ok(bytecode)
+        } else {
+            Ok(bytecode.add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(value_reg))?))
+        }
+    }
+
+    fn is_free_of_side_effects(expr: &Expr) -> bool {
+        match expr {
+            // Substitutions of templates are converted to strings
+            Expr::Literal(Literal::Template(template)) => template.expressions.is_empty(),
+            Expr::Literal(_) | Expr::Ident(_) | Expr::This => true,
+            // Functions are only created, their bodies are not run
+            Expr::Function(_) | Expr::ArrowFunction(_) => true,
+            _ => false
+        }
+    }
+
+    fn has_side_effects<'e>(mut exprs: impl Iterator<Item = &'e Expr>) -> bool {
+        exprs.any(|expr| !Self::is_free_of_side_effects(expr))
+    }
+
+    /// Compiles a binary expression
+    ///
+    /// Nested left operands, e.g. of ``a + b + c``, are computed one after another in a single
+    /// temporary register, thus long chains of operations do not need a register per operation.
     fn compile_binary_expr(&mut self, bin: &BinaryExpr, target_reg: Reg) -> BytecodeResult {
-        let (left_bc, left_reg) = self.maybe_compile_expr(bin.left.borrow(), None)?;
-        let (right_bc, right_reg) = self.maybe_compile_expr(bin.right.borrow(), None)?;
+        let mut chain = vec![bin];
+        let mut leftmost = bin.left.borrow();
+        while let Expr::Binary(left_bin) = leftmost {
+            chain.push(left_bin);
+            leftmost = left_bin.left.borrow();
+        }
 
-        Ok(left_bc
-            .add_bytecode(right_bc)
-            .add(self.isa.binary_op(&bin.operator, target_reg, left_reg, right_reg)?)
-        )
+        // The leftmost operand is read after the right operand of the innermost operation is evaluated
+        let innermost_right = chain[chain.len() - 1].right.borrow();
+        let (mut bytecode, mut left_reg) = self.maybe_compile_operand(leftmost, !Self::is_free_of_side_effects(innermost_right))?;
+        let acc_reg = if chain.len() > 1 { self.scopes.reserve_temp_register()? } else { target_reg };
+
+        while let Some(bin) = chain.pop() {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let (right_bc, right_reg) = self.maybe_compile_expr(bin.right.borrow(), None)?;
+            let dst_reg = if chain.is_empty() { target_reg } else { acc_reg };
+
+            bytecode = bytecode
+                .add_bytecode(right_bc)
+                .add(self.isa.binary_op(&bin.operator, dst_reg, left_reg, right_reg)?);
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+            left_reg = acc_reg;
+        }
+
+        Ok(bytecode)
     }
 
     fn compile_call_expr(&mut self, call: &CallExpr, target_reg: Reg) -> BytecodeResult {
         match call.callee.borrow() {
-            Expr::Ident(ident) => {
-                if self.functions.iter().any(|func| func.ident == *ident) {
-                    self.compile_bytecode_func_call(ident.to_string(), &call.arguments, target_reg)
-                } else {
-                    self.compile_extern_func_call(call, target_reg)
-                }
-            }
+            Expr::Ident(ident) => match self.direct_callee(ident) {
+                Some(func_ident) => self.compile_bytecode_func_call(func_ident, &call.arguments, target_reg),
+                None => self.compile_extern_func_call(call, target_reg)
+            },
+            Expr::Super => self.compile_super_call(&call.arguments, target_reg),
             _ => self.compile_extern_func_call(call, target_reg)
         }
     }
 
+    /// Returns the bytecode function which a call of ``ident`` can jump to directly
+    ///
+    /// Calling a generator or async function does not run its body right away, thus they are called as callbacks.
+    fn direct_callee(&self, ident: &str) -> Option<Identifier> {
+        let func_ident = self.scopes.get_func_decl(ident)?;
+        self.functions.iter().find(|func| func.ident == func_ident && !func.is_generator && !func.is_async)
+                             .map(|func| func.ident.clone())
+    }
+
     fn compile_conditional_expr(&mut self, conditional: &ConditionalExpr, target_reg: Reg) -> BytecodeResult {
         let (test_bc, test_reg) = self.maybe_compile_expr(conditional.test.borrow(), None)?;
         let (consequent_bc, _) = self.maybe_compile_expr(conditional.consequent.borrow(), Some(target_reg))?;
         let (alt_bc, _) = self.maybe_compile_expr(conditional.alternate.borrow(), Some(target_reg))?;
 
-        let after_alt_label = self.label_generator.generate_label();
-        let after_cons_label = self.label_generator.generate_label();
+        let alt_start_label = self.label_generator.generate_label();
+        let cond_end_label = self.label_generator.generate_label();
 
         Ok(test_bc
-            .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(test_reg), Operand::branch_addr(after_alt_label)]))
+            .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(test_reg), Operand::branch_addr(alt_start_label)]))
             .add_bytecode(consequent_bc)
-            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(after_cons_label)]))
-            .add_label(after_alt_label)
+            .add(Operation::new(Instruction::Jump, vec![Operand::branch_addr(cond_end_label)]))
+            .add_label(alt_start_label)
             .add_bytecode(alt_bc)
-            .add_label(after_cons_label))
+            .add_label(cond_end_label))
     }
 
     fn compile_bytecode_func_call(&mut self, func: String, args: &[Expr], target_reg: Reg) -> BytecodeResult {
-        let (args_bytecode, arg_regs): (Vec<Bytecode>, Vec<Reg>) = args.iter().map(|arg_expr| {
-            self.maybe_compile_expr(arg_expr, None)
-        }).collect::<CompilerResult<Vec<(Bytecode, Reg)>>>()?.into_iter().unzip();
+        if Self::has_arguments_array(args) {
+            // The function is applied to the array of arguments as a callback
+            let (args_bc, args_reg) = self.compile_spread_arguments(args)?;
+            let callback_reg = self.scopes.reserve_temp_register()?;
+
+            return Ok(args_bc
+                .add_bytecode(self.compile_bytecode_func_callback(&func, callback_reg)?)
+                .add(Operation::new(Instruction::CallFuncApply, vec![
+                    Operand::Reg(target_reg),
+                    Operand::Reg(callback_reg),
+                    Operand::Reg(self.isa.common_literal_reg(&CommonLiteral::Void0)),
+                    Operand::Reg(args_reg)
+                ])));
+        }
+
+        let (args_bytecode, arg_regs) = self.compile_call_arguments(args)?;
+
+        Ok(args_bytecode.add_bytecode(self.compile_bytecode_func_call_op(func, arg_regs, target_reg)?))
+    }
 
-        Ok(args_bytecode.into_iter().collect::<Bytecode>()
+    fn compile_bytecode_func_call_op(&self, func: String, arg_regs: Vec<Reg>, target_reg: Reg) -> BytecodeResult {
+        let env_hops = self.func_env_hops(&func)?;
+
+        Ok(Bytecode::new()
             .add(Operation::new(Instruction::CallBytecodeFunc,
                                 vec![Operand::function_addr(func),
                                      Operand::Reg(target_reg),
-                                     Operand::bc_func_args(arg_regs)])))
+                                     Operand::bc_func_args(arg_regs),
+                                     Operand::ShortNum(env_hops)])))
     }
 
-    fn compile_extern_func_call(&mut self, call: &CallExpr, target_reg: Reg) -> BytecodeResult {
-        let (callee_bc, callee_reg) = self.maybe_compile_expr(&call.callee, None)?;
+    fn compile_call_arguments(&mut self, args: &[Expr]) -> CompilerResult<(Bytecode, Vec<Reg>)> {
+        let (args_bytecode, arg_regs): (Vec<Bytecode>, Vec<Reg>) = args.iter().enumerate().map(|(i, arg_expr)| {
+            self.maybe_compile_operand(arg_expr, Self::has_side_effects(args[i + 1..].iter()))
+        }).collect::<CompilerResult<Vec<(Bytecode, Reg)>>>()?.into_iter().unzip();
 
-        let (callee_this_bc, callee_this_reg) =
-            if let Expr::Member(member_expr) = call.callee.borrow() {
-                self.maybe_compile_expr(&member_expr.object, None)?
-            } else {
-                (Bytecode::new(), self.isa.common_literal_reg(&CommonLiteral::Void0))
-            };
+        Ok((args_bytecode.into_iter().collect(), arg_regs))
+    }
 
-        let (bytecode, arg_regs): (Vec<Bytecode>, Vec<Reg>) = call.arguments.iter().map(|arg| {
-            self.maybe_compile_expr(arg, None)
-        }).collect::<CompilerResult<Vec<(Bytecode, Reg)>>>()?.into_iter().unzip();
+    /// Compiles an operand, which is read only after the following operands are evaluated
+    ///
+    /// Declarations in registers would be read after the following operands assigned them,
+    /// e.g. in ``f(i, i++)``, thus they are copied if the following operands might have side effects.
+    fn maybe_compile_operand(&mut self, expr: &Expr, is_followed_by_side_effects: bool) -> CompilerResult<(Bytecode, Reg)> {
+        match expr {
+            Expr::Ident(_) if is_followed_by_side_effects => {
+                let operand_reg = self.scopes.reserve_temp_register()?;
+                self.maybe_compile_expr(expr, Some(operand_reg))
+            },
+            _ => self.maybe_compile_expr(expr, None)
+        }
+    }
+
+    /// Compiles the callee of an extern function call and the ``this`` value it is called with
+    fn compile_extern_callee(&mut self, callee: &Expr, args: &[Expr]) -> CompilerResult<(Bytecode, Reg, Reg)> {
+        let is_followed_by_side_effects = Self::has_side_effects(args.iter());
+
+        match callee {
+            // Methods of the parent class are called with the current 'this'
+            Expr::Member(member_expr) if matches!(*member_expr.object, Expr::Super) => {
+                let (callee_bc, callee_reg) = self.maybe_compile_expr(callee, None)?;
+                let (this_bc, this_reg) = self.maybe_compile_expr(&Expr::This, None)?;
+
+                Ok((callee_bc.add_bytecode(this_bc), callee_reg, this_reg))
+            },
+            // The object is evaluated only once, since it is the receiver of the call as well
+            Expr::Member(member_expr) => {
+                let callee_reg = self.scopes.reserve_temp_register()?;
+                let (member_bc, obj_reg, prop_reg) = self.compile_member_operands(member_expr, is_followed_by_side_effects)?;
+
+                Ok((member_bc.add(Operation::new(Instruction::PropAccess, vec![
+                        Operand::Reg(callee_reg), Operand::Reg(obj_reg), Operand::Reg(prop_reg)
+                    ])), callee_reg, obj_reg))
+            },
+            _ => {
+                let (callee_bc, callee_reg) = self.maybe_compile_operand(callee, is_followed_by_side_effects)?;
+                Ok((callee_bc, callee_reg, self.isa.common_literal_reg(&CommonLiteral::Void0)))
+            }
+        }
+    }
 
-        Ok(bytecode.into_iter().collect::<Bytecode>()
-            .add_bytecode(callee_bc)
-            .add_bytecode(callee_this_bc)
+    fn compile_extern_func_call(&mut self, call: &CallExpr, target_reg: Reg) -> BytecodeResult {
+        let (callee_bc, callee_reg, callee_this_reg) = self.compile_extern_callee(&call.callee, &call.arguments)?;
+
+        if Self::has_arguments_array(&call.arguments) {
+            let (args_bc, args_reg) = self.compile_spread_arguments(&call.arguments)?;
+
+            return Ok(callee_bc
+                .add_bytecode(args_bc)
+                .add(Operation::new(Instruction::CallFuncApply, vec![
+                        Operand::Reg(target_reg),
+                        Operand::Reg(callee_reg),
+                        Operand::Reg(callee_this_reg),
+                        Operand::Reg(args_reg)
+                    ]
+            )));
+        }
+
+        let (args_bc, arg_regs) = self.compile_call_arguments(&call.arguments)?;
+
+        // The callee is evaluated before the arguments, which might reuse its temporary registers
+        Ok(callee_bc
+            .add_bytecode(args_bc)
             .add(Operation::new(Instruction::CallFunc, vec![
                     Operand::Reg(target_reg),
                     Operand::Reg(callee_reg),
@@ -717,34 +2192,217 @@ impl BytecodeCompiler {
         )))
     }
 
+    fn compile_template_literal(&mut self, template: &TemplateLiteral, target_reg: Reg) -> BytecodeResult {
+        // The string is built in a temporary register as substitutions might read the target
+        let string_reg = self.scopes.reserve_temp_register()?;
+        let part_reg = self.scopes.reserve_temp_register()?;
+        let mut bytecode = Bytecode::new();
+        let mut is_empty = true;
+        let temp_regs_marker = self.scopes.temp_registers_marker()?;
+
+        for (i, quasi) in template.quasis.iter().enumerate() {
+            let cooked = BytecodeLiteral::cook_template_element(quasi)
+                .ok_or_else(|| CompilerError::Custom(format!("Invalid escape sequence in template '{}'", quasi.raw)))?;
+            if !cooked.is_empty() {
+                let dst_reg = if is_empty { string_reg } else { part_reg };
+                bytecode = bytecode.add(self.isa.load_op(dst_reg, Operand::String(cooked)));
+                bytecode = self.add_template_part(bytecode, &mut is_empty, string_reg, part_reg)?;
+            }
+
+            if let Some(expr) = template.expressions.get(i) {
+                let (expr_bc, expr_reg) = self.maybe_compile_expr(expr, None)?;
+                let dst_reg = if is_empty { string_reg } else { part_reg };
+                bytecode = bytecode.add_bytecode(expr_bc)
+                    .add(Operation::new(Instruction::ToString, vec![Operand::Reg(dst_reg), Operand::Reg(expr_reg)]));
+                bytecode = self.add_template_part(bytecode, &mut is_empty, string_reg, part_reg)?;
+                // The value of the substitution is part of the string now
+                self.scopes.release_temp_registers(temp_regs_marker)?;
+            }
+        }
+
+        Ok(bytecode.add(Operation::new(Instruction::Copy, vec![Operand::Reg(target_reg), Operand::Reg(string_reg)])))
+    }
+
+    fn add_template_part(&self, bytecode: Bytecode, is_empty: &mut bool, string_reg: Reg, part_reg: Reg) -> BytecodeResult {
+        if *is_empty {
+            *is_empty = false;
+            return Ok(bytecode);
+        }
+
+        Ok(bytecode.add(self.isa.binary_op(&BinaryOperator::Plus, string_reg, string_reg, part_reg)?))
+    }
+
+    /// Compiles the strings array of a tagged template
+    ///
+    /// The cooked and raw strings are passed to the VM, which creates the frozen array only once for every call site.
+    fn compile_template_object(&mut self, template: &TemplateLiteral) -> CompilerResult<(Bytecode, Reg)> {
+        let cooked: Vec<Option<String>> = template.quasis.iter().map(BytecodeLiteral::cook_template_element).collect();
+        let raw: Vec<Option<String>> = template.quasis.iter().map(|quasi| Some(BytecodeLiteral::raw_template_element(quasi))).collect();
+
+        let cooked_reg = self.scopes.reserve_temp_register()?;
+        let raw_reg = self.scopes.reserve_temp_register()?;
+        let bytecode = self.compile_strings_array(&cooked, cooked_reg)?
+                           .add_bytecode(self.compile_strings_array(&raw, raw_reg)?);
+
+        let strings_reg = self.scopes.reserve_temp_register()?;
+        Ok((bytecode.add(Operation::new(Instruction::TemplateObject, vec![
+                Operand::Reg(strings_reg),
+                Operand::Reg(cooked_reg),
+                Operand::Reg(raw_reg)
+            ])), strings_reg))
+    }
+
+    /// Builds an array of ``strings`` in ``array_reg``, ``None`` strings are undefined
+    ///
+    /// The strings are loaded in runs of up to ``MAX_CHUNK_SIZE``, like the elements of array literals.
+    fn compile_strings_array(&mut self, strings: &[Option<String>], array_reg: Reg) -> BytecodeResult {
+        let mut bytecode = Bytecode::new();
+        let mut is_loaded = false;
+
+        for chunk in strings.chunks(MAX_CHUNK_SIZE) {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let mut run_regs = vec![];
+
+            for string in chunk {
+                match string {
+                    Some(string) => {
+                        let string_reg = self.scopes.reserve_temp_register()?;
+                        bytecode = bytecode.add(self.isa.load_op(string_reg, Operand::String(string.clone())));
+                        run_regs.push(string_reg);
+                    },
+                    None => run_regs.push(self.isa.common_literal_reg(&CommonLiteral::Void0))
+                }
+            }
+
+            bytecode = bytecode.add_bytecode(self.compile_array_run(&mut run_regs, &mut is_loaded, array_reg)?);
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+        }
+
+        Ok(bytecode)
+    }
+
+    fn compile_tagged_template_expr(&mut self, tagged: &TaggedTemplateExpr, target_reg: Reg) -> BytecodeResult {
+        let direct_callee = match tagged.tag.borrow() {
+            Expr::Ident(ident) => self.direct_callee(ident),
+            _ => None
+        };
+
+        if let Some(func_ident) = direct_callee {
+            let (args_bc, arg_regs) = self.compile_tagged_template_arguments(&tagged.quasi)?;
+            return Ok(args_bc.add_bytecode(self.compile_bytecode_func_call_op(func_ident, arg_regs, target_reg)?));
+        }
+
+        let (tag_bc, tag_reg, tag_this_reg) = self.compile_extern_callee(&tagged.tag, &tagged.quasi.expressions)?;
+        let (args_bc, arg_regs) = self.compile_tagged_template_arguments(&tagged.quasi)?;
+
+        Ok(tag_bc
+            .add_bytecode(args_bc)
+            .add(Operation::new(Instruction::CallFunc, vec![
+                    Operand::Reg(target_reg),
+                    Operand::Reg(tag_reg),
+                    Operand::Reg(tag_this_reg),
+                    Operand::RegistersArray(arg_regs)
+                ]
+        )))
+    }
+
+    /// Compiles the arguments of a tag function, which are the strings array followed by the substitutions
+    fn compile_tagged_template_arguments(&mut self, template: &TemplateLiteral) -> CompilerResult<(Bytecode, Vec<Reg>)> {
+        let (strings_bc, strings_reg) = self.compile_template_object(template)?;
+        let (subs_bc, sub_regs) = self.compile_call_arguments(&template.expressions)?;
+
+        Ok((strings_bc.add_bytecode(subs_bc), std::iter::once(strings_reg).chain(sub_regs).collect()))
+    }
+
+    fn compile_new_expr(&mut self, new: &NewExpr, target_reg: Reg) -> BytecodeResult {
+        let (callee_bc, callee_reg) = self.maybe_compile_operand(&new.callee, Self::has_side_effects(new.arguments.iter()))?;
+
+        if Self::has_arguments_array(&new.arguments) {
+            let (args_bc, args_reg) = self.compile_spread_arguments(&new.arguments)?;
+
+            return Ok(callee_bc
+                .add_bytecode(args_bc)
+                .add(Operation::new(Instruction::ConstructApply, vec![
+                        Operand::Reg(target_reg),
+                        Operand::Reg(callee_reg),
+                        Operand::Reg(args_reg)
+                    ]
+            )));
+        }
+
+        let (args_bc, arg_regs) = self.compile_call_arguments(&new.arguments)?;
+
+        Ok(callee_bc
+            .add_bytecode(args_bc)
+            .add(Operation::new(Instruction::Construct, vec![
+                    Operand::Reg(target_reg),
+                    Operand::Reg(callee_reg),
+                    Operand::RegistersArray(arg_regs)
+                ]
+        )))
+    }
+
     fn compile_operand_assignment(&self, left: Reg, right: Operand) -> BytecodeResult {
         Ok(Bytecode::new().add(self.isa.load_op(left, right)))
     }
 
     fn compile_identifier_expr(&mut self, ident: &Identifier, target_reg: Reg) -> BytecodeResult {
         match self.scopes.get_var(&ident).map(|decl| decl.clone()) {
-            Ok(decl) => self.compile_operand_assignment(target_reg, Operand::Reg(decl.register)),
-            Err(_) => match self.functions.iter().find(|func| func.ident == *ident) {
-                Some(func) => {
-                    Ok(Bytecode::new()
-                        .add(Operation::new(Instruction::BytecodeFuncCallback, vec![
-                            Operand::Reg(target_reg),
-                            Operand::function_addr(ident.clone()),
-                            Operand::RegistersArray(func.arguments.clone())])))
-                },
-                None => {
-                    for i in 0..self.scopes.scopes.len()-1 {
-                        self.scopes.scopes[i].try_reserve_specific_reg(target_reg)?;
-                    }
+            Ok(decl) => {
+                let load_bc = match (decl.env_slot, decl.spill_slot) {
+                    (Some(env_slot), _) => self.compile_load_captured(env_slot, target_reg)?,
+                    (None, Some(spill_slot)) => Bytecode::new().add(Operation::new(Instruction::LoadSpilled, vec![
+                        Operand::Reg(target_reg), Operand::LongNum(i32::from(spill_slot))
+                    ])),
+                    (None, None) => self.compile_operand_assignment(target_reg, Operand::Reg(decl.register))?
+                };
 
-                    self.decl_dependencies.add_decl_dep(ident.to_string(), target_reg);
-                    Ok(Bytecode::new())
-                },
+                Ok(load_bc.add_bytecode(self.compile_tdz_check(&decl, target_reg)))
+            },
+            Err(_) if self.scopes.get_func_decl(ident).is_some() =>
+                Err(CompilerError::Custom(format!("The function declaration '{}' is not bound to a declaration", ident))),
+            Err(_) => {
+                let dep_reg = self.get_or_add_decl_dep(ident)?;
+                self.compile_operand_assignment(target_reg, Operand::Reg(dep_reg))
             }
         }
     }
 
+    /// Returns the register of an external declaration
+    ///
+    /// External declarations are placed in registers shared by all frames, thus they
+    /// can be accessed from every bytecode function.
+    fn get_or_add_decl_dep(&mut self, ident: &Identifier) -> CompilerResult<Reg> {
+        if let Some(&reg) = self.decl_dependencies.try_get_dep(ident) {
+            return Ok(reg);
+        }
+
+        let reg = self.scopes.reserve_shared_register()?;
+        self.decl_dependencies.add_decl_dep(ident.to_string(), reg);
+        Ok(reg)
+    }
+
     fn compile_literal_expr(&mut self, lit: &Literal, target_reg: Reg) -> BytecodeResult {
+        match BytecodeLiteral::from_lit(lit.clone())? {
+            // Every evaluation of a regex literal creates a new object, thus it is never cached
+            BytecodeLiteral::RegEx(pattern, flags) => {
+                return Ok(Bytecode::new().add(Operation::new(Instruction::LoadRegExp, vec![
+                    Operand::Reg(target_reg),
+                    Operand::String(pattern),
+                    Operand::String(flags)
+                ])));
+            },
+            // There are no boolean operands, thus booleans are loaded by negating a number
+            BytecodeLiteral::Bool(boolean) => {
+                let negated = if boolean { CommonLiteral::Num0 } else { CommonLiteral::Num1 };
+                return Ok(Bytecode::new().add(Operation::new(Instruction::Not, vec![
+                    Operand::Reg(target_reg),
+                    Operand::Reg(self.isa.common_literal_reg(&negated))
+                ])));
+            },
+            _ => {}
+        }
+
         let operand = Operand::from_literal(BytecodeLiteral::from_lit(lit.clone())?)?;
         // This feature is currenlty disabled
         if false { // operand.is_worth_caching()
@@ -774,20 +2432,45 @@ impl BytecodeCompiler {
     }
 
     fn compile_member_expr(&mut self, member: &MemberExpr) -> CompilerResult<(Bytecode, Reg, Reg)> {
-        let (obj_bc, obj_reg) = self.maybe_compile_expr(member.object.borrow(), None)?;
-        let (prop_bc, prop_reg) = if member.computed {
-            self.maybe_compile_expr(member.property.borrow(), None)?
+        self.compile_member_operands(member, false)
+    }
+
+    /// Compiles the object and the key of a member expression, which are read after the following operands are evaluated
+    fn compile_member_operands(&mut self, member: &MemberExpr, is_followed_by_side_effects: bool) -> CompilerResult<(Bytecode, Reg, Reg)> {
+        if let Expr::Super = member.object.borrow() {
+            return Err(CompilerError::are_unsupported("Modifications of 'super' properties"));
+        }
+
+        let is_key_followed_by_side_effects = is_followed_by_side_effects && member.computed;
+        let is_object_followed_by_side_effects = is_followed_by_side_effects ||
+            (member.computed && !Self::is_free_of_side_effects(member.property.borrow()));
+
+        let (obj_bc, obj_reg) = self.maybe_compile_operand(member.object.borrow(), is_object_followed_by_side_effects)?;
+        let (prop_bc, prop_reg) = if is_key_followed_by_side_effects {
+            self.maybe_compile_operand(member.property.borrow(), true)?
         } else {
-            match member.property.borrow() {
-                Expr::Ident(ident) => self.maybe_compile_expr(&Expr::Literal(Literal::String(format!("\"{}\"", ident))), None)?,
-                _ => self.maybe_compile_expr(member.property.borrow(), None)?
-            }
+            self.compile_member_property(member)?
         };
 
         Ok((obj_bc.add_bytecode(prop_bc), obj_reg, prop_reg))
     }
 
+    fn compile_member_property(&mut self, member: &MemberExpr) -> CompilerResult<(Bytecode, Reg)> {
+        if member.computed {
+            self.maybe_compile_expr(member.property.borrow(), None)
+        } else {
+            match member.property.borrow() {
+                Expr::Ident(ident) => self.maybe_compile_expr(&Expr::Literal(Literal::String(format!("\"{}\"", ident))), None),
+                _ => self.maybe_compile_expr(member.property.borrow(), None)
+            }
+        }
+    }
+
     fn compile_member_expr_access(&mut self, member: &MemberExpr, target_reg: Reg) -> BytecodeResult {
+        if let Expr::Super = member.object.borrow() {
+            return self.compile_super_member_access(member, target_reg);
+        }
+
         let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
 
         Ok(member_bc
@@ -797,73 +2480,670 @@ impl BytecodeCompiler {
             )))
     }
 
-    fn compile_update_expr(&mut self, update: &UpdateExpr, _target_reg: Reg) -> BytecodeResult {
-        if update.prefix {
-            let (arg_bc, arg_reg) = self.maybe_compile_expr(update.argument.borrow(), None)?;
-            Ok(arg_bc.add(self.isa.update_op(&update.operator, arg_reg)))
+    /// Compiles ``super.prop``, which is looked up in the parent of the member's home object
+    ///
+    /// The home object of methods is the prototype of their class, the one of static methods the class itself.
+    fn compile_super_member_access(&mut self, member: &MemberExpr, target_reg: Reg) -> BytecodeResult {
+        let is_static = match self.class_member {
+            Some(ClassMember::Method { is_static }) => is_static,
+            Some(ClassMember::Constructor { .. }) | Some(ClassMember::DerivedConstructorArrow) => false,
+            None => { return Err(CompilerError::Custom("'super' is only valid in methods and constructors of classes".into())); }
+        };
+
+        let home_reg = self.scopes.reserve_temp_register()?;
+        let mut bytecode = self.compile_identifier_expr(&CLASS_BINDING.to_string(), home_reg)?;
+        if !is_static {
+            let (key_bc, key_reg) = self.compile_property_key(&PropertyKey::Expr(Expr::Ident("prototype".into())), false)?;
+            bytecode = bytecode
+                .add_bytecode(key_bc)
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(home_reg), Operand::Reg(home_reg), Operand::Reg(key_reg)]));
+        }
+
+        let (prop_bc, prop_reg) = self.compile_member_property(member)?;
+
+        Ok(bytecode
+            .add_bytecode(prop_bc)
+            .add(Operation::new(Instruction::SuperPropAccess, vec![
+                Operand::Reg(target_reg), Operand::Reg(home_reg), Operand::Reg(prop_reg)
+            ])))
+    }
+
+    /// Compiles ``super(...)``, which initializes ``this`` by calling the constructor of the parent class
+    fn compile_super_call(&mut self, args: &[Expr], target_reg: Reg) -> BytecodeResult {
+        if self.class_member == Some(ClassMember::DerivedConstructorArrow) {
+            return Err(CompilerError::are_unsupported("super() calls in arrow functions"));
+        }
+        if !self.is_in_derived_constructor() {
+            return Err(CompilerError::Custom("'super' can only be called in constructors of derived classes".into()));
+        }
+
+        let (args_bc, args_reg) = self.compile_spread_arguments(args)?;
+        let class_reg = self.scopes.reserve_temp_register()?;
+
+        Ok(args_bc
+            .add_bytecode(self.compile_identifier_expr(&CLASS_BINDING.to_string(), class_reg)?)
+            .add(Operation::new(Instruction::SuperCall, vec![
+                Operand::Reg(target_reg), Operand::Reg(class_reg), Operand::Reg(args_reg)
+            ])))
+    }
+
+    fn compile_object_expr(&mut self, object: &ObjectExpr, target_reg: Reg) -> BytecodeResult {
+        // Large objects are built in a temporary register, which their values cannot read,
+        // thus each property can be defined as soon as its value is computed
+        let is_chunked = object.len() > MAX_CHUNK_SIZE;
+        let object_reg = if is_chunked { self.scopes.reserve_temp_register()? } else { target_reg };
+        let mut bytecode = Bytecode::new();
+        if is_chunked {
+            bytecode = bytecode.add(Operation::new(Instruction::CreateObject, vec![Operand::Reg(object_reg)]));
+        }
+        let mut definitions = vec![];
+
+        for (i, obj_prop) in object.iter().enumerate() {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let prop = match obj_prop {
+                ObjectProperty::Property(prop) => prop,
+                ObjectProperty::Spread(_) => { return Err(CompilerError::are_unsupported("spread properties in object literals")); }
+            };
+
+            // Unless the object is chunked, keys and values are read after all properties are evaluated
+            let is_followed_by_side_effects = !is_chunked && object[i + 1..].iter().any(Self::has_property_side_effects);
+            let is_key_followed_by_side_effects = is_followed_by_side_effects || match &prop.value {
+                PropertyValue::Expr(expr) => !Self::is_free_of_side_effects(expr),
+                _ => false
+            };
+
+            let is_proto_setter = Self::is_proto_setter(prop);
+            let (key_bc, key_reg) = match &prop.key {
+                _ if is_proto_setter => (Bytecode::new(), self.isa.common_literal_reg(&CommonLiteral::Void0)),
+                PropertyKey::Expr(key_expr) if prop.computed => self.maybe_compile_operand(key_expr, is_key_followed_by_side_effects)?,
+                _ => self.compile_property_key(&prop.key, prop.computed)?
+            };
+
+            let (value_bc, value_reg) = match (&prop.value, &prop.kind) {
+                (PropertyValue::Expr(Expr::Function(func)), _) if prop.method || prop.kind != PropertyKind::Init => {
+                    let value_reg = self.scopes.reserve_temp_register()?;
+                    (self.compile_func_expr(func, value_reg)?, value_reg)
+                },
+                (PropertyValue::Expr(expr), PropertyKind::Init) => self.maybe_compile_operand(expr, is_followed_by_side_effects)?,
+                (PropertyValue::None, PropertyKind::Init) if prop.short_hand => match &prop.key {
+                    PropertyKey::Expr(key_expr) => self.maybe_compile_operand(key_expr, is_followed_by_side_effects)?,
+                    _ => { return Err(CompilerError::Custom("Shorthand properties require an identifier as key".into())); }
+                },
+                _ => { return Err(CompilerError::is_unsupported("Object property", prop)); }
+            };
+
+            let instruction = match prop.kind {
+                PropertyKind::Get => Instruction::DefineGetter,
+                PropertyKind::Set => Instruction::DefineSetter,
+                _ if is_proto_setter => Instruction::SetPrototype,
+                _ => Instruction::DefineProperty
+            };
+
+            bytecode = bytecode.add_bytecode(key_bc).add_bytecode(value_bc);
+            if is_chunked {
+                bytecode = bytecode.add(Self::property_definition(instruction, object_reg, key_reg, value_reg));
+                self.scopes.release_temp_registers(temp_regs_marker)?;
+            } else {
+                definitions.push((instruction, key_reg, value_reg));
+            }
+        }
+
+        // The object must not replace a key or a value, e.g. of 'a' in ``a = {b: a}``, before it is defined
+        let is_aliased = definitions.iter().any(|&(_, key_reg, value_reg)| key_reg == target_reg || value_reg == target_reg);
+        let object_reg = if !is_chunked && is_aliased { self.scopes.reserve_temp_register()? } else { object_reg };
+        if !is_chunked {
+            bytecode = definitions.into_iter().fold(
+                bytecode.add(Operation::new(Instruction::CreateObject, vec![Operand::Reg(object_reg)])),
+                |bytecode, (instruction, key_reg, value_reg)| bytecode.add(Self::property_definition(instruction, object_reg, key_reg, value_reg))
+            );
+        }
+
+        if object_reg == target_reg {
+            Ok(bytecode)
+        } else {
+            Ok(bytecode.add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(object_reg))?))
+        }
+    }
+
+    /// Whether ``prop`` is a ``__proto__: value`` definition, which sets the prototype of the object instead
+    fn is_proto_setter(prop: &Property) -> bool {
+        let is_proto_key = match &prop.key {
+            PropertyKey::Expr(Expr::Ident(ident)) => ident == "__proto__",
+            PropertyKey::Literal(Literal::String(string)) => string == "\"__proto__\"" || string == "'__proto__'",
+            _ => false
+        };
+
+        is_proto_key && !prop.computed && !prop.short_hand && !prop.method && prop.kind == PropertyKind::Init
+    }
+
+    /// Defines the property ``key_reg`` of ``object_reg``, a prototype has no key
+    fn property_definition(instruction: Instruction, object_reg: Reg, key_reg: Reg, value_reg: Reg) -> Operation {
+        match instruction {
+            Instruction::SetPrototype => Operation::new(instruction, vec![Operand::Reg(object_reg), Operand::Reg(value_reg)]),
+            _ => Operation::new(instruction, vec![Operand::Reg(object_reg), Operand::Reg(key_reg), Operand::Reg(value_reg)])
+        }
+    }
+
+    fn has_property_side_effects(obj_prop: &ObjectProperty) -> bool {
+        match obj_prop {
+            ObjectProperty::Property(prop) => {
+                let key_has_side_effects = match &prop.key {
+                    PropertyKey::Expr(key_expr) => prop.computed && !Self::is_free_of_side_effects(key_expr),
+                    _ => false
+                };
+                let value_has_side_effects = match &prop.value {
+                    PropertyValue::Expr(expr) => !Self::is_free_of_side_effects(expr),
+                    _ => false
+                };
+                key_has_side_effects || value_has_side_effects
+            },
+            ObjectProperty::Spread(_) => true
+        }
+    }
+
+    fn compile_property_key(&mut self, key: &PropertyKey, computed: bool) -> CompilerResult<(Bytecode, Reg)> {
+        match key {
+            PropertyKey::Expr(Expr::Ident(ident)) if !computed => {
+                self.maybe_compile_expr(&Expr::Literal(Literal::String(format!("\"{}\"", ident))), None)
+            },
+            PropertyKey::Expr(expr) => self.maybe_compile_expr(expr, None),
+            PropertyKey::Literal(lit) => self.maybe_compile_expr(&Expr::Literal(lit.clone()), None),
+            PropertyKey::Pat(_) => Err(CompilerError::are_unsupported("Patterns as property keys"))
+        }
+    }
+
+    /// Compiles a class declaration or expression into the constructor ``target_reg``
+    ///
+    /// The constructor is a bytecode function, methods and accessors are defined on its prototype,
+    /// static ones on the constructor itself. Anonymous classes are named after the identifier
+    /// ``binding_name`` they are assigned to.
+    fn compile_class(&mut self, class: &Class, binding_name: Option<&str>, target_reg: Reg) -> BytecodeResult {
+        // The name of a class is bound inside of its body, 'super' refers to the hidden binding
+        self.scopes.enter_new_block_scope()?;
+        let has_class_binding = self.scopes.current_scope()?.is_captured(CLASS_BINDING);
+        let class_decls: Vec<(String, MyVariableKind)> = class.id.iter().cloned()
+            .chain(Some(CLASS_BINDING.to_string()).filter(|_| has_class_binding))
+            .map(|name| (name, MyVariableKind::Const))
+            .collect();
+        let (entry_bc, has_env) = self.compile_lexical_scope_entry(&class_decls)?;
+        if has_class_binding {
+            // Methods cannot run before the class is defined, thus the accesses to its hidden binding need no checks
+            self.scopes.initialize_decl(CLASS_BINDING)?;
+        }
+
+        let (heritage_bc, parent_reg) = match &class.super_class {
+            Some(super_class) => {
+                let (heritage_bc, parent_reg) = self.maybe_compile_expr(super_class, None)?;
+                (heritage_bc, Some(parent_reg))
+            },
+            None => (Bytecode::new(), None)
+        };
+        let is_derived = parent_reg.is_some();
+
+        let default_constructor = Self::default_constructor(is_derived);
+        let constructor = match class.body.iter().find(|member| member.kind == PropertyKind::Ctor).map(|member| &member.value) {
+            Some(PropertyValue::Expr(Expr::Function(func))) => func,
+            Some(value) => { return Err(CompilerError::is_unsupported("Class constructor", value)); },
+            None => &default_constructor
+        };
+        let constructor_ident = self.generate_anonymous_func_ident();
+        self.compile_bytecode_func(constructor_ident.clone(), constructor.into(),
+                                   FunctionKind::ClassMember(ClassMember::Constructor { is_derived }))?;
+
+        let class_reg = self.scopes.reserve_temp_register()?;
+        let mut bytecode = entry_bc
+            .add_bytecode(heritage_bc)
+            .add_bytecode(self.compile_bytecode_func_callback(&constructor_ident, class_reg)?);
+        if let Some(parent_reg) = parent_reg {
+            bytecode = bytecode.add(Operation::new(Instruction::ExtendClass, vec![Operand::Reg(class_reg), Operand::Reg(parent_reg)]));
+        }
+        // Static members might replace the name afterwards
+        if let Some(name) = class.id.as_deref().or(binding_name) {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let (name_bc, name_reg) = self.compile_property_key(&PropertyKey::Expr(Expr::Ident(name.into())), false)?;
+            bytecode = bytecode
+                .add_bytecode(name_bc)
+                .add(Operation::new(Instruction::DefineClassName, vec![Operand::Reg(class_reg), Operand::Reg(name_reg)]));
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+        }
+
+        let prototype_reg = self.scopes.reserve_temp_register()?;
+        if class.body.iter().any(|member| member.kind != PropertyKind::Ctor && !self.static_members.contains(member)) {
+            let (key_bc, key_reg) = self.compile_property_key(&PropertyKey::Expr(Expr::Ident("prototype".into())), false)?;
+            bytecode = bytecode
+                .add_bytecode(key_bc)
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(prototype_reg), Operand::Reg(class_reg), Operand::Reg(key_reg)]));
+        }
+
+        for member in class.body.iter().filter(|member| member.kind != PropertyKind::Ctor) {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            let is_static = self.static_members.contains(member);
+            let func = match &member.value {
+                PropertyValue::Expr(Expr::Function(func)) => func,
+                _ => { return Err(CompilerError::is_unsupported("Class member", member)); }
+            };
+
+            let (key_bc, key_reg) = self.compile_property_key(&member.key, member.computed)?;
+            let func_ident = self.generate_anonymous_func_ident();
+            self.compile_bytecode_func(func_ident.clone(), func.into(), FunctionKind::ClassMember(ClassMember::Method { is_static }))?;
+            let func_reg = self.scopes.reserve_temp_register()?;
+
+            let instruction = match member.kind {
+                PropertyKind::Get => Instruction::DefineMethodGetter,
+                PropertyKind::Set => Instruction::DefineMethodSetter,
+                _ => Instruction::DefineMethod
+            };
+            let home_reg = if is_static { class_reg } else { prototype_reg };
+
+            bytecode = bytecode
+                .add_bytecode(key_bc)
+                .add_bytecode(self.compile_bytecode_func_callback(&func_ident, func_reg)?)
+                .add(Operation::new(instruction, vec![Operand::Reg(home_reg), Operand::Reg(key_reg), Operand::Reg(func_reg)]));
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+        }
+
+        for (name, kind) in class_decls.iter() {
+            let binding = PatternBinding::Declaration(DeclarationType::Variable(kind.clone()));
+            bytecode = bytecode.add_bytecode(self.compile_pattern_ident(name, class_reg, &binding)?);
+        }
+        self.scopes.leave_current_block_scope()?;
+
+        Ok(bytecode
+            .add_bytecode(self.compile_leave_envs(has_env as u8))
+            .add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(class_reg))?))
+    }
+
+    /// Returns the constructor of classes without one
+    ///
+    /// Derived classes pass all their arguments to the parent constructor: ``constructor(...args) { super(...args); }``
+    fn default_constructor(is_derived: bool) -> Function {
+        if !is_derived {
+            return Function::new(None, vec![], vec![], false, false);
+        }
+
+        let args = Identifier::from("args");
+        let super_call = Expr::Call(CallExpr {
+            callee: Box::new(Expr::Super),
+            arguments: vec![Expr::Spread(Box::new(Expr::Ident(args.clone())))]
+        });
+
+        Function::new(None, vec![FunctionArg::Pat(Pat::RestElement(Box::new(Pat::Identifier(args))))],
+                      vec![ProgramPart::Stmt(Stmt::Expr(super_call))], false, false)
+    }
+
+    fn compile_update_expr(&mut self, update: &UpdateExpr, target_reg: Reg) -> BytecodeResult {
+        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+
+        match update.argument.borrow() {
+            Expr::Ident(ident) => {
+                let (arg_bc, arg_reg) = if self.is_decl_in_register(ident) {
+                    self.compile_assignment_target(ident)?
+                } else {
+                    self.check_assignable(ident)?;
+                    self.maybe_compile_expr(update.argument.borrow(), None)?
+                };
+                let update_bc = self.compile_update_op(update, arg_reg, target_reg);
+
+                Ok(arg_bc.add_bytecode(update_bc).add_bytecode(self.compile_decl_store(ident, arg_reg)?))
+            },
+            Expr::Member(member) => {
+                let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
+                let value_reg = self.scopes.reserve_temp_register()?;
+                // The target is written last, as it might be the object or property register
+                let result_reg = if target_reg == trash_reg {
+                    trash_reg
+                } else if update.prefix {
+                    value_reg
+                } else {
+                    self.scopes.reserve_temp_register()?
+                };
+
+                let bytecode = member_bc
+                    .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(value_reg), Operand::Reg(obj_reg), Operand::Reg(prop_reg)]))
+                    .add_bytecode(self.compile_update_op(update, value_reg, result_reg))
+                    .add(Operation::new(Instruction::PropertySet, vec![Operand::Reg(obj_reg), Operand::Reg(prop_reg), Operand::Reg(value_reg)]));
+
+                if result_reg == trash_reg {
+                    Ok(bytecode)
+                } else {
+                    Ok(bytecode.add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(result_reg))?))
+                }
+            },
+            _ => Err(CompilerError::Custom("Invalid left-hand side in update expression".into()))
+        }
+    }
+
+    /// Updates ``value_reg`` in place and writes the value of the update expression into ``result_reg``
+    fn compile_update_op(&self, update: &UpdateExpr, value_reg: Reg, result_reg: Reg) -> Bytecode {
+        let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+        let num0_reg = self.isa.common_literal_reg(&CommonLiteral::Num0);
+        let update_bc = Bytecode::new().add(self.isa.update_op(&update.operator, value_reg, value_reg));
+
+        if result_reg == trash_reg {
+            update_bc
+        } else if update.prefix {
+            if result_reg == value_reg {
+                update_bc
+            } else {
+                update_bc.add(self.isa.load_op(result_reg, Operand::Reg(value_reg)))
+            }
         } else {
-            Err(CompilerError::are_unsupported("suffix update expressions"))
+            // Postfix updates result in the old value converted to a number (value - 0)
+            let to_number_op = Operation::new(Instruction::Minus, vec![Operand::Reg(result_reg), Operand::Reg(value_reg), Operand::Reg(num0_reg)]);
+
+            if result_reg == value_reg {
+                // e.g. 'x = x++', where the old value is assigned again
+                Bytecode::new().add(to_number_op)
+            } else {
+                Bytecode::new().add(to_number_op).add(self.isa.update_op(&update.operator, value_reg, result_reg))
+            }
         }
     }
 
     fn compile_unary_expr(&mut self, unary: &UnaryExpr, target_reg: Reg) -> BytecodeResult {
         if unary.prefix {
-            if UnaryOperator::Void == unary.operator {
-                let (arg_bc, _) = self.maybe_compile_expr(unary.argument.borrow(), None)?;
-                let void0_reg = self.isa.common_literal_reg(&CommonLiteral::Void0);
-                Ok(arg_bc
-                    .add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(void0_reg))?))
-            } else {
-                let (arg_bc, arg_reg) = self.maybe_compile_expr(unary.argument.borrow(), None)?;
-                Ok(arg_bc.add(self.isa.unary_op(&unary.operator, target_reg, arg_reg)?))
+            match unary.operator {
+                UnaryOperator::Void => {
+                    let (arg_bc, _) = self.maybe_compile_expr(unary.argument.borrow(), None)?;
+                    let void0_reg = self.isa.common_literal_reg(&CommonLiteral::Void0);
+                    Ok(arg_bc
+                        .add_bytecode(self.compile_operand_assignment(target_reg, Operand::Reg(void0_reg))?))
+                },
+                UnaryOperator::Delete => self.compile_delete_expr(unary.argument.borrow(), target_reg),
+                _ => {
+                    let (arg_bc, arg_reg) = self.maybe_compile_expr(unary.argument.borrow(), None)?;
+                    Ok(arg_bc.add(self.isa.unary_op(&unary.operator, target_reg, arg_reg)?))
+                }
             }
         } else {
             Err(CompilerError::are_unsupported("suffix unary expressions"))
         }
     }
 
-    fn compile_func(&mut self, func: &Function) -> BytecodeResult {
-        if func.generator || func.is_async {
-            return Err(CompilerError::are_unsupported("generator and async functions"))
+    fn compile_delete_expr(&mut self, argument: &Expr, target_reg: Reg) -> BytecodeResult {
+        match argument {
+            Expr::Member(member) => {
+                let (member_bc, obj_reg, prop_reg) = self.compile_member_expr(member)?;
+                Ok(member_bc.add(Operation::new(Instruction::DeleteProperty,
+                                    vec![Operand::Reg(target_reg), Operand::Reg(obj_reg), Operand::Reg(prop_reg)])))
+            },
+            Expr::Ident(_) => Err(CompilerError::are_unsupported("'delete' on identifiers")),
+            // Deleting anything but a reference always results in true (!0)
+            _ => {
+                let (arg_bc, _) = self.maybe_compile_expr(argument, None)?;
+                let num0_reg = self.isa.common_literal_reg(&CommonLiteral::Num0);
+                Ok(arg_bc.add(Operation::new(Instruction::Not, vec![Operand::Reg(target_reg), Operand::Reg(num0_reg)])))
+            }
         }
+    }
+
+    /// Compiles function expressions as well as methods, getters and setters of object literals.
+    ///
+    /// The function is compiled under a generated identifier and is loaded into ``target_reg``
+    /// as a callback, since it has no name it can be called by.
+    fn compile_func_expr(&mut self, func: &Function, target_reg: Reg) -> BytecodeResult {
+        let func_ident = self.generate_anonymous_func_ident();
 
-        let func_ident =  match &func.id {
-            Some(ident) => ident.to_string(),
-            None => { return Err(CompilerError::are_unsupported("anonymous functions")); }
+        self.compile_bytecode_func(func_ident.clone(), func.into(), FunctionKind::Function)?;
+        self.compile_bytecode_func_callback(&func_ident, target_reg)
+    }
+
+    fn compile_arrow_func_expr(&mut self, arrow: &ArrowFunctionExpr, target_reg: Reg) -> BytecodeResult {
+        let body = match &arrow.body {
+            ArrowFunctionBody::FunctionBody(body) => body,
+            // Expression bodies are turned into blocks by the parser workarounds
+            ArrowFunctionBody::Expr(_) => { return Err(CompilerError::Custom("The body of an arrow function is not a block".into())); }
         };
+        let func = FunctionRef { id: None, params: &arrow.params, body, generator: arrow.generator, is_async: arrow.is_async };
+        let func_ident = self.generate_anonymous_func_ident();
+
+        self.compile_bytecode_func(func_ident.clone(), func, FunctionKind::Arrow)?;
+        self.compile_bytecode_func_callback(&func_ident, target_reg)
+    }
 
-        self.scopes.enter_new_scope()?;
+    fn generate_anonymous_func_ident(&mut self) -> Identifier {
+        // '#' cannot be part of a JavaScript identifier, thus this never collides with named functions
+        let ident = format!("#anonymous{}", self.funcs_counter);
+        self.funcs_counter += 1;
+        ident
+    }
+
+    /// Generates the identifier of a function declaration, which is unique even if functions of other scopes share its name
+    fn generate_func_decl_ident(&mut self, name: &str) -> Identifier {
+        let ident = format!("{}#{}", name, self.funcs_counter);
+        self.funcs_counter += 1;
+        ident
+    }
 
-        let arg_regs = func.params.iter().map(|param| {
-            match param {
-                FunctionArg::Expr(expr) => match expr {
-                    Expr::Ident(ident) => self.scopes.add_decl(ident.to_string(), DeclarationType::Function),
-                    _ => Err(CompilerError::Custom("Only identifiers are accepted as function arguments".into()))
+    fn compile_bytecode_func_callback(&self, func_ident: &str, target_reg: Reg) -> BytecodeResult {
+        let func = self.functions.iter().find(|func| func.ident == func_ident).ok_or(
+            CompilerError::Custom(format!("The bytecode function '{}' does not exist", func_ident))
+        )?;
+
+        let mut operands = vec![
+            Operand::Reg(target_reg),
+            Operand::function_addr(func_ident.to_string()),
+            Operand::RegistersArray(func.arguments.clone()),
+            Operand::ShortNum(self.func_env_hops(func_ident)?)
+        ];
+
+        let instruction = match func.kind {
+            FunctionKind::Arrow if func.is_async => Instruction::BytecodeAsyncArrowFuncCallback,
+            FunctionKind::Arrow => Instruction::BytecodeArrowFuncCallback,
+            FunctionKind::ClassMember(ClassMember::Constructor { is_derived }) => {
+                operands.push(Operand::ShortNum(is_derived as u8));
+                Instruction::BytecodeClassCallback
+            },
+            _ if func.is_generator => Instruction::BytecodeGeneratorCallback,
+            _ if func.is_async => Instruction::BytecodeAsyncFuncCallback,
+            _ => Instruction::BytecodeFuncCallback
+        };
+
+        Ok(Bytecode::new().add(Operation::new(instruction, operands)))
+    }
+
+    /// Returns the number of environments between the current one and the one of ``func_ident``'s definition
+    fn func_env_hops(&self, func_ident: &str) -> CompilerResult<u8> {
+        let func = self.functions.iter().find(|func| func.ident == func_ident).ok_or(
+            CompilerError::Custom(format!("The bytecode function '{}' does not exist", func_ident))
+        )?;
+
+        self.scopes.current_scope()?.env_depth().checked_sub(func.env_depth).ok_or(
+            CompilerError::Custom(format!("The function '{}' is not visible in this block", func_ident))
+        )
+    }
+
+    fn is_decl_in_register(&self, ident: &str) -> bool {
+        match self.scopes.get_var(ident) {
+            Ok(decl) => decl.is_in_register(),
+            Err(_) => true
+        }
+    }
+
+    fn compile_env_creation(&self, needs_env: bool) -> Bytecode {
+        if needs_env {
+            Bytecode::new().add(Operation::new(Instruction::CreateEnvironment, vec![]))
+        } else {
+            Bytecode::new()
+        }
+    }
+
+    fn compile_load_captured(&self, env_slot: EnvSlot, target_reg: Reg) -> BytecodeResult {
+        let env_hops = self.scopes.current_scope()?.env_depth() - env_slot.depth;
+
+        Ok(Bytecode::new().add(Operation::new(Instruction::LoadCaptured, vec![
+            Operand::Reg(target_reg), Operand::ShortNum(env_hops), Operand::ShortNum(env_slot.index)
+        ])))
+    }
+
+    /// Stores ``value_reg`` to the environment or the spill slot of the declaration ``ident``
+    ///
+    /// Declarations living in a register need no store, thus the bytecode is empty for them.
+    fn compile_decl_store(&mut self, ident: &str, value_reg: Reg) -> BytecodeResult {
+        let (env_slot, spill_slot) = match self.scopes.get_var(ident) {
+            Ok(decl) => (decl.env_slot, decl.spill_slot),
+            Err(_) => (None, None)
+        };
+
+        self.compile_slot_store(env_slot, spill_slot, value_reg)
+    }
+
+    /// Stores ``value_reg`` in the environment slot or the spill slot of a declaration
+    fn compile_slot_store(&self, env_slot: Option<EnvSlot>, spill_slot: Option<u16>, value_reg: Reg) -> BytecodeResult {
+        match (env_slot, spill_slot) {
+            (Some(env_slot), _) => {
+                let env_hops = self.scopes.current_scope()?.env_depth() - env_slot.depth;
+                Ok(Bytecode::new().add(Operation::new(Instruction::StoreCaptured, vec![
+                    Operand::ShortNum(env_hops), Operand::ShortNum(env_slot.index), Operand::Reg(value_reg)
+                ])))
+            },
+            (None, Some(spill_slot)) => Ok(Bytecode::new().add(Operation::new(Instruction::StoreSpilled, vec![
+                Operand::LongNum(i32::from(spill_slot)), Operand::Reg(value_reg)
+            ]))),
+            (None, None) => Ok(Bytecode::new())
+        }
+    }
+
+    fn compile_bytecode_func(&mut self, func_ident: Identifier, func: FunctionRef, kind: FunctionKind) -> CompilerResult<()> {
+        let func_idx = self.declare_bytecode_func(func_ident, func, kind)?;
+        self.compile_declared_bytecode_func(func_idx, func, true)
+    }
+
+    /// Adds a phantom of a function, which is compiled later
+    ///
+    /// The function can be called and referenced as soon as it is declared. Functions are executed
+    /// in their own frame, thus their arguments are always passed in the first frame registers.
+    /// # Returns
+    /// The index of the phantom in the function list.
+    fn declare_bytecode_func(&mut self, func_ident: Identifier, func: FunctionRef, kind: FunctionKind) -> CompilerResult<usize> {
+        if func.generator && func.is_async {
+            return Err(CompilerError::are_unsupported("async generator functions"))
+        }
+
+        // The rest parameter has no argument register
+        let args_count = func.params.iter().filter(|param| !matches!(param, FunctionArg::Pat(Pat::RestElement(_)))).count();
+        if args_count > usize::from(FRAME_REGISTERS_END) {
+            return Err(CompilerError::Custom(format!("The function '{}' has too many parameters", func_ident)));
+        }
+
+        let env_depth = self.scopes.current_scope()?.env_depth();
+        let arg_regs = (0..args_count).map(|reg| reg as Register).collect();
+        self.functions.push(BytecodeFunction::new_phantom(func_ident, arg_regs, kind, func, env_depth));
+
+        Ok(self.functions.len() - 1)
+    }
+
+    /// Compiles the body of the declared function ``func_idx``
+    ///
+    /// The name of a function expression is only visible inside of its body, thus it ``binds_own_name``.
+    /// The name of a function declaration is bound by the enclosing scope instead.
+    fn compile_declared_bytecode_func(&mut self, func_idx: usize, func: FunctionRef, binds_own_name: bool) -> CompilerResult<()> {
+        let func_ident = self.functions[func_idx].ident.clone();
+        let kind = self.functions[func_idx].kind;
+
+        let class_member = match kind {
+            FunctionKind::Function => None,
+            // Arrow functions use the 'super' of their enclosing function, but cannot call the parent constructor
+            FunctionKind::Arrow => self.class_member.map(|member| match member {
+                ClassMember::Constructor { is_derived: true } => ClassMember::DerivedConstructorArrow,
+                ClassMember::Constructor { is_derived: false } => ClassMember::Method { is_static: false },
+                member => member
+            }),
+            FunctionKind::ClassMember(member) => Some(member)
+        };
+        let enclosing_class_member = std::mem::replace(&mut self.class_member, class_member);
+        let enclosing_is_generator = std::mem::replace(&mut self.is_generator, func.generator);
+        let enclosing_is_async = std::mem::replace(&mut self.is_async, func.is_async);
+        let enclosing_finally_blocks = std::mem::take(&mut self.finally_blocks);
+
+        let self_binding = func.id.filter(|_| binds_own_name);
+
+        let needs_env = self.scopes.enter_new_func_scope(captured_identifiers(func.params, func.body))?;
+        let mut prologue_bc = self.compile_env_creation(needs_env);
+
+        let mut arg_regs = vec![];
+        let mut arg_patterns = vec![];
+        for (idx, param) in func.params.iter().enumerate() {
+            let pattern = match param {
+                FunctionArg::Expr(Expr::Ident(ident)) |
+                FunctionArg::Pat(Pat::Identifier(ident)) => {
+                    let arg_reg = self.scopes.add_decl(ident.to_string(), DeclarationType::Function)?;
+                    prologue_bc = prologue_bc.add_bytecode(self.compile_decl_store(ident, arg_reg)?);
+                    arg_regs.push(arg_reg);
+                    continue;
                 },
-                FunctionArg::Pat(pat) => match pat {
-                    Pat::Identifier(ident) => self.scopes.add_decl(ident.to_string(), DeclarationType::Function),
-                    _ => Err(CompilerError::Custom("Only identifiers are accepted as function arguments".into()))
-                }
-            }
-        }).collect::<CompilerResult<Vec<Register>>>()?;
+                FunctionArg::Pat(Pat::RestElement(pat)) => {
+                    if idx + 1 != func.params.len() {
+                        return Err(CompilerError::Custom("A rest parameter must be the last parameter".into()));
+                    }
+
+                    // The rest parameter has no argument register, it collects the arguments following the others
+                    let rest_reg = self.scopes.reserve_register()?;
+                    prologue_bc = prologue_bc.add(Operation::new(Instruction::LoadRestArguments,
+                                                                 vec![Operand::Reg(rest_reg), Operand::ShortNum(arg_regs.len() as u8)]));
+                    arg_patterns.push((PatternTarget::Pat(pat), rest_reg));
+                    continue;
+                },
+                FunctionArg::Pat(pat @ Pat::Array(_)) |
+                FunctionArg::Pat(pat @ Pat::Object(_)) |
+                FunctionArg::Pat(pat @ Pat::Assignment(_)) => PatternTarget::Pat(pat),
+                FunctionArg::Expr(expr @ Expr::Array(_)) |
+                FunctionArg::Expr(expr @ Expr::Object(_)) |
+                FunctionArg::Expr(expr @ Expr::Assignment(_)) => PatternTarget::Expr(expr),
+                _ => { return Err(CompilerError::Custom("Only identifiers, defaults and destructuring patterns are accepted as function arguments".into())); }
+            };
+
+            // The argument is passed in an anonymous register and destructured by the prologue
+            let arg_reg = self.scopes.reserve_register()?;
+            arg_patterns.push((pattern, arg_reg));
+            arg_regs.push(arg_reg);
+        }
+
+        debug_assert_eq!(arg_regs, self.functions[func_idx].arguments);
 
-        self.functions.push(BytecodeFunction::new_phantom(func_ident, arg_regs));
+        if let Some(name) = self_binding {
+            let self_reg = self.scopes.add_decl(name.to_string(), DeclarationType::Function)?;
+            prologue_bc = prologue_bc
+                .add_bytecode(self.compile_bytecode_func_callback(&func_ident, self_reg)?)
+                .add_bytecode(self.compile_decl_store(name, self_reg)?);
+        }
+
+        // Arrow functions use the 'arguments' object of their enclosing function
+        if kind != FunctionKind::Arrow && uses_arguments_object(func.params, func.body) {
+            let arguments_reg = self.scopes.add_decl("arguments".into(), DeclarationType::Variable(MyVariableKind::Var))?;
+            prologue_bc = prologue_bc
+                .add(Operation::new(Instruction::LoadArguments, vec![Operand::Reg(arguments_reg)]))
+                .add_bytecode(self.compile_decl_store("arguments", arguments_reg)?);
+        }
+
+        // Defaults and patterns are evaluated in order, once all arguments are in their registers
+        for (pattern, arg_reg) in arg_patterns {
+            let temp_regs_marker = self.scopes.temp_registers_marker()?;
+            prologue_bc = prologue_bc.add_bytecode(
+                self.compile_pattern(pattern, arg_reg, &PatternBinding::Declaration(DeclarationType::Function))?);
+            self.scopes.release_temp_registers(temp_regs_marker)?;
+        }
+
+        prologue_bc = prologue_bc.add_bytecode(self.compile_func_scope_decls(func.body, func.params)?);
 
         let mut func_bc = func.body.iter().map(|part| self.compile_program_part(&part))
                                    .collect::<BytecodeResult>()?;
+        func_bc = prologue_bc.add_bytecode(func_bc);
 
         if !func_bc.last_op_is_return() {
             func_bc = func_bc.add_bytecode(self.compile_return_stmt(&None)?)
         }
 
-        let func_scope = self.scopes.leave_current_scope()?;
-        let used_decls = func_scope.used_decls.into_iter().map(|used_decl| used_decl.register).collect();
+        self.scopes.leave_current_scope()?;
+        self.class_member = enclosing_class_member;
+        self.is_generator = enclosing_is_generator;
+        self.is_async = enclosing_is_async;
+        self.finally_blocks = enclosing_finally_blocks;
 
-        // It is save to unwrap here since it was definitly pushed above
-        let phantom_func = self.functions.pop().unwrap();
-        self.functions.push(BytecodeFunction::from_phantom(phantom_func, func_bc, used_decls));
+        let phantom_func = self.functions[func_idx].clone();
+        self.functions[func_idx] = BytecodeFunction::from_phantom(phantom_func, func_bc);
 
-        Ok(Bytecode::new())
+        Ok(())
     }
 
     fn finalize_label_addresses(&self, mut bc: Bytecode, offset: usize) -> BytecodeResult {
@@ -923,9 +3203,11 @@ impl BytecodeCompiler {
                 };
 
                 if let Operand::FunctionArguments(arg_regs) = args {
-                    cmd.operands[2] = Operand::RegistersArray(
-                        func.arguments.iter().zip(arg_regs.args.iter()).map(|(&a, &b)| vec![a, b]).flatten().collect()
-                    );
+                    // Every argument is passed, surplus ones are written to the trash register
+                    let trash_reg = self.isa.reserved_reg(&ReservedeRegister::TrashRegister);
+                    cmd.operands[2] = Operand::RegistersArray(arg_regs.args.iter().enumerate().map(|(idx, &arg_reg)| {
+                        vec![*func.arguments.get(idx).unwrap_or(&trash_reg), arg_reg]
+                    }).flatten().collect());
                 } else {
                     return Err(CompilerError::Custom(
                         "Bytecode function argument should be a bytecode func args placeholder".into()))
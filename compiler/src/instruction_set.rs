@@ -124,7 +124,11 @@ make_enum_helper!(
 enum ReservedeRegister {
     BytecodePointer,
     BytecodeFuncReturn,
-    TrashRegister
+    TrashRegister,
+    This,
+    Environment,
+    FramePointer,
+    Uninitialized
 });
 
 /// Represents a set of reserved registers
@@ -145,6 +149,10 @@ impl ReservedeRegisters {
                     ReservedeRegister::BytecodePointer => scope.try_reserve_specific_reg(200),
                     ReservedeRegister::BytecodeFuncReturn => scope.try_reserve_specific_reg(201),
                     ReservedeRegister::TrashRegister => scope.try_reserve_specific_reg(202),
+                    ReservedeRegister::This => scope.try_reserve_specific_reg(203),
+                    ReservedeRegister::Environment => scope.try_reserve_specific_reg(204),
+                    ReservedeRegister::FramePointer => scope.try_reserve_specific_reg(205),
+                    ReservedeRegister::Uninitialized => scope.try_reserve_specific_reg(206),
                     ReservedeRegister::__VarinatsCountHelper__ => panic!("ReservedeRegister::__VarinatsCountHelper__")
                 }
             }).collect::<CompilerResult<Vec<Register>>>()?
@@ -209,21 +217,20 @@ impl InstructionSet {
             AssignmentOperator::MinusEqual => Instruction::Minus,
             AssignmentOperator::TimesEqual => Instruction::Mul,
             AssignmentOperator::DivEqual => Instruction::Div,
-            // ModEqual,
-            // LeftShiftEqual,
-            // RightShiftEqual,
-            // UnsignedRightShiftEqual,
-            // OrEqual,
-            // XOrEqual,
-            // AndEqual,
-            // PowerOfEqual,
-            _ => unimplemented!("The correct branch for the assignment op ist not yet implemented")
+            AssignmentOperator::ModEqual => Instruction::Mod,
+            AssignmentOperator::LeftShiftEqual => Instruction::LeftShift,
+            AssignmentOperator::RightShiftEqual => Instruction::RightShift,
+            AssignmentOperator::UnsignedRightShiftEqual => Instruction::UnsignedRightShift,
+            AssignmentOperator::OrEqual => Instruction::BitOr,
+            AssignmentOperator::XOrEqual => Instruction::BitXOr,
+            AssignmentOperator::AndEqual => Instruction::BitAnd,
+            AssignmentOperator::PowerOfEqual => Instruction::PowerOf,
         };
 
         Operation::new(instr, vec![Operand::Reg(rd), Operand::Reg(rd), Operand::Reg(rs)])
     }
 
-    pub fn update_op(&self, op: &UpdateOperator, rd: Reg) -> Operation {
+    pub fn update_op(&self, op: &UpdateOperator, rd: Reg, rs: Reg) -> Operation {
         let instr = match op {
             UpdateOperator::Increment => Instruction::Add,
             UpdateOperator::Decrement => Instruction::Minus,
@@ -231,7 +238,7 @@ impl InstructionSet {
 
         Operation::new(instr, vec![
             Operand::Reg(rd),
-            Operand::Reg(rd),
+            Operand::Reg(rs),
             Operand::Reg(self.common_literal_reg(&CommonLiteral::Num1))
             ]
         )
@@ -245,18 +252,18 @@ impl InstructionSet {
                 Operand::Reg(rs)
                 ]
             ),
-            UnaryOperator::Plus => Operation::new(Instruction::Add, vec![
+            // Subtracting 0 converts to a number, while adding 0 would concatenate strings
+            UnaryOperator::Plus => Operation::new(Instruction::Minus, vec![
                 Operand::Reg(rd),
-                Operand::Reg(self.common_literal_reg(&CommonLiteral::Num0)),
-                Operand::Reg(rs)
+                Operand::Reg(rs),
+                Operand::Reg(self.common_literal_reg(&CommonLiteral::Num0))
                 ]
             ),
-            // Not,
-            // Tilde,
-            // TypeOf,
+            UnaryOperator::Not => Operation::new(Instruction::Not, vec![Operand::Reg(rd), Operand::Reg(rs)]),
+            UnaryOperator::Tilde => Operation::new(Instruction::BitNot, vec![Operand::Reg(rd), Operand::Reg(rs)]),
+            UnaryOperator::TypeOf => Operation::new(Instruction::TypeOf, vec![Operand::Reg(rd), Operand::Reg(rs)]),
             UnaryOperator::Void => { return Err(CompilerError::Custom("The 'void' must be handled on compiler-level".into())); },
-            // Delete,
-            _ => { return Err(CompilerError::is_unsupported("Unary operation", op)); }
+            UnaryOperator::Delete => { return Err(CompilerError::Custom("The 'delete' must be handled on compiler-level".into())); },
         })
     }
 
@@ -270,21 +277,20 @@ impl InstructionSet {
             BinaryOperator::GreaterThan => Instruction::CompGreaterThan,
             BinaryOperator::LessThanEqual => Instruction::CompLessThanEqual,
             BinaryOperator::GreaterThanEqual => Instruction::CompGreaterThanEqual,
-            // BinaryOperator::LeftShift => Instruction::Sh,
-            // BinaryOperator::RightShift,
-            // BinaryOperator::UnsignedRightShift,
+            BinaryOperator::LeftShift => Instruction::LeftShift,
+            BinaryOperator::RightShift => Instruction::RightShift,
+            BinaryOperator::UnsignedRightShift => Instruction::UnsignedRightShift,
             BinaryOperator::Plus => Instruction::Add,
             BinaryOperator::Minus => Instruction::Minus,
             BinaryOperator::Times => Instruction::Mul,
             BinaryOperator::Over => Instruction::Div,
-            // Mod,
-            // Or,
-            // XOr,
-            // And,
-            // In,
-            // InstanceOf,
-            // PowerOf,
-            _ => { return Err(CompilerError::is_unsupported("Binary operation", op)); }
+            BinaryOperator::Mod => Instruction::Mod,
+            BinaryOperator::Or => Instruction::BitOr,
+            BinaryOperator::XOr => Instruction::BitXOr,
+            BinaryOperator::And => Instruction::BitAnd,
+            BinaryOperator::In => Instruction::In,
+            BinaryOperator::InstanceOf => Instruction::InstanceOf,
+            BinaryOperator::PowerOf => Instruction::PowerOf,
         };
 
         Ok(Operation::new(instr, vec![Operand::Reg(rd), Operand::Reg(r0), Operand::Reg(r1)]))
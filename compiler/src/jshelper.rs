@@ -1,6 +1,9 @@
-use crate::error::{CompilerError};
+use crate::error::{CompilerError, CompilerResult};
 
 use ressa::{Parser};
+use ress::{Scanner, Token, Keyword, Punct};
+use resast::prelude::*;
+use std::collections::HashSet;
 
 /// A wrapper for JavaScript source code
 ///
@@ -33,7 +36,8 @@ impl JSSourceCode {
 /// let js_ast = JSAst::parse(&js_code).expect("Failed to parse input code");
 /// ```
 pub struct JSAst {
-    pub ast: resast::Program
+    pub ast: resast::Program,
+    pub static_members: StaticMembers
 }
 
 impl JSAst {
@@ -43,9 +47,434 @@ impl JSAst {
             Err(e) => { return Err(CompilerError::Parser(e)); }
         };
 
-        match parser.parse() {
-            Ok(ast) => Ok(JSAst{ ast }),
-            Err(e) => Err(CompilerError::Parser(e))
+        let mut ast = match parser.parse() {
+            Ok(ast) => ast,
+            Err(e) => { return Err(CompilerError::Parser(e)); }
+        };
+
+        let mut workarounds = ParserWorkarounds::new(static_class_members(&source.source_code), spread_brackets(&source.source_code));
+        workarounds.visit_program(&mut ast)?;
+
+        Ok(JSAst{ ast, static_members: workarounds.static_members })
+    }
+}
+
+/// The static members of the classes of an AST
+///
+/// The parser does not keep the ``static`` keyword of class members, thus they are located in the source
+/// code and kept apart from the AST. Members are identified by their position in the AST, which is
+/// why the AST must not be copied.
+#[derive(Debug, Clone, Default)]
+pub struct StaticMembers {
+    members: HashSet<usize>
+}
+
+impl StaticMembers {
+    pub fn contains(&self, member: &Property) -> bool {
+        self.members.contains(&Self::position(member))
+    }
+
+    fn insert(&mut self, member: &Property) {
+        self.members.insert(Self::position(member));
+    }
+
+    fn position(member: &Property) -> usize {
+        member as *const Property as usize
+    }
+}
+
+/// Returns which members of the classes in ``source`` are static
+///
+/// The classes are ordered by the position of their ``class`` keyword, their members
+/// by their position in the class body.
+fn static_class_members(source: &str) -> Vec<Vec<bool>> {
+    let tokens: Vec<Token> = Scanner::new(source).map(|item| item.token)
+                                                 .filter(|token| !token.is_comment())
+                                                 .collect();
+    let mut classes: Vec<Vec<bool>> = vec![];
+    // Classes whose body is not reached yet, with the bracket depth of their 'class' keyword
+    let mut pending_classes: Vec<(usize, usize)> = vec![];
+    // The currently open brackets, class bodies are marked by the index of their class
+    let mut brackets: Vec<Option<usize>> = vec![];
+    let mut at_member_start = false;
+
+    for (idx, token) in tokens.iter().enumerate() {
+        let previous = if idx > 0 { tokens.get(idx - 1) } else { None };
+        let next = tokens.get(idx + 1);
+        let next_is = |punct: Punct| matches!(next, Some(next) if next.matches_punct(punct));
+
+        if let (Some(Some(class)), true) = (brackets.last(), at_member_start) {
+            if !token.matches_punct(Punct::SemiColon) && !token.matches_punct(Punct::CloseBrace) {
+                // A method can be called 'static' as well
+                classes[*class].push(token.matches_keyword(Keyword::Static) && !next_is(Punct::OpenParen));
+                at_member_start = false;
+            }
+        }
+
+        match token {
+            // 'class' might be the name of a property as well
+            Token::Keyword(Keyword::Class) if !matches!(previous, Some(prev) if prev.matches_punct(Punct::Period)) &&
+                                              !next_is(Punct::Colon) && !next_is(Punct::OpenParen) => {
+                pending_classes.push((classes.len(), brackets.len()));
+                classes.push(vec![]);
+            },
+            Token::Punct(Punct::OpenBrace) => {
+                let class = match pending_classes.last() {
+                    Some(&(class, depth)) if depth == brackets.len() => {
+                        pending_classes.pop();
+                        Some(class)
+                    },
+                    _ => None
+                };
+                at_member_start = class.is_some();
+                brackets.push(class);
+            },
+            Token::Punct(Punct::OpenParen) | Token::Punct(Punct::OpenBracket) => brackets.push(None),
+            Token::Punct(Punct::CloseBrace) => {
+                brackets.pop();
+                // The body of a method ends its member
+                at_member_start = matches!(brackets.last(), Some(Some(_)));
+            },
+            Token::Punct(Punct::CloseParen) | Token::Punct(Punct::CloseBracket) => { brackets.pop(); },
+            _ => {}
+        }
+    }
+
+    classes
+}
+
+/// Returns for every ``[`` in ``source`` whether a spread or rest element is directly inside its brackets
+///
+/// The brackets are ordered by their position.
+fn spread_brackets(source: &str) -> Vec<bool> {
+    let mut spread_brackets = vec![];
+    // The currently open brackets, square brackets are marked by their index
+    let mut brackets: Vec<Option<usize>> = vec![];
+
+    for token in Scanner::new(source).map(|item| item.token) {
+        match token {
+            Token::Punct(Punct::OpenBracket) => {
+                brackets.push(Some(spread_brackets.len()));
+                spread_brackets.push(false);
+            },
+            Token::Punct(Punct::OpenBrace) | Token::Punct(Punct::OpenParen) => brackets.push(None),
+            Token::Punct(Punct::CloseBrace) | Token::Punct(Punct::CloseParen) | Token::Punct(Punct::CloseBracket) => { brackets.pop(); },
+            Token::Punct(Punct::Spread) => if let Some(Some(bracket)) = brackets.last() {
+                spread_brackets[*bracket] = true;
+            },
+            _ => {}
+        }
+    }
+
+    spread_brackets
+}
+
+/// Repairs the parts of a program which the parser gets wrong
+///
+/// It collects the static members of all classes, see [StaticMembers](struct.StaticMembers.html).
+/// The classes are visited in the order of their ``class`` keywords.
+///
+/// Async function declarations are returned as expression statements by the parser,
+/// they are turned back into declarations.
+///
+/// Expression bodies of arrow functions are turned into blocks which return the expression,
+/// thus arrow functions can be compiled without copying their body.
+///
+/// The parser drops the rest marker of array patterns in declarations, parameters and catch clauses,
+/// it is restored from the square brackets which contain a ``...``. The brackets are visited in the
+/// order of their position.
+struct ParserWorkarounds {
+    classes: std::vec::IntoIter<Vec<bool>>,
+    spread_brackets: std::vec::IntoIter<bool>,
+    static_members: StaticMembers
+}
+
+impl ParserWorkarounds {
+    fn new(classes: Vec<Vec<bool>>, spread_brackets: Vec<bool>) -> Self {
+        ParserWorkarounds {
+            classes: classes.into_iter(),
+            spread_brackets: spread_brackets.into_iter(),
+            static_members: StaticMembers::default()
+        }
+    }
+
+    fn visit_program(&mut self, program: &mut Program) -> CompilerResult<()> {
+        match program {
+            Program::Mod(parts) | Program::Script(parts) => self.visit_parts(parts)?
+        }
+
+        if self.spread_brackets.next().is_some() {
+            return Err(CompilerError::Custom("Failed to locate the square brackets of the program".into()));
+        }
+
+        match self.classes.next() {
+            Some(_) => Err(CompilerError::Custom("Failed to locate the members of a class".into())),
+            None => Ok(())
+        }
+    }
+
+    /// Returns whether the next square brackets directly contain a spread or rest element
+    fn visit_bracket(&mut self) -> CompilerResult<bool> {
+        self.spread_brackets.next().ok_or(
+            CompilerError::Custom("Failed to locate the square brackets of the program".into())
+        )
+    }
+
+    fn visit_class(&mut self, class: &mut Class) -> CompilerResult<()> {
+        let statics = self.classes.next().filter(|statics| statics.len() == class.body.len()).ok_or(
+            CompilerError::Custom("Failed to locate the members of a class".into())
+        )?;
+
+        if let Some(super_class) = &mut class.super_class {
+            self.visit_expr(super_class)?;
+        }
+        for (member, is_static) in class.body.iter_mut().zip(statics) {
+            if is_static {
+                self.static_members.insert(member);
+            }
+            self.visit_property(member)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_parts(&mut self, parts: &mut [ProgramPart]) -> CompilerResult<()> {
+        parts.iter_mut().try_for_each(|part| {
+            if let ProgramPart::Stmt(Stmt::Expr(Expr::Function(func))) = part {
+                if func.is_async && func.id.is_some() {
+                    *part = ProgramPart::Decl(Decl::Function(func.clone()));
+                }
+            }
+
+            match part {
+                ProgramPart::Dir(_) => Ok(()),
+                ProgramPart::Decl(decl) => self.visit_decl(decl),
+                ProgramPart::Stmt(stmt) => self.visit_stmt(stmt)
+            }
+        })
+    }
+
+    fn visit_func(&mut self, func: &mut Function) -> CompilerResult<()> {
+        self.visit_func_args(&mut func.params)?;
+        self.visit_parts(&mut func.body)
+    }
+
+    fn visit_func_args(&mut self, args: &mut [FunctionArg]) -> CompilerResult<()> {
+        args.iter_mut().try_for_each(|arg| match arg {
+            FunctionArg::Pat(pat) => self.visit_pat(pat),
+            FunctionArg::Expr(expr) => self.visit_expr(expr)
+        })
+    }
+
+    fn visit_decl(&mut self, decl: &mut Decl) -> CompilerResult<()> {
+        match decl {
+            Decl::Variable(_, decls) => self.visit_var_decls(decls),
+            Decl::Function(func) => self.visit_func(func),
+            Decl::Class(class) => self.visit_class(class),
+            Decl::Import(_) | Decl::Export(_) => Ok(())
+        }
+    }
+
+    fn visit_var_decls(&mut self, decls: &mut [VariableDecl]) -> CompilerResult<()> {
+        for decl in decls.iter_mut() {
+            self.visit_pat(&mut decl.id)?;
+            if let Some(init) = &mut decl.init {
+                self.visit_expr(init)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_stmt(&mut self, stmt: &mut Stmt) -> CompilerResult<()> {
+        match stmt {
+            Stmt::Expr(expr) | Stmt::Throw(expr) => self.visit_expr(expr),
+            Stmt::Block(block) => self.visit_parts(block),
+            Stmt::Empty | Stmt::Debugger | Stmt::Break(_) | Stmt::Continue(_) | Stmt::Return(None) => Ok(()),
+            Stmt::Return(Some(expr)) => self.visit_expr(expr),
+            Stmt::With(with) => {
+                self.visit_expr(&mut with.object)?;
+                self.visit_stmt(&mut with.body)
+            },
+            Stmt::Labeled(labeled) => self.visit_stmt(&mut labeled.body),
+            Stmt::If(if_stmt) => {
+                self.visit_expr(&mut if_stmt.test)?;
+                self.visit_stmt(&mut if_stmt.consequent)?;
+                match &mut if_stmt.alternate {
+                    Some(alternate) => self.visit_stmt(alternate),
+                    None => Ok(())
+                }
+            },
+            Stmt::Switch(switch) => {
+                self.visit_expr(&mut switch.discriminant)?;
+                for case in switch.cases.iter_mut() {
+                    if let Some(test) = &mut case.test {
+                        self.visit_expr(test)?;
+                    }
+                    self.visit_parts(&mut case.consequent)?;
+                }
+                Ok(())
+            },
+            Stmt::Try(try_stmt) => {
+                self.visit_parts(&mut try_stmt.block)?;
+                if let Some(handler) = &mut try_stmt.handler {
+                    if let Some(param) = &mut handler.param {
+                        self.visit_pat(param)?;
+                    }
+                    self.visit_parts(&mut handler.body)?;
+                }
+                match &mut try_stmt.finalizer {
+                    Some(finalizer) => self.visit_parts(finalizer),
+                    None => Ok(())
+                }
+            },
+            Stmt::While(while_stmt) => {
+                self.visit_expr(&mut while_stmt.test)?;
+                self.visit_stmt(&mut while_stmt.body)
+            },
+            Stmt::DoWhile(dowhile_stmt) => {
+                self.visit_stmt(&mut dowhile_stmt.body)?;
+                self.visit_expr(&mut dowhile_stmt.test)
+            },
+            Stmt::For(for_stmt) => {
+                match &mut for_stmt.init {
+                    Some(LoopInit::Variable(_, decls)) => self.visit_var_decls(decls)?,
+                    Some(LoopInit::Expr(expr)) => self.visit_expr(expr)?,
+                    None => {}
+                }
+                for expr in for_stmt.test.iter_mut().chain(for_stmt.update.iter_mut()) {
+                    self.visit_expr(expr)?;
+                }
+                self.visit_stmt(&mut for_stmt.body)
+            },
+            Stmt::ForIn(ForInStmt { left, right, body }) |
+            Stmt::ForOf(ForOfStmt { left, right, body, .. }) => {
+                match left {
+                    LoopLeft::Expr(expr) => self.visit_expr(expr)?,
+                    LoopLeft::Variable(_, decl) => self.visit_var_decls(std::slice::from_mut(decl))?,
+                    LoopLeft::Pat(pat) => self.visit_pat(pat)?
+                }
+                self.visit_expr(right)?;
+                self.visit_stmt(body)
+            },
+            Stmt::Var(decls) => self.visit_var_decls(decls),
+        }
+    }
+
+    fn visit_pat(&mut self, pat: &mut Pat) -> CompilerResult<()> {
+        match pat {
+            Pat::Identifier(_) => Ok(()),
+            Pat::Object(parts) => parts.iter_mut().try_for_each(|part| match part {
+                ObjectPatPart::Assignment(prop) => self.visit_property(prop),
+                ObjectPatPart::Rest(pat) => self.visit_pat(pat)
+            }),
+            Pat::Array(parts) => {
+                if self.visit_bracket()? {
+                    // Only the last element can be a rest element
+                    if let Some(Some(ArrayPatPart::Pat(last))) = parts.last_mut() {
+                        if !matches!(last, Pat::RestElement(_)) {
+                            *last = Pat::RestElement(Box::new(last.clone()));
+                        }
+                    }
+                }
+
+                parts.iter_mut().flatten().try_for_each(|part| match part {
+                    ArrayPatPart::Pat(pat) => self.visit_pat(pat),
+                    ArrayPatPart::Expr(expr) => self.visit_expr(expr)
+                })
+            },
+            Pat::RestElement(pat) => self.visit_pat(pat),
+            Pat::Assignment(assign) => {
+                self.visit_pat(&mut assign.left)?;
+                self.visit_expr(&mut assign.right)
+            }
+        }
+    }
+
+    fn visit_property(&mut self, prop: &mut Property) -> CompilerResult<()> {
+        if prop.computed {
+            self.visit_bracket()?;
+        }
+
+        match &mut prop.key {
+            PropertyKey::Expr(key) => self.visit_expr(key)?,
+            PropertyKey::Pat(pat) => self.visit_pat(pat)?,
+            PropertyKey::Literal(_) => {}
+        }
+
+        match &mut prop.value {
+            PropertyValue::Expr(expr) => self.visit_expr(expr),
+            PropertyValue::Pat(pat) => self.visit_pat(pat),
+            PropertyValue::None => Ok(())
+        }
+    }
+
+    fn visit_exprs<'a, I>(&mut self, exprs: I) -> CompilerResult<()>
+        where I: IntoIterator<Item = &'a mut Expr>
+    {
+        exprs.into_iter().try_for_each(|expr| self.visit_expr(expr))
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) -> CompilerResult<()> {
+        match expr {
+            Expr::Array(array) => {
+                self.visit_bracket()?;
+                self.visit_exprs(array.iter_mut().flatten())
+            },
+            Expr::ArrowFunction(arrow) => {
+                self.visit_func_args(&mut arrow.params)?;
+                if let ArrowFunctionBody::Expr(expr) = &mut arrow.body {
+                    let expr = std::mem::replace(expr.as_mut(), Expr::This);
+                    arrow.body = ArrowFunctionBody::FunctionBody(vec![ProgramPart::Stmt(Stmt::Return(Some(expr)))]);
+                    arrow.expression = false;
+                }
+                match &mut arrow.body {
+                    ArrowFunctionBody::FunctionBody(body) => self.visit_parts(body),
+                    ArrowFunctionBody::Expr(expr) => self.visit_expr(expr)
+                }
+            },
+            Expr::ArrowParamPlaceHolder(args, _) => self.visit_func_args(args),
+            Expr::Assignment(assign) => {
+                match &mut assign.left {
+                    AssignmentLeft::Pat(pat) => self.visit_pat(pat)?,
+                    AssignmentLeft::Expr(expr) => self.visit_expr(expr)?
+                }
+                self.visit_expr(&mut assign.right)
+            },
+            Expr::Await(expr) | Expr::Spread(expr) => self.visit_expr(expr),
+            Expr::Binary(BinaryExpr { left, right, .. }) |
+            Expr::Logical(LogicalExpr { left, right, .. }) => self.visit_exprs(vec![left.as_mut(), right.as_mut()]),
+            Expr::Class(class) => self.visit_class(class),
+            Expr::Call(CallExpr { callee, arguments }) |
+            Expr::New(NewExpr { callee, arguments }) => {
+                self.visit_expr(callee)?;
+                self.visit_exprs(arguments.iter_mut())
+            },
+            Expr::Conditional(cond) => self.visit_exprs(vec![cond.test.as_mut(), cond.consequent.as_mut(), cond.alternate.as_mut()]),
+            Expr::Function(func) => self.visit_func(func),
+            Expr::Literal(Literal::Template(template)) => self.visit_exprs(template.expressions.iter_mut()),
+            Expr::Member(member) => {
+                self.visit_expr(&mut member.object)?;
+                if member.computed {
+                    self.visit_bracket()?;
+                }
+                self.visit_expr(&mut member.property)
+            },
+            Expr::Object(props) => props.iter_mut().try_for_each(|prop| match prop {
+                ObjectProperty::Property(prop) => self.visit_property(prop),
+                ObjectProperty::Spread(expr) => self.visit_expr(expr)
+            }),
+            Expr::Sequence(exprs) => self.visit_exprs(exprs.iter_mut()),
+            Expr::TaggedTemplate(tagged) => {
+                self.visit_expr(&mut tagged.tag)?;
+                self.visit_exprs(tagged.quasi.expressions.iter_mut())
+            },
+            Expr::Unary(UnaryExpr { argument, .. }) |
+            Expr::Update(UpdateExpr { argument, .. }) => self.visit_expr(argument),
+            Expr::Yield(yield_expr) => match &mut yield_expr.argument {
+                Some(argument) => self.visit_expr(argument),
+                None => Ok(())
+            },
+            Expr::Ident(_) | Expr::Literal(_) | Expr::MetaProperty(_) | Expr::Super | Expr::This => Ok(())
         }
     }
 }
@@ -1,4 +1,5 @@
 extern crate ressa;
+extern crate ress;
 extern crate resast;
 extern crate base64;
 
@@ -1,3 +1,4 @@
+use std::borrow::Borrow;
 use std::collections::*;
 use resast::prelude::*;
 
@@ -7,6 +8,23 @@ use crate::error::{CompilerError, CompilerResult};
 pub type Register = u8;
 pub type Reg = Register;
 
+/// Registers below this one are local to the frame of a function call
+///
+/// All other registers are shared by all frames. They are used for reserved registers,
+/// common literals and external declarations.
+pub const FRAME_REGISTERS_END: Register = 200;
+
+/// Number of frame registers that are kept free for temporary values
+///
+/// Once fewer registers are left, new declarations are spilled.
+pub const TEMP_REGISTERS_RESERVE: usize = 32;
+
+/// Name of the hidden declaration holding the class whose members are compiled
+///
+/// ``super`` refers to the parent of this class. '#' cannot be part of a JavaScript
+/// identifier, thus the name never collides with other declarations.
+pub const CLASS_BINDING: &str = "#class";
+
 /// A reimplementantion of resast::prelude::VaribaleKind
 ///
 /// This reimplementantion of resast::prelude::VaribaleKind is done to derive the HashMap,
@@ -37,13 +55,62 @@ pub enum DeclarationType {
     // Intermediate
 }
 
+impl DeclarationType {
+    /// Whether the declaration is block scoped, i.e. a ``let`` or ``const`` declaration
+    pub fn is_lexical(&self) -> bool {
+        matches!(self, DeclarationType::Variable(MyVariableKind::Let) | DeclarationType::Variable(MyVariableKind::Const))
+    }
+
+    pub fn is_const(&self) -> bool {
+        *self == DeclarationType::Variable(MyVariableKind::Const)
+    }
+}
+
 pub type DeclType = DeclarationType;
 
+/// The temporal dead zone (TDZ) state of a declaration
+///
+/// Lexical declarations must not be accessed before they are initialized. As long as the compiler
+/// cannot rule out such an access, it is checked at runtime.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Tdz {
+    /// The declaration is initialized
+    Passed,
+    /// The declaration is not initialized yet, every access throws
+    Definite,
+    /// The declaration holds the uninitialized value until it is initialized, accesses check its value
+    Unknown,
+}
+
+/// The location of a declaration that is captured by a closure
+///
+/// Captured declarations do not live in a register but in a slot of a heap allocated environment.
+/// Every function containing captured declarations creates such an environment when it is called.
+/// ``depth`` is the number of environments between the global environment and the one holding
+/// this declaration, ``index`` is the position of the slot in this environment.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct EnvSlot {
+    pub depth: u8,
+    pub index: u8,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Declaration
 {
     pub register: Register,
     pub decl_type: DeclarationType,
+    pub env_slot: Option<EnvSlot>,
+    /// Declarations that do not fit into the registers of a frame are spilled to a slot
+    /// of the frame's spill array. ``register`` is then only a temporary register.
+    pub spill_slot: Option<u16>,
+    pub tdz: Tdz,
+}
+
+impl Declaration {
+    /// Whether the value of the declaration is kept in its register
+    pub fn is_in_register(&self) -> bool {
+        self.env_slot.is_none() && self.spill_slot.is_none()
+    }
 }
 
 
@@ -51,35 +118,120 @@ pub struct Declaration
 pub struct Scope
 {
     decls: HashMap<String, Declaration>,
-    new_decls: HashSet<String>,
+    /// Function declarations, mapped to the unique identifiers their bytecode functions are compiled under
+    func_decls: HashMap<String, String>,
+    /// The ``var`` declarations of the current function, which homonymous functions of nested blocks are assigned to
+    block_func_vars: HashMap<String, Declaration>,
     /// Is always sorted
     unused_register: VecDeque<Register>,
-    pub used_decls: HashSet<Declaration>
+    /// Registers holding temporary values, which are released after each statement
+    temp_registers: Vec<Register>,
+    /// Declarations with these names are referenced by closures and are thus placed in the environment
+    captured_decls: HashSet<String>,
+    env_depth: u8,
+    env_size: u8,
+    spill_size: u16,
+    /// The cases of a switch statement share one scope. Jumping to a case might skip the
+    /// initialization of lexical declarations, thus their accesses are always checked at runtime.
+    is_switch_scope: bool,
 }
 
 impl Scope {
     pub fn new() -> Self {
         Scope {
             decls: HashMap::new(),
-            new_decls: HashSet::new(),
+            func_decls: HashMap::new(),
+            block_func_vars: HashMap::new(),
             unused_register: (0..(Register::max_value() as u16 + 1)).map(|reg: u16| reg as u8).collect(),
-            used_decls: HashSet::new()
+            temp_registers: vec![],
+            captured_decls: HashSet::new(),
+            env_depth: 0,
+            env_size: 0,
+            spill_size: 0,
+            is_switch_scope: false,
         }
     }
 
     pub fn derive_scope(parent_scope: &Scope) -> CompilerResult<Self> {
         Ok(Scope {
             decls: parent_scope.decls.clone(),
-            new_decls: HashSet::new(),
+            func_decls: parent_scope.func_decls.clone(),
+            block_func_vars: parent_scope.block_func_vars.clone(),
             unused_register: parent_scope.unused_register.clone(),
-            used_decls: HashSet::new()
+            temp_registers: vec![],
+            captured_decls: parent_scope.captured_decls.clone(),
+            env_depth: parent_scope.env_depth,
+            env_size: parent_scope.env_size,
+            spill_size: parent_scope.spill_size,
+            is_switch_scope: false,
         })
     }
 
+    /// Derives the scope of a function
+    ///
+    /// Functions are executed in their own frame, thus all frame registers are available again.
+    pub fn derive_func_scope(parent_scope: &Scope) -> CompilerResult<Self> {
+        let mut scope = Scope::derive_scope(parent_scope)?;
+        scope.unused_register = (0..FRAME_REGISTERS_END)
+            .chain(parent_scope.unused_register.iter().cloned().filter(|&reg| reg >= FRAME_REGISTERS_END))
+            .collect();
+        scope.spill_size = 0;
+        scope.block_func_vars.clear();
+
+        Ok(scope)
+    }
+
+    /// Marks declarations as captured by closures
+    ///
+    /// If any declaration is captured, the scope gets its own environment.
+    /// # Returns
+    /// Whether a new environment has to be created for this scope.
+    pub fn capture_decls(&mut self, captured_decls: HashSet<String>) -> bool {
+        let needs_env = !captured_decls.is_empty();
+
+        if needs_env {
+            self.env_depth += 1;
+            self.env_size = 0;
+        }
+        self.captured_decls = captured_decls;
+
+        needs_env
+    }
+
+    /// Gives a block its own environment
+    ///
+    /// Declarations of the block that are captured by closures are placed in this environment.
+    /// Thus, every execution of the block, e.g. every iteration of a loop, gets fresh bindings.
+    pub fn create_block_env(&mut self) {
+        self.env_depth += 1;
+        self.env_size = 0;
+    }
+
+    pub fn env_depth(&self) -> u8 {
+        self.env_depth
+    }
+
+    /// Whether declarations with the name ``decl_name`` are referenced by closures
+    pub fn is_captured(&self, decl_name: &str) -> bool {
+        self.captured_decls.contains(decl_name)
+    }
+
+    /// Returns an unused frame register
     pub fn get_unused_register(&mut self) -> CompilerResult<Register> {
-        self.unused_register.pop_front().ok_or(
-            CompilerError::Custom("All registers are in use. Free up some registers".into())
-        )
+        match self.unused_register.front() {
+            Some(&reg) if reg < FRAME_REGISTERS_END => Ok(self.unused_register.pop_front().unwrap()),
+            _ => Err(CompilerError::Custom("All registers are in use. Free up some registers".into()))
+        }
+    }
+
+    fn unused_frame_registers_count(&self) -> usize {
+        self.unused_register.iter().take_while(|&&reg| reg < FRAME_REGISTERS_END).count()
+    }
+
+    fn release_register(&mut self, reg: Register) {
+        let idx = self.unused_register.iter().position(|&unused_reg| unused_reg > reg)
+                                             .unwrap_or(self.unused_register.len());
+        self.unused_register.insert(idx, reg);
     }
 
     pub fn get_unused_register_back(&mut self) -> CompilerResult<Register> {
@@ -103,24 +255,107 @@ impl Scope {
     }
 
     pub fn add_decl(&mut self, decl_name: String, decl_type: DeclarationType) -> CompilerResult<Register> {
-        let unused_reg = self.get_unused_register()?;
+        let env_slot = if self.captured_decls.contains(&decl_name) {
+            let index = self.env_size;
+            self.env_size = self.env_size.checked_add(1).ok_or(
+                CompilerError::Custom("Too many captured declarations in one function".into())
+            )?;
+            Some(EnvSlot { depth: self.env_depth, index })
+        } else {
+            None
+        };
+
+        let spill_slot = if env_slot.is_none() && self.unused_frame_registers_count() <= TEMP_REGISTERS_RESERVE {
+            let slot = self.spill_size;
+            self.spill_size = self.spill_size.checked_add(1).ok_or(
+                CompilerError::Custom("Too many spilled declarations in one function".into())
+            )?;
+            Some(slot)
+        } else {
+            None
+        };
+
+        let unused_reg = match spill_slot {
+            Some(_) => self.reserve_temp_register()?,
+            None => self.get_unused_register()?
+        };
+
+        let tdz = match (decl_type.is_lexical(), env_slot) {
+            (false, _) => Tdz::Passed,
+            // Closures might access the declaration at any time
+            (true, Some(_)) => Tdz::Unknown,
+            (true, None) if self.is_switch_scope => Tdz::Unknown,
+            (true, None) => Tdz::Definite,
+        };
+
+        // The declaration shadows homonymous functions
+        self.func_decls.remove(&decl_name);
         self.decls.insert(decl_name.clone(), Declaration {
             register: unused_reg,
-            decl_type: decl_type
+            decl_type: decl_type,
+            env_slot,
+            spill_slot,
+            tdz
         });
-        self.new_decls.insert(decl_name);
         Ok(unused_reg)
     }
 
-    pub fn get_decl(&mut self, decl_name: &str) -> CompilerResult<&Declaration> {
-        let decl = self.decls.get(decl_name).ok_or(
-            CompilerError::Custom(format!("The declaration '{}' does not exist", decl_name))
-        )?;
-
-        if !self.new_decls.contains(decl_name) {
-            self.used_decls.insert(decl.clone());
+    /// Marks the lexical declaration ``decl_name`` as initialized
+    ///
+    /// Declarations of switch statements stay checked at runtime.
+    pub fn initialize_decl(&mut self, decl_name: &str) {
+        if self.is_switch_scope {
+            return;
+        }
+        if let Some(decl) = self.decls.get_mut(decl_name) {
+            decl.tdz = Tdz::Passed;
         }
-        Ok(decl)
+    }
+
+    /// Adds a declaration that is stored in a register shared by all frames
+    pub fn add_shared_decl(&mut self, decl_name: String, decl_type: DeclarationType, reg: Register) {
+        self.decls.insert(decl_name, Declaration {
+            register: reg,
+            decl_type,
+            env_slot: None,
+            spill_slot: None,
+            tdz: Tdz::Passed
+        });
+    }
+
+    /// Adds the function declaration ``func_name``, whose bytecode function is compiled under ``func_ident``
+    ///
+    /// Calls of ``func_name`` jump to this bytecode function directly. If the function is used as a
+    /// value, it was added as declaration beforehand.
+    pub fn add_func_decl(&mut self, func_name: String, func_ident: String) {
+        self.func_decls.insert(func_name, func_ident);
+    }
+
+    /// Returns the identifier of the bytecode function the function declaration ``func_name`` refers to
+    pub fn get_func_decl(&self, func_name: &str) -> Option<&str> {
+        self.func_decls.get(func_name).map(String::as_str)
+    }
+
+    /// Marks the declaration ``decl_name`` as the ``var`` declaration of homonymous functions declared in blocks
+    pub fn add_block_func_var(&mut self, decl_name: &str) -> CompilerResult<()> {
+        let decl = self.get_decl(decl_name)?.clone();
+        self.block_func_vars.insert(decl_name.to_string(), decl);
+        Ok(())
+    }
+
+    /// Returns the ``var`` declaration a function ``func_name`` declared in a block is assigned to
+    pub fn get_block_func_var(&self, func_name: &str) -> Option<&Declaration> {
+        self.block_func_vars.get(func_name)
+    }
+
+    pub fn remove_block_func_var(&mut self, func_name: &str) {
+        self.block_func_vars.remove(func_name);
+    }
+
+    pub fn get_decl(&self, decl_name: &str) -> CompilerResult<&Declaration> {
+        self.decls.get(decl_name).ok_or(
+            CompilerError::Custom(format!("The declaration '{}' does not exist", decl_name))
+        )
     }
 
     pub fn reserve_register(&mut self) -> CompilerResult<Register> {
@@ -130,6 +365,28 @@ impl Scope {
     pub fn reserve_register_back(&mut self) -> CompilerResult<Register> {
         self.get_unused_register_back()
     }
+
+    /// Reserves a register for a temporary value
+    ///
+    /// The register is released again by [release_temp_registers](#method.release_temp_registers).
+    pub fn reserve_temp_register(&mut self) -> CompilerResult<Register> {
+        let reg = self.get_unused_register()?;
+        self.temp_registers.push(reg);
+        Ok(reg)
+    }
+
+    /// Returns a marker for all temporary registers which are currently reserved
+    pub fn temp_registers_marker(&self) -> usize {
+        self.temp_registers.len()
+    }
+
+    /// Releases all temporary registers reserved after ``marker`` was taken
+    pub fn release_temp_registers(&mut self, marker: usize) {
+        let released_regs: Vec<Register> = self.temp_registers.drain(marker..).collect();
+        for reg in released_regs {
+            self.release_register(reg);
+        }
+    }
 }
 
 
@@ -164,7 +421,10 @@ impl Scopes
         self.literals.push((literal,
             Declaration {
                 register: reg,
-                decl_type: DeclarationType::Literal
+                decl_type: DeclarationType::Literal,
+                env_slot: None,
+                spill_slot: None,
+                tdz: Tdz::Passed
             }
         ));
 
@@ -183,12 +443,72 @@ impl Scopes
         self.current_scope_mut()?.reserve_register()
     }
 
+    pub fn reserve_temp_register(&mut self) -> CompilerResult<Register> {
+        self.current_scope_mut()?.reserve_temp_register()
+    }
+
+    pub fn temp_registers_marker(&self) -> CompilerResult<usize> {
+        Ok(self.current_scope()?.temp_registers_marker())
+    }
+
+    pub fn release_temp_registers(&mut self, marker: usize) -> CompilerResult<()> {
+        self.current_scope_mut()?.release_temp_registers(marker);
+        Ok(())
+    }
+
     pub fn reserve_register_back(&mut self) -> CompilerResult<Register> {
         self.current_scope_mut()?.reserve_register_back()
     }
 
-    pub fn get_var(&mut self, var_name: &str) -> CompilerResult<&Declaration> {
-        self.current_scope_mut()?.get_decl(var_name)
+    /// Reserves a register that is shared by all frames, and thus in all scopes
+    pub fn reserve_shared_register(&mut self) -> CompilerResult<Register> {
+        let reg = self.current_scope_mut()?.reserve_register_back()?;
+        if reg < FRAME_REGISTERS_END {
+            return Err(CompilerError::Custom("All shared registers are in use".into()));
+        }
+
+        for scope in self.scopes.iter_mut().rev().skip(1) {
+            // Registers might already be taken by a block scope that has been left
+            let _ = scope.try_reserve_specific_reg(reg);
+        }
+
+        Ok(reg)
+    }
+
+    /// Adds an external declaration, which lives in a register shared by all frames
+    pub fn add_shared_decl(&mut self, decl: String, decl_type: DeclarationType) -> CompilerResult<Register> {
+        let reg = self.reserve_shared_register()?;
+        for scope in self.scopes.iter_mut() {
+            scope.add_shared_decl(decl.clone(), decl_type.clone(), reg);
+        }
+
+        Ok(reg)
+    }
+
+    pub fn get_var(&self, var_name: &str) -> CompilerResult<&Declaration> {
+        self.current_scope()?.get_decl(var_name)
+    }
+
+    pub fn add_func_decl(&mut self, func_name: String, func_ident: String) -> CompilerResult<()> {
+        self.current_scope_mut()?.add_func_decl(func_name, func_ident);
+        Ok(())
+    }
+
+    pub fn get_func_decl(&self, func_name: &str) -> Option<&str> {
+        self.current_scope().ok()?.get_func_decl(func_name)
+    }
+
+    pub fn add_block_func_var(&mut self, decl_name: &str) -> CompilerResult<()> {
+        self.current_scope_mut()?.add_block_func_var(decl_name)
+    }
+
+    pub fn get_block_func_var(&self, func_name: &str) -> Option<&Declaration> {
+        self.current_scope().ok()?.get_block_func_var(func_name)
+    }
+
+    pub fn remove_block_func_var(&mut self, func_name: &str) -> CompilerResult<()> {
+        self.current_scope_mut()?.remove_block_func_var(func_name);
+        Ok(())
     }
 
     pub fn get_lit_decl(&self, literal: &BytecodeLiteral) -> CompilerResult<&Declaration> {
@@ -202,15 +522,44 @@ impl Scopes
     }
 
     pub fn enter_new_scope(&mut self) -> CompilerResult<()> {
-        self.scopes.push(Scope::derive_scope(self.current_scope()?)?);
+        self.enter_new_func_scope(HashSet::new())?;
         Ok(())
     }
 
+    /// Enters the scope of a function whose declarations in ``captured_decls`` are used by closures
+    ///
+    /// # Returns
+    /// Whether the function has to create a new environment for its captured declarations.
+    pub fn enter_new_func_scope(&mut self, captured_decls: HashSet<String>) -> CompilerResult<bool> {
+        let mut scope = Scope::derive_func_scope(self.current_scope()?)?;
+        let needs_env = scope.capture_decls(captured_decls);
+        self.scopes.push(scope);
+        Ok(needs_env)
+    }
+
     pub fn enter_new_block_scope(&mut self) -> CompilerResult<()> {
         self.scopes.push(Scope::derive_scope(self.current_scope()?)?);
         Ok(())
     }
 
+    /// Enters the scope shared by the cases of a switch statement
+    pub fn enter_new_switch_scope(&mut self) -> CompilerResult<()> {
+        let mut scope = Scope::derive_scope(self.current_scope()?)?;
+        scope.is_switch_scope = true;
+        self.scopes.push(scope);
+        Ok(())
+    }
+
+    pub fn create_block_env(&mut self) -> CompilerResult<()> {
+        self.current_scope_mut()?.create_block_env();
+        Ok(())
+    }
+
+    pub fn initialize_decl(&mut self, decl_name: &str) -> CompilerResult<()> {
+        self.current_scope_mut()?.initialize_decl(decl_name);
+        Ok(())
+    }
+
     pub fn current_scope(&self) -> CompilerResult<&Scope> {
         self.scopes.last().ok_or(
             CompilerError::Custom("No current scope".into())
@@ -224,15 +573,9 @@ impl Scopes
     }
 
     pub fn leave_current_scope(&mut self) -> CompilerResult<Scope> {
-        let scope = self.scopes.pop().ok_or(
+        self.scopes.pop().ok_or(
             CompilerError::Custom("Cannot leave inexisting scope".into())
-        )?;
-
-        if let Ok(current_scope) = self.current_scope_mut() {
-            current_scope.used_decls.extend(scope.used_decls.iter().cloned());
-        }
-
-        Ok(scope)
+        )
     }
 
     pub fn leave_current_block_scope(&mut self) -> CompilerResult<Scope> {
@@ -242,13 +585,646 @@ impl Scopes
 
         if let Ok(current_scope) = self.current_scope_mut() {
             current_scope.unused_register = scope.unused_register.clone();
-            current_scope.used_decls.extend(scope.used_decls.iter().cloned());
+            current_scope.temp_registers.extend(scope.temp_registers.iter().cloned());
+            // The slots of a block's own environment are gone with the block
+            if current_scope.env_depth == scope.env_depth {
+                current_scope.env_size = scope.env_size;
+            }
+            current_scope.spill_size = scope.spill_size;
         }
 
         Ok(scope)
     }
 }
 
+/// Returns the names of the declarations of a function that are referenced by its closures
+///
+/// ``params`` and ``body`` describe the analyzed function (or the whole script). The analysis is
+/// conservative: block scoped declarations of closures are not considered as shadowing, thus a
+/// declaration might be captured although a closure only uses a homonymous declaration.
+pub fn captured_identifiers(params: &[FunctionArg], body: &[ProgramPart]) -> HashSet<String> {
+    let mut analysis = CaptureAnalysis::default();
+    params.iter().for_each(|param| analysis.visit_func_arg(param));
+    body.iter().for_each(|part| analysis.visit_program_part(part));
+
+    analysis.captured
+}
+
+/// Returns the ``let``, ``const`` and class declarations of a block, a function body or a script
+///
+/// Declarations of nested blocks are not included, they belong to the scopes of these blocks.
+/// Class declarations behave like ``let`` declarations.
+pub fn lexical_declarations<'a, I>(parts: I) -> Vec<(String, MyVariableKind)>
+    where I: IntoIterator<Item = &'a ProgramPart>
+{
+    parts.into_iter().flat_map(|part| match part {
+        ProgramPart::Decl(Decl::Variable(kind, decls)) => lexical_variable_declarations(kind, decls.iter()),
+        ProgramPart::Decl(Decl::Class(Class { id: Some(name), .. })) => vec![(name.to_string(), MyVariableKind::Let)],
+        _ => vec![]
+    }).collect()
+}
+
+/// Returns the names bound by ``decls`` if they are ``let`` or ``const`` declarations
+pub fn lexical_variable_declarations<'a, I>(kind: &VariableKind, decls: I) -> Vec<(String, MyVariableKind)>
+    where I: IntoIterator<Item = &'a VariableDecl>
+{
+    if *kind == VariableKind::Var {
+        return vec![];
+    }
+
+    decls.into_iter()
+         .flat_map(|decl| bound_identifiers(&decl.id))
+         .map(|name| (name, MyVariableKind::from(kind)))
+         .collect()
+}
+
+/// Returns the names of the ``var`` declarations of a function body or a script
+///
+/// ``var`` declarations belong to the whole function, thus declarations nested in blocks, loops
+/// and other statements are included. Declarations of nested functions are not.
+pub fn var_declarations<'a, I>(parts: I) -> Vec<String>
+    where I: IntoIterator<Item = &'a ProgramPart>
+{
+    let mut names = vec![];
+    parts.into_iter().for_each(|part| collect_part_var_decls(part, &mut names));
+    names
+}
+
+fn collect_part_var_decls(part: &ProgramPart, names: &mut Vec<String>) {
+    match part {
+        ProgramPart::Decl(Decl::Variable(VariableKind::Var, decls)) => collect_var_decl_bindings(decls.iter(), names),
+        ProgramPart::Stmt(stmt) => collect_stmt_var_decls(stmt, names),
+        _ => {}
+    }
+}
+
+fn collect_stmt_var_decls(stmt: &Stmt, names: &mut Vec<String>) {
+    match stmt {
+        Stmt::Var(decls) => collect_var_decl_bindings(decls.iter(), names),
+        Stmt::Block(parts) => parts.iter().for_each(|part| collect_part_var_decls(part, names)),
+        Stmt::With(with) => collect_stmt_var_decls(&with.body, names),
+        Stmt::Labeled(labeled) => collect_stmt_var_decls(&labeled.body, names),
+        Stmt::If(if_stmt) => {
+            collect_stmt_var_decls(&if_stmt.consequent, names);
+            if let Some(alternate) = &if_stmt.alternate {
+                collect_stmt_var_decls(alternate, names);
+            }
+        },
+        Stmt::Switch(switch) => switch.cases.iter().flat_map(|case| case.consequent.iter())
+                                                 .for_each(|part| collect_part_var_decls(part, names)),
+        Stmt::Try(try_stmt) => {
+            try_stmt.block.iter().for_each(|part| collect_part_var_decls(part, names));
+            if let Some(handler) = &try_stmt.handler {
+                handler.body.iter().for_each(|part| collect_part_var_decls(part, names));
+            }
+            if let Some(finalizer) = &try_stmt.finalizer {
+                finalizer.iter().for_each(|part| collect_part_var_decls(part, names));
+            }
+        },
+        Stmt::While(WhileStmt { body, .. }) | Stmt::DoWhile(DoWhileStmt { body, .. }) => collect_stmt_var_decls(body, names),
+        Stmt::For(for_stmt) => {
+            if let Some(LoopInit::Variable(VariableKind::Var, decls)) = &for_stmt.init {
+                collect_var_decl_bindings(decls.iter(), names);
+            }
+            collect_stmt_var_decls(&for_stmt.body, names);
+        },
+        Stmt::ForIn(ForInStmt { left, body, .. }) |
+        Stmt::ForOf(ForOfStmt { left, body, .. }) => {
+            if let LoopLeft::Variable(VariableKind::Var, decl) = left {
+                collect_var_decl_bindings(std::iter::once(decl), names);
+            }
+            collect_stmt_var_decls(body, names);
+        },
+        _ => {}
+    }
+}
+
+/// Returns the names of the functions declared in the blocks of a function body or a script
+///
+/// As in sloppy mode, these functions are also assigned to a ``var`` declaration of the whole
+/// function. Functions declared directly in the body, generators and async functions are not included.
+pub fn block_function_declarations<'a, I>(parts: I) -> Vec<String>
+    where I: IntoIterator<Item = &'a ProgramPart>
+{
+    let mut names = vec![];
+    parts.into_iter().for_each(|part| if let ProgramPart::Stmt(stmt) = part {
+        collect_stmt_block_funcs(stmt, &mut names);
+    });
+    names
+}
+
+fn collect_block_funcs<'a, I>(parts: I, names: &mut Vec<String>)
+    where I: Iterator<Item = &'a ProgramPart>
+{
+    for part in parts {
+        match part {
+            ProgramPart::Decl(Decl::Function(Function { id: Some(name), generator: false, is_async: false, .. }))
+                if !names.contains(name) => names.push(name.to_string()),
+            ProgramPart::Stmt(stmt) => collect_stmt_block_funcs(stmt, names),
+            _ => {}
+        }
+    }
+}
+
+fn collect_stmt_block_funcs(stmt: &Stmt, names: &mut Vec<String>) {
+    match stmt {
+        Stmt::Block(parts) => collect_block_funcs(parts.iter(), names),
+        Stmt::With(with) => collect_stmt_block_funcs(&with.body, names),
+        Stmt::Labeled(labeled) => collect_stmt_block_funcs(&labeled.body, names),
+        Stmt::If(if_stmt) => {
+            collect_stmt_block_funcs(&if_stmt.consequent, names);
+            if let Some(alternate) = &if_stmt.alternate {
+                collect_stmt_block_funcs(alternate, names);
+            }
+        },
+        Stmt::Switch(switch) => collect_block_funcs(switch.cases.iter().flat_map(|case| case.consequent.iter()), names),
+        Stmt::Try(try_stmt) => {
+            collect_block_funcs(try_stmt.block.iter(), names);
+            if let Some(handler) = &try_stmt.handler {
+                collect_block_funcs(handler.body.iter(), names);
+            }
+            if let Some(finalizer) = &try_stmt.finalizer {
+                collect_block_funcs(finalizer.iter(), names);
+            }
+        },
+        Stmt::While(WhileStmt { body, .. }) | Stmt::DoWhile(DoWhileStmt { body, .. }) |
+        Stmt::For(ForStmt { body, .. }) | Stmt::ForIn(ForInStmt { body, .. }) |
+        Stmt::ForOf(ForOfStmt { body, .. }) => collect_stmt_block_funcs(body, names),
+        _ => {}
+    }
+}
+
+fn collect_var_decl_bindings<'a, I>(decls: I, names: &mut Vec<String>)
+    where I: Iterator<Item = &'a VariableDecl>
+{
+    for name in decls.flat_map(|decl| bound_identifiers(&decl.id)) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+}
+
+/// Returns the names of the identifiers the parameters of a function declare
+pub fn parameter_identifiers(params: &[FunctionArg]) -> Vec<String> {
+    let mut idents = vec![];
+    for param in params.iter() {
+        match param {
+            FunctionArg::Pat(pat) => collect_pat_bindings(pat, &mut idents),
+            FunctionArg::Expr(expr) => collect_expr_bindings(expr, &mut idents)
+        }
+    }
+    idents
+}
+
+/// Returns the names of the identifiers a binding pattern declares
+pub fn bound_identifiers(pat: &Pat) -> Vec<String> {
+    let mut idents = vec![];
+    collect_pat_bindings(pat, &mut idents);
+    idents
+}
+
+fn collect_pat_bindings(pat: &Pat, idents: &mut Vec<String>) {
+    match pat {
+        Pat::Identifier(ident) => idents.push(ident.to_string()),
+        Pat::Object(parts) => for part in parts.iter() {
+            match part {
+                ObjectPatPart::Assignment(prop) => collect_prop_bindings(prop, idents),
+                ObjectPatPart::Rest(pat) => collect_pat_bindings(pat, idents)
+            }
+        },
+        Pat::Array(parts) => for part in parts.iter().flatten() {
+            match part {
+                ArrayPatPart::Pat(pat) => collect_pat_bindings(pat, idents),
+                ArrayPatPart::Expr(expr) => collect_expr_bindings(expr, idents)
+            }
+        },
+        Pat::RestElement(pat) => collect_pat_bindings(pat, idents),
+        Pat::Assignment(assign) => collect_pat_bindings(&assign.left, idents)
+    }
+}
+
+/// Patterns might contain expressions, e.g. a shorthand property with a default (``{a = 1}``)
+fn collect_expr_bindings(expr: &Expr, idents: &mut Vec<String>) {
+    match expr {
+        Expr::Ident(ident) => idents.push(ident.to_string()),
+        Expr::Assignment(assign) => match &assign.left {
+            AssignmentLeft::Pat(pat) => collect_pat_bindings(pat, idents),
+            AssignmentLeft::Expr(expr) => collect_expr_bindings(expr, idents)
+        },
+        Expr::Array(exprs) => exprs.iter().flatten().for_each(|expr| collect_expr_bindings(expr, idents)),
+        Expr::Object(props) => for prop in props.iter() {
+            match prop {
+                ObjectProperty::Property(prop) => collect_prop_bindings(prop, idents),
+                ObjectProperty::Spread(expr) => collect_expr_bindings(expr, idents)
+            }
+        },
+        Expr::Spread(expr) => collect_expr_bindings(expr, idents),
+        _ => {}
+    }
+}
+
+fn collect_prop_bindings(prop: &Property, idents: &mut Vec<String>) {
+    match (&prop.value, &prop.key) {
+        (PropertyValue::Pat(pat), _) => collect_pat_bindings(pat, idents),
+        // Shorthand properties bind their key, even if they have a default value
+        (_, PropertyKey::Expr(key)) if prop.short_hand => collect_expr_bindings(key, idents),
+        (_, PropertyKey::Pat(key)) if prop.short_hand => collect_pat_bindings(key, idents),
+        (PropertyValue::Expr(expr), _) => collect_expr_bindings(expr, idents),
+        (PropertyValue::None, _) => {}
+    }
+}
+
+/// Returns whether a function refers to its ``arguments`` object
+///
+/// References of arrow functions are included, since they use the ``arguments`` object of their enclosing function.
+pub fn uses_arguments_object(params: &[FunctionArg], body: &[ProgramPart]) -> bool {
+    let mut analysis = CaptureAnalysis::default();
+    params.iter().for_each(|param| analysis.visit_func_arg(param));
+    body.iter().for_each(|part| analysis.visit_program_part(part));
+
+    analysis.referenced.contains("arguments") && !analysis.declared.contains("arguments")
+}
+
+/// Returns the identifiers which are assigned to in ``body``
+///
+/// Like the captured identifiers, the assignments of closures are included and shadowing is ignored.
+pub fn assigned_identifiers<'a, I>(parts: I) -> HashSet<String>
+    where I: IntoIterator<Item = &'a ProgramPart>
+{
+    let mut analysis = CaptureAnalysis::default();
+    parts.into_iter().for_each(|part| analysis.visit_program_part(part));
+
+    analysis.assigned
+}
+
+/// Returns the identifiers whose values are used by ``body``, not counting the callees of calls
+///
+/// Like the captured identifiers, the references of closures are included and shadowing is ignored.
+pub fn value_references<'a, I>(parts: I) -> HashSet<String>
+    where I: IntoIterator<Item = &'a ProgramPart>
+{
+    let mut analysis = CaptureAnalysis::default();
+    parts.into_iter().for_each(|part| analysis.visit_program_part(part));
+
+    analysis.values
+}
+
+#[derive(Default)]
+struct CaptureAnalysis {
+    /// Identifiers referenced by the function, including the free identifiers of its closures
+    referenced: HashSet<String>,
+    /// Identifiers declared on function level
+    declared: HashSet<String>,
+    /// Free identifiers of the closures defined in the function
+    captured: HashSet<String>,
+    /// Referenced identifiers, except for those which are only called
+    values: HashSet<String>,
+    /// Identifiers which are assigned to, including declarations with initializers
+    /// and functions declared in blocks, which are assigned to the declaration of their function
+    assigned: HashSet<String>,
+    block_depth: usize,
+}
+
+impl CaptureAnalysis {
+    fn add_closure(&mut self, closure: CaptureAnalysis) {
+        let free_idents: Vec<String> = closure.referenced.difference(&closure.declared).cloned().collect();
+        self.captured.extend(free_idents.iter().cloned());
+        self.referenced.extend(free_idents);
+        self.values.extend(closure.values.difference(&closure.declared).cloned());
+        self.assigned.extend(closure.assigned.difference(&closure.declared).cloned());
+    }
+
+    fn visit_func(&mut self, func: &Function, binds_own_name: bool) {
+        let mut closure = CaptureAnalysis::default();
+        if let (Some(name), true) = (&func.id, binds_own_name) {
+            closure.declared.insert(name.to_string());
+        }
+        // Unlike arrow functions, functions have their own 'arguments' object
+        closure.declared.insert("arguments".into());
+        func.params.iter().for_each(|param| closure.visit_func_arg(param));
+        func.body.iter().for_each(|part| closure.visit_program_part(part));
+
+        self.add_closure(closure);
+    }
+
+    fn visit_arrow_func(&mut self, arrow: &ArrowFunctionExpr) {
+        let mut closure = CaptureAnalysis::default();
+        arrow.params.iter().for_each(|param| closure.visit_func_arg(param));
+        match &arrow.body {
+            ArrowFunctionBody::FunctionBody(body) => body.iter().for_each(|part| closure.visit_program_part(part)),
+            ArrowFunctionBody::Expr(expr) => closure.visit_expr(expr)
+        }
+
+        self.add_closure(closure);
+    }
+
+    fn visit_func_arg(&mut self, arg: &FunctionArg) {
+        match arg {
+            FunctionArg::Pat(pat) => self.visit_pat(pat, true),
+            FunctionArg::Expr(Expr::Ident(ident)) => { self.declared.insert(ident.to_string()); },
+            FunctionArg::Expr(Expr::Assignment(assign)) => {
+                match &assign.left {
+                    AssignmentLeft::Pat(pat) => self.visit_pat(pat, true),
+                    AssignmentLeft::Expr(expr) => match expr.borrow() {
+                        Expr::Ident(ident) => { self.declared.insert(ident.to_string()); },
+                        expr => self.visit_expr(expr)
+                    }
+                }
+                self.visit_expr(&assign.right);
+            },
+            FunctionArg::Expr(expr) => self.visit_expr(expr)
+        }
+    }
+
+    fn visit_program_part(&mut self, part: &ProgramPart) {
+        match part {
+            ProgramPart::Dir(_) => {},
+            ProgramPart::Decl(decl) => self.visit_decl(decl),
+            ProgramPart::Stmt(stmt) => self.visit_stmt(stmt)
+        }
+    }
+
+    fn visit_block(&mut self, parts: &[ProgramPart]) {
+        self.block_depth += 1;
+        parts.iter().for_each(|part| self.visit_program_part(part));
+        self.block_depth -= 1;
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        match decl {
+            Decl::Variable(kind, decls) => self.visit_var_decls(kind, decls.iter()),
+            Decl::Function(func) => {
+                if let Some(name) = &func.id {
+                    self.declared.insert(name.to_string());
+                    if self.block_depth > 0 {
+                        self.assigned.insert(name.to_string());
+                    }
+                }
+                self.visit_func(func, false);
+            },
+            Decl::Class(class) => {
+                if let (Some(name), 0) = (&class.id, self.block_depth) {
+                    self.declared.insert(name.to_string());
+                }
+                self.visit_class(class);
+            },
+            Decl::Import(_) | Decl::Export(_) => {}
+        }
+    }
+
+    fn visit_var_decls<'a, I>(&mut self, kind: &VariableKind, decls: I)
+        where I: Iterator<Item = &'a VariableDecl>
+    {
+        let declares = *kind == VariableKind::Var || self.block_depth == 0;
+
+        for decl in decls {
+            self.visit_pat(&decl.id, declares);
+            if let Some(init) = &decl.init {
+                self.assigned.extend(bound_identifiers(&decl.id));
+                self.visit_expr(init);
+            }
+        }
+    }
+
+    fn visit_class(&mut self, class: &Class) {
+        if let Some(super_class) = &class.super_class {
+            self.visit_expr(super_class);
+            // The default constructor of a derived class calls the parent constructor through 'super'
+            if !class.body.iter().any(|member| member.kind == PropertyKind::Ctor) {
+                self.captured.insert(CLASS_BINDING.into());
+                self.referenced.insert(CLASS_BINDING.into());
+            }
+        }
+        class.body.iter().for_each(|prop| self.visit_property(prop));
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expr(expr) | Stmt::Throw(expr) => self.visit_expr(expr),
+            Stmt::Block(block) => self.visit_block(block),
+            Stmt::Empty | Stmt::Debugger | Stmt::Break(_) | Stmt::Continue(_) => {},
+            Stmt::With(with) => {
+                self.visit_expr(&with.object);
+                self.visit_stmt(&with.body);
+            },
+            Stmt::Return(ret) => if let Some(expr) = ret { self.visit_expr(expr) },
+            Stmt::Labeled(labeled) => self.visit_stmt(&labeled.body),
+            Stmt::If(if_stmt) => {
+                self.visit_expr(&if_stmt.test);
+                self.visit_stmt(&if_stmt.consequent);
+                if let Some(alternate) = &if_stmt.alternate {
+                    self.visit_stmt(alternate);
+                }
+            },
+            Stmt::Switch(switch) => {
+                self.visit_expr(&switch.discriminant);
+                self.block_depth += 1;
+                for case in switch.cases.iter() {
+                    if let Some(test) = &case.test {
+                        self.visit_expr(test);
+                    }
+                    case.consequent.iter().for_each(|part| self.visit_program_part(part));
+                }
+                self.block_depth -= 1;
+            },
+            Stmt::Try(try_stmt) => {
+                self.visit_block(&try_stmt.block);
+                if let Some(handler) = &try_stmt.handler {
+                    if let Some(param) = &handler.param {
+                        self.visit_pat(param, false);
+                    }
+                    self.visit_block(&handler.body);
+                }
+                if let Some(finalizer) = &try_stmt.finalizer {
+                    self.visit_block(finalizer);
+                }
+            },
+            Stmt::While(while_stmt) => {
+                self.visit_expr(&while_stmt.test);
+                self.visit_stmt(&while_stmt.body);
+            },
+            Stmt::DoWhile(dowhile_stmt) => {
+                self.visit_stmt(&dowhile_stmt.body);
+                self.visit_expr(&dowhile_stmt.test);
+            },
+            Stmt::For(for_stmt) => {
+                self.block_depth += 1;
+                match &for_stmt.init {
+                    Some(LoopInit::Variable(kind, decls)) => self.visit_var_decls(kind, decls.iter()),
+                    Some(LoopInit::Expr(expr)) => self.visit_expr(expr),
+                    None => {}
+                }
+                for expr in for_stmt.test.iter().chain(for_stmt.update.iter()) {
+                    self.visit_expr(expr);
+                }
+                self.visit_stmt(&for_stmt.body);
+                self.block_depth -= 1;
+            },
+            Stmt::ForIn(ForInStmt { left, right, body }) |
+            Stmt::ForOf(ForOfStmt { left, right, body, .. }) => {
+                self.block_depth += 1;
+                match left {
+                    LoopLeft::Expr(expr) => self.visit_assignment_target(expr),
+                    LoopLeft::Variable(kind, decl) => {
+                        self.assigned.extend(bound_identifiers(&decl.id));
+                        self.visit_var_decls(kind, std::iter::once(decl));
+                    },
+                    LoopLeft::Pat(pat) => {
+                        self.assigned.extend(bound_identifiers(pat));
+                        self.visit_pat(pat, false);
+                    }
+                }
+                self.visit_expr(right);
+                self.visit_stmt(body);
+                self.block_depth -= 1;
+            },
+            Stmt::Var(decls) => self.visit_var_decls(&VariableKind::Var, decls.iter()),
+        }
+    }
+
+    /// Visits a pattern, its identifiers are declarations if ``declares`` is set and references otherwise
+    fn visit_pat(&mut self, pat: &Pat, declares: bool) {
+        match pat {
+            Pat::Identifier(ident) => self.visit_binding(ident, declares),
+            Pat::Object(parts) => for part in parts.iter() {
+                match part {
+                    ObjectPatPart::Assignment(prop) => {
+                        if let (PropertyKey::Expr(key), true) = (&prop.key, prop.computed) {
+                            self.visit_expr(key);
+                        }
+                        match (&prop.value, &prop.key) {
+                            (PropertyValue::Pat(pat), _) => self.visit_pat(pat, declares),
+                            (PropertyValue::Expr(expr), _) => self.visit_expr(expr),
+                            (PropertyValue::None, PropertyKey::Expr(Expr::Ident(ident))) => self.visit_binding(ident, declares),
+                            (PropertyValue::None, PropertyKey::Pat(pat)) => self.visit_pat(pat, declares),
+                            (PropertyValue::None, _) => {}
+                        }
+                    },
+                    ObjectPatPart::Rest(pat) => self.visit_pat(pat, declares)
+                }
+            },
+            Pat::Array(parts) => for part in parts.iter().flatten() {
+                match part {
+                    ArrayPatPart::Pat(pat) => self.visit_pat(pat, declares),
+                    ArrayPatPart::Expr(Expr::Ident(ident)) => self.visit_binding(ident, declares),
+                    ArrayPatPart::Expr(expr) => self.visit_expr(expr)
+                }
+            },
+            Pat::RestElement(pat) => self.visit_pat(pat, declares),
+            Pat::Assignment(assign) => {
+                self.visit_pat(&assign.left, declares);
+                self.visit_expr(&assign.right);
+            }
+        }
+    }
+
+    fn visit_binding(&mut self, ident: &Identifier, declares: bool) {
+        if declares {
+            self.declared.insert(ident.to_string());
+        } else {
+            self.reference(ident);
+        }
+    }
+
+    fn reference(&mut self, ident: &str) {
+        self.referenced.insert(ident.to_string());
+        self.values.insert(ident.to_string());
+    }
+
+    fn visit_property(&mut self, prop: &Property) {
+        match &prop.key {
+            PropertyKey::Expr(key) if prop.computed => self.visit_expr(key),
+            PropertyKey::Pat(pat) => self.visit_pat(pat, false),
+            _ => {}
+        }
+
+        match (&prop.value, &prop.key) {
+            (PropertyValue::Expr(expr), _) => self.visit_expr(expr),
+            (PropertyValue::Pat(pat), _) => self.visit_pat(pat, false),
+            (PropertyValue::None, PropertyKey::Expr(Expr::Ident(ident))) if prop.short_hand => self.reference(ident),
+            (PropertyValue::None, _) => {}
+        }
+    }
+
+    fn visit_assignment_target(&mut self, expr: &Expr) {
+        let mut idents = vec![];
+        collect_expr_bindings(expr, &mut idents);
+        self.assigned.extend(idents);
+        self.visit_expr(expr);
+    }
+
+    fn visit_exprs<'a, I>(&mut self, exprs: I)
+        where I: IntoIterator<Item = &'a Expr>
+    {
+        exprs.into_iter().for_each(|expr| self.visit_expr(expr));
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Array(array) => self.visit_exprs(array.iter().flatten()),
+            Expr::ArrowFunction(arrow) => self.visit_arrow_func(arrow),
+            Expr::ArrowParamPlaceHolder(args, _) => for arg in args.iter() {
+                if let FunctionArg::Expr(expr) = arg {
+                    self.visit_expr(expr);
+                }
+            },
+            Expr::Assignment(assign) => {
+                match &assign.left {
+                    AssignmentLeft::Pat(pat) => {
+                        self.assigned.extend(bound_identifiers(pat));
+                        self.visit_pat(pat, false);
+                    },
+                    AssignmentLeft::Expr(expr) => self.visit_assignment_target(expr)
+                }
+                self.visit_expr(&assign.right);
+            },
+            Expr::Await(expr) | Expr::Spread(expr) => self.visit_expr(expr),
+            Expr::Binary(BinaryExpr { left, right, .. }) |
+            Expr::Logical(LogicalExpr { left, right, .. }) => self.visit_exprs(vec![left.as_ref(), right.as_ref()]),
+            Expr::Class(class) => self.visit_class(class),
+            // Calls of function declarations do not need their value
+            Expr::Call(CallExpr { callee, arguments }) => {
+                match callee.borrow() {
+                    Expr::Ident(ident) => { self.referenced.insert(ident.to_string()); },
+                    callee => self.visit_expr(callee)
+                }
+                self.visit_exprs(arguments.iter());
+            },
+            Expr::New(NewExpr { callee, arguments }) => {
+                self.visit_expr(callee);
+                self.visit_exprs(arguments.iter());
+            },
+            Expr::Conditional(cond) => self.visit_exprs(vec![cond.test.as_ref(), cond.consequent.as_ref(), cond.alternate.as_ref()]),
+            Expr::Function(func) => self.visit_func(func, true),
+            Expr::Ident(ident) => self.reference(ident),
+            Expr::Literal(Literal::Template(template)) => self.visit_exprs(template.expressions.iter()),
+            Expr::Literal(_) => {},
+            Expr::Member(member) => {
+                self.visit_expr(&member.object);
+                if member.computed {
+                    self.visit_expr(&member.property);
+                }
+            },
+            Expr::Super => { self.referenced.insert(CLASS_BINDING.into()); },
+            Expr::MetaProperty(_) | Expr::This => {},
+            Expr::Object(props) => for prop in props.iter() {
+                match prop {
+                    ObjectProperty::Property(prop) => self.visit_property(prop),
+                    ObjectProperty::Spread(expr) => self.visit_expr(expr)
+                }
+            },
+            Expr::Sequence(exprs) => self.visit_exprs(exprs.iter()),
+            Expr::TaggedTemplate(tagged) => {
+                self.visit_expr(&tagged.tag);
+                self.visit_exprs(tagged.quasi.expressions.iter());
+            },
+            Expr::Unary(UnaryExpr { argument, .. }) => self.visit_expr(argument),
+            Expr::Update(UpdateExpr { argument, .. }) => self.visit_assignment_target(argument),
+            Expr::Yield(yield_expr) => if let Some(arg) = &yield_expr.argument { self.visit_expr(arg) },
+        }
+    }
+}
+
 #[test]
 fn test_scopes() {
     let mut scopes = Scopes::new();
@@ -260,9 +1236,10 @@ fn test_scopes() {
         let r2 = scopes.add_var_decl("anotherVar".into()).unwrap();
         let rx = scopes.current_scope_mut().unwrap().get_unused_register().unwrap();
         let rxb = scopes.current_scope_mut().unwrap().get_unused_register_back().unwrap();
-        assert_ne!(r0, r1);
+        // Functions have their own frame registers
+        assert_eq!(r0, r1);
         assert_ne!(r1, r2);
-        assert_eq!(rx, 3);
+        assert_eq!(rx, 2);
         assert_eq!(rxb, 255);
         assert_eq!(scopes.get_var("testVar").unwrap().register, r1);
         assert_eq!(scopes.get_var("anotherVar").unwrap().register, r2);
@@ -272,7 +1249,126 @@ fn test_scopes() {
     assert!(scopes.get_var("testVar").is_err());
     assert!(scopes.get_var("anotherVar").is_err());
 
+    let shared = scopes.add_shared_decl("sharedVar".into(), DeclarationType::Variable(MyVariableKind::Var)).unwrap();
+    assert!(shared >= FRAME_REGISTERS_END);
+    scopes.enter_new_scope().unwrap();
+        assert_eq!(scopes.get_var("sharedVar").unwrap().register, shared);
+        assert_ne!(scopes.current_scope_mut().unwrap().get_unused_register_back().unwrap(), shared);
+    assert!(scopes.leave_current_scope().is_ok());
+
     assert!(scopes.leave_current_scope().is_ok());
 
     assert!(scopes.current_scope().is_err());
 }
+
+#[test]
+fn test_captured_identifiers() {
+    use crate::jshelper::{JSSourceCode, JSAst};
+
+    let captured = |js_code: &str| -> Vec<String> {
+        let body = match JSAst::parse(&JSSourceCode::from_str(js_code)).unwrap().ast {
+            Program::Script(body) => body,
+            Program::Mod(_) => unreachable!()
+        };
+        let mut idents: Vec<String> = captured_identifiers(&[], &body).into_iter().collect();
+        idents.sort();
+        idents
+    };
+
+    assert!(captured("var a = 0; a += 1;").is_empty());
+    assert_eq!(captured("var a, b; function f() { return a; }"), vec!["a"]);
+    assert_eq!(captured("var c = 0; var inc = () => c++;"), vec!["c"]);
+    // Declarations of the closure itself are not captured
+    assert!(captured("var a; function f(a) { var b; return a + b; }").is_empty());
+    // Free identifiers of nested closures are captured by all enclosing functions
+    assert_eq!(captured("var a; function f() { return function() { return a + x.b; }; }"), vec!["a", "x"]);
+    // The name of a function expression is bound inside of it
+    assert!(captured("var g = function g() { return g; };").is_empty());
+    // Methods using 'super' capture the class they are defined in
+    assert_eq!(captured("class A extends B { m() { return super.m(); } }"), vec!["#class"]);
+}
+
+#[test]
+fn test_uses_arguments_object() {
+    use crate::jshelper::{JSSourceCode, JSAst};
+
+    let uses_arguments = |js_code: &str| -> bool {
+        let body = match JSAst::parse(&JSSourceCode::from_str(js_code)).unwrap().ast {
+            Program::Script(body) => body,
+            Program::Mod(_) => unreachable!()
+        };
+        uses_arguments_object(&[], &body)
+    };
+
+    assert!(uses_arguments("var n = arguments.length;"));
+    assert!(!uses_arguments("var arguments = []; var n = arguments.length;"));
+    // Arrow functions use the 'arguments' object of their enclosing function, functions have their own
+    assert!(uses_arguments("var f = () => arguments[0];"));
+    assert!(!uses_arguments("var f = function() { return arguments[0]; };"));
+}
+
+#[test]
+fn test_lexical_declarations() {
+    use crate::jshelper::{JSSourceCode, JSAst};
+
+    let lexical_decls = |js_code: &str| -> Vec<(String, MyVariableKind)> {
+        match JSAst::parse(&JSSourceCode::from_str(js_code)).unwrap().ast {
+            Program::Script(body) => lexical_declarations(body.iter()),
+            Program::Mod(_) => unreachable!()
+        }
+    };
+
+    assert!(lexical_decls("var a; { let b; }").is_empty());
+    assert_eq!(lexical_decls("let a, {b, c: [d], e = 1, ...f} = o; const g = 0;"), vec![
+        ("a".into(), MyVariableKind::Let), ("b".into(), MyVariableKind::Let), ("d".into(), MyVariableKind::Let),
+        ("e".into(), MyVariableKind::Let), ("f".into(), MyVariableKind::Let), ("g".into(), MyVariableKind::Const)
+    ]);
+    assert_eq!(lexical_decls("class A {} var B = class C {};"), vec![("A".into(), MyVariableKind::Let)]);
+}
+
+#[test]
+fn test_block_function_declarations() {
+    use crate::jshelper::{JSSourceCode, JSAst};
+
+    let parse = |js_code: &str| -> Vec<ProgramPart> {
+        match JSAst::parse(&JSSourceCode::from_str(js_code)).unwrap().ast {
+            Program::Script(body) => body,
+            Program::Mod(_) => unreachable!()
+        }
+    };
+
+    let body = parse("function a() {} if (x) { function b() {} } else { while (y) { function c() { function d() {} } } }");
+    assert_eq!(block_function_declarations(body.iter()), vec!["b", "c"]);
+
+    let sorted = |idents: HashSet<String>| -> Vec<String> {
+        let mut idents: Vec<String> = idents.into_iter().collect();
+        idents.sort();
+        idents
+    };
+    // Functions declared in blocks are assigned to the declaration of their function
+    assert_eq!(sorted(assigned_identifiers(parse("var a = 1, b; c = 2; d++; [e] = f; { function g() {} }").iter())),
+               vec!["a", "c", "d", "e", "g"]);
+    // Only called functions are no values
+    assert_eq!(sorted(value_references(parse("f(); g(h); new i(); o.m();").iter())), vec!["h", "i", "o"]);
+}
+
+#[test]
+fn test_tdz() {
+    let mut scopes = Scopes::new();
+
+    scopes.enter_new_block_scope().unwrap();
+        scopes.add_decl("a".into(), DeclarationType::Variable(MyVariableKind::Let)).unwrap();
+        scopes.add_var_decl("b".into()).unwrap();
+        assert_eq!(scopes.get_var("a").unwrap().tdz, Tdz::Definite);
+        assert_eq!(scopes.get_var("b").unwrap().tdz, Tdz::Passed);
+        scopes.initialize_decl("a").unwrap();
+        assert_eq!(scopes.get_var("a").unwrap().tdz, Tdz::Passed);
+    scopes.leave_current_block_scope().unwrap();
+
+    // Jumps to the cases of a switch statement might skip initializations
+    scopes.enter_new_switch_scope().unwrap();
+        scopes.add_decl("c".into(), DeclarationType::Variable(MyVariableKind::Const)).unwrap();
+        scopes.initialize_decl("c").unwrap();
+        assert_eq!(scopes.get_var("c").unwrap().tdz, Tdz::Unknown);
+    scopes.leave_current_block_scope().unwrap();
+}
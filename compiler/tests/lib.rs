@@ -96,6 +96,10 @@ fn test_compile_js_decls() {
         .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(0), Operand::String("Hello World".into())]))
     );
 
+    run_test("var r = /\\d+/g;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadRegExp, reg!(0), string!("\\d+"), string!("g")))
+    );
+
     // TODO: null !== undefined
     // run_test("var xxx = null;", BytecodeCompiler::new(), Bytecode::new()
     //     .add(op!(LoadNum, reg!(0), reg!(253)))
@@ -103,33 +107,32 @@ fn test_compile_js_decls() {
 
     run_test("function foo() {}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
     run_test("function foo(a) {}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
     run_test("function foo(a, b) {}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
     run_test("function foo(a) {return a;}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0)]))
     );
     run_test("function foo(a, b) {a+=b; return a;}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Exit, vec![]))
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(1)]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0)]))
     );
 
+    // External declarations are placed in registers shared by all frames
     run_test_deps("var a = document.cookie;", &["document"], Bytecode::new()
-        .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("cookie".into())]))
-        .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(0), Operand::Reg(1), Operand::Reg(2)]))
+        .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(1), Operand::String("cookie".into())]))
+        .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(0), Operand::Reg(252), Operand::Reg(1)]))
     );
 
-    check_is_unsupported_error("class C {}", BytecodeCompiler::new());
-
     check_is_unsupported_error("import foo from \"bar.js;\"", BytecodeCompiler::new());
     check_is_unsupported_error("export {foo}", BytecodeCompiler::new());
 }
@@ -137,78 +140,122 @@ fn test_compile_js_decls() {
 #[test]
 fn test_bytecode_func_calls() {
     run_test("function test() {}; test();", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(8), Operand::Reg(202), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(9), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
 
     run_test("function foo() {}; function bar() {}; foo();bar();", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(15), Operand::Reg(202), Operand::RegistersArray(vec![])]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(18), Operand::Reg(202), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(17), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(19), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
 
     run_test("function foo() {var a = 5;}; function bar() {}; foo();bar();", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(15), Operand::Reg(202), Operand::RegistersArray(vec![])]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(21), Operand::Reg(202), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(17), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(22), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(5)]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
 
+    // 'a' is captured by 'foo' and thus lives in the environment
     run_test("var a = 5; function foo() {a = 10;}; foo();", BytecodeCompiler::new(), Bytecode::new()
+        .add(Operation::new(Instruction::CreateEnvironment, vec![]))
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(5)]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(11), Operand::Reg(202), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::StoreCaptured, vec![Operand::ShortNum(0), Operand::ShortNum(0), Operand::Reg(0)]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(17), Operand::Reg(202), Operand::RegistersArray(vec![]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![0])]))
+        .add(Operation::new(Instruction::StoreCaptured, vec![Operand::ShortNum(0), Operand::ShortNum(0), Operand::Reg(0)]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
 
     run_test("function testy(a) {} testy(10);", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(13), Operand::Reg(202), Operand::RegistersArray(vec![0, 0])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(14), Operand::Reg(202), Operand::RegistersArray(vec![0, 0]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(253)]))
     );
 
     run_test("function testy(a) {return a;} testy(10);", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(13), Operand::Reg(202), Operand::RegistersArray(vec![0, 0])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(14), Operand::Reg(202), Operand::RegistersArray(vec![0, 0]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0)]))
     );
 
+    // Functions have their own frame, thus 'a' and 'x' share a register number
     run_test("var x = 10; function testy(a) {return a;} testy(x);", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
-        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(13), Operand::Reg(202), Operand::RegistersArray(vec![1, 0])]))
+        .add(Operation::new(Instruction::CallBytecodeFunc, vec![Operand::LongNum(14), Operand::Reg(202), Operand::RegistersArray(vec![0, 0]), Operand::ShortNum(0)]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(1), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0)]))
+    );
+
+    // Every call has its own frame, thus recursive calls do not overwrite the registers of their caller
+    run_test("function fib(n) { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); } var r = fib(10);",
+             BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateEnvironment,))
+        .add(op!(LoadNum, reg!(1), short_num!(10)))
+        .add(op!(CallBytecodeFunc, long_num!(15), reg!(0), reg_arr!(0, 1), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(LoadNum, reg!(2), short_num!(2)))
+        .add(op!(CompLessThan, reg!(1), reg!(0), reg!(2)))
+        .add(op!(JumpCondNeg, reg!(1), long_num!(30)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+        .add_label(0)
+        .add(op!(Minus, reg!(3), reg!(0), reg!(254)))
+        .add(op!(CallBytecodeFunc, long_num!(15), reg!(2), reg_arr!(0, 3), short_num!(0)))
+        // The registers of 'n - 1' are released once the first call is compiled
+        .add(op!(LoadNum, reg!(5), short_num!(2)))
+        .add(op!(Minus, reg!(4), reg!(0), reg!(5)))
+        .add(op!(CallBytecodeFunc, long_num!(15), reg!(3), reg_arr!(0, 4), short_num!(0)))
+        .add(op!(Add, reg!(1), reg!(2), reg!(3)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
     );
 
     run_test_deps("function testy(a) {return a;}; var interval = setInterval(testy, 60);", &["setInterval"], Bytecode::new()
-        .add(Operation::new(Instruction::BytecodeFuncCallback, vec![Operand::Reg(2), Operand::LongNum(19), Operand::RegistersArray(vec![0])]))
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(3), Operand::ShortNum(60)]))
-        .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(0), Operand::Reg(1),
-                                                      Operand::Reg(253), Operand::RegistersArray(vec![2, 3])]))
+        .add(Operation::new(Instruction::BytecodeFuncCallback, vec![Operand::Reg(1), Operand::LongNum(20), Operand::RegistersArray(vec![0]), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(2), Operand::ShortNum(60)]))
+        .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(0), Operand::Reg(252),
+                                                      Operand::Reg(253), Operand::RegistersArray(vec![1, 2])]))
         .add(Operation::new(Instruction::Exit, vec![]))
-        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0), Operand::RegistersArray(vec![])]))
+        .add(Operation::new(Instruction::ReturnBytecodeFunc, vec![Operand::Reg(0)]))
+    );
+
+    // The surplus argument is passed to the trash register and collected by the rest parameter
+    run_test("function f(a, b = 2, ...r) { return arguments; } f(1, 2, 3);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadNum, reg!(0), short_num!(2)))
+        .add(op!(LoadNum, reg!(1), short_num!(3)))
+        .add(op!(CallBytecodeFunc, long_num!(21), reg!(202), reg_arr!(0, 254, 1, 0, 202, 1), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(LoadRestArguments, reg!(2), short_num!(2)))
+        .add(op!(LoadArguments, reg!(3)))
+        .add(op!(CompStrictEqual, reg!(4), reg!(1), reg!(253)))
+        .add(op!(JumpCondNeg, reg!(4), long_num!(39)))
+        .add(op!(LoadNum, reg!(1), short_num!(2)))
+        .add_label(0)
+        .add(op!(Copy, reg!(5), reg!(1)))
+        .add(op!(Copy, reg!(4), reg!(2)))
+        .add(op!(ReturnBytecodeFunc, reg!(3)))
     );
 }
 
 #[test]
 fn test_jump_stmts() {
     run_test("var a = false; if(a){a+=a;}", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(254)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(13)]))
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(0)]))
         .add_label(0)
     );
 
     run_test("var a = false; if(a){a+=a;}else{a+=2}", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(254)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(18)]))
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(0)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(25)]))
@@ -219,18 +266,19 @@ fn test_jump_stmts() {
     );
 
     run_test("var a = true; while(a){a=false;}", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(1)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(255)]))
         .add_label(0)
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(17)]))
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(254)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(1)
     );
 
     run_test("var a = true; do{a=false;}while(a)", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(1)]))
-        .add_label(0)
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(0)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(255)]))
+        .add_label(2)
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(254)]))
+        .add_label(0) // 'continue' target
         .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(0), Operand::LongNum(3)]))
         .add_label(1)
     );
@@ -239,7 +287,7 @@ fn test_jump_stmts() {
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
         // Init
         .add(Operation::new(Instruction::Copy, vec![Operand::Reg(1), Operand::Reg(255)]))
-        .add_label(0)
+        .add_label(2)
         // Comp
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(3), Operand::ShortNum(10)]))
         .add(Operation::new(Instruction::CompLessThan, vec![Operand::Reg(2), Operand::Reg(1), Operand::Reg(3)]))
@@ -247,6 +295,7 @@ fn test_jump_stmts() {
         // Body
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(254)]))
         // Update
+        .add_label(0) // 'continue' target
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(1), Operand::Reg(1), Operand::Reg(254)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
         .add_label(1)
@@ -258,7 +307,7 @@ fn test_jump_stmts() {
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
         // Init
         .add(Operation::new(Instruction::Copy, vec![Operand::Reg(1), Operand::Reg(255)]))
-        .add_label(0)
+        .add_label(2)
         // Comp
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(3), Operand::ShortNum(10)]))
         .add(Operation::new(Instruction::CompLessThan, vec![Operand::Reg(2), Operand::Reg(1), Operand::Reg(3)]))
@@ -266,6 +315,7 @@ fn test_jump_stmts() {
         // Body
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(254)]))
         // Update
+        .add_label(0) // 'continue' target
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(1), Operand::Reg(1), Operand::Reg(254)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
         .add_label(1)
@@ -277,10 +327,11 @@ fn test_jump_stmts() {
         .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
         // Init
         .add(Operation::new(Instruction::Copy, vec![Operand::Reg(1), Operand::Reg(255)]))
-        .add_label(0)
+        .add_label(2)
         // Body
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(254)]))
         // Update
+        .add_label(0) // 'continue' target
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
         .add_label(1)
         // Check that i still exists
@@ -290,23 +341,24 @@ fn test_jump_stmts() {
     run_test("var i = 0; for(;;){++i}", BytecodeCompiler::new(), Bytecode::new()
         .add(Operation::new(Instruction::Copy, vec![Operand::Reg(0), Operand::Reg(255)]))
         // Init
-        .add_label(0)
+        .add_label(2)
         // Body
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(254)]))
         // Update
+        .add_label(0) // 'continue' target
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(1)
     );
 
     let break_testcase_bytecode = Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(1)]))
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(1), Operand::ShortNum(1)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(255)]))
         .add_label(0)
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(1), Operand::Reg(255)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(1), Operand::LongNum(28)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(23)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(28)]))
         .add_label(2) // If block end label
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(1); // while block end label
 
     // Test 'break'
@@ -319,45 +371,156 @@ fn test_jump_stmts() {
 
     // Test 'continue'
     run_test("var b = true; while(true) { if(b) {continue;} }", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(1)]))
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(1), Operand::ShortNum(1)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(255)]))
         .add_label(0)
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(1), Operand::Reg(255)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(1), Operand::LongNum(28)]))
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(23)]))
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(2) // If block end label
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(1) // while block end label
     );
 
     // Test labeled 'continue'
     run_test("var b = true; foo: while(true) { var x = 0; for(;;) { if(b) {continue;} } }", BytecodeCompiler::new(), Bytecode::new()
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(1)]))
-        .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(1), Operand::ShortNum(1)]))
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(0), Operand::Reg(255)]))
         .add_label(0)
-        .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(1), Operand::LongNum(36)]))
-        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(2), Operand::Reg(255)]))
-        .add_label(2)
+        .add(Operation::new(Instruction::Not, vec![Operand::Reg(2), Operand::Reg(255)]))
+        .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(2), Operand::LongNum(36)]))
+        // 'x' is hoisted, thus its register is reserved before the loop
+        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(1), Operand::Reg(255)]))
+        .add_label(4)
         .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(0), Operand::LongNum(26)]))
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(15)]))
-        .add_label(4) // If block end label
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(26)])) // continue jump to the update
+        .add_label(5) // If block end label
+        .add_label(2)
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(15)]))
         .add_label(3)
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(6)]))
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(3)]))
         .add_label(1) // while block end label
     );
 
     run_test("outer: for(;;){ for(;;) {break outer;} }", BytecodeCompiler::new(), Bytecode::new()
-        .add_label(0)
         .add_label(2)
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(10)])) // break jump
-        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(0)]))
+        .add_label(5)
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(15)])) // break jump
         .add_label(3)
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(0)]))
+        .add_label(4)
+        .add_label(0)
+        .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(0)]))
         .add_label(1)
     );
 }
 
+#[test]
+fn test_switch_stmt() {
+    run_test("var x = 5; var y = 0; switch (x) { case 1: y = 2; case y: y = 3; break; default: y = 4; }",
+             BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadNum, reg!(0), short_num!(5)))
+        .add(op!(Copy, reg!(1), reg!(255)))
+        .add(op!(CompStrictEqual, reg!(2), reg!(0), reg!(254)))
+        .add(op!(JumpCond, reg!(2), addr!(31)))
+        .add(op!(CompStrictEqual, reg!(2), reg!(0), reg!(1)))
+        .add(op!(JumpCond, reg!(2), addr!(34)))
+        .add(op!(Jump, addr!(42))) // No match, jump to default
+        .add_label(2)
+        .add(op!(LoadNum, reg!(1), short_num!(2)))
+        .add_label(3)
+        .add(op!(LoadNum, reg!(1), short_num!(3)))
+        .add(op!(Jump, addr!(45))) // break
+        .add_label(4)
+        .add(op!(LoadNum, reg!(1), short_num!(4)))
+        .add_label(1)
+    );
+
+    // Dense integer cases are dispatched through a jump table
+    run_test("var x = 5; var y = 0; switch (x) { case 0: case 1: y = 1; break; case 3: y = 3; default: y = 4; case 4: y = 5; }",
+             BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadNum, reg!(0), short_num!(5)))
+        .add(op!(Copy, reg!(1), reg!(255)))
+        .add(op!(JumpTable, reg!(0), long_num!(0), short_num!(5), addr!(38), addr!(38), addr!(49), addr!(46), addr!(52)))
+        .add(op!(Jump, addr!(49)))
+        .add_label(2)
+        .add_label(3)
+        .add(op!(LoadNum, reg!(1), short_num!(1)))
+        .add(op!(Jump, addr!(55)))
+        .add_label(4)
+        .add(op!(LoadNum, reg!(1), short_num!(3)))
+        .add_label(5)
+        .add(op!(LoadNum, reg!(1), short_num!(4)))
+        .add_label(6)
+        .add(op!(LoadNum, reg!(1), short_num!(5)))
+        .add_label(1)
+    );
+
+    // 'continue' skips the switch block
+    run_test("for(;;) { switch (1) { case 1: continue; } }", BytecodeCompiler::new(), Bytecode::new()
+        .add_label(2)
+        .add(op!(CompStrictEqual, reg!(0), reg!(254), reg!(254)))
+        .add(op!(JumpCond, reg!(0), addr!(15)))
+        .add(op!(Jump, addr!(20)))
+        .add_label(5)
+        .add(op!(Jump, addr!(20)))
+        .add_label(4)
+        .add_label(0)
+        .add(op!(Jump, addr!(0)))
+        .add_label(1)
+    );
+}
+
+#[test]
+fn test_for_in_of_stmts() {
+    run_test("var o = {}; for (var k in o) { if (k) { break; } } for (k of o) { continue; }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(GetKeysIterator, reg!(2), reg!(0)))
+        .add_label(0)
+        .add(op!(IteratorNext, reg!(3), reg!(1), reg!(2)))
+        .add(op!(JumpCond, reg!(3), addr!(31)))
+        .add(op!(JumpCondNeg, reg!(1), addr!(26)))
+        .add(op!(Jump, addr!(31))) // break
+        .add_label(2)
+        .add(op!(Jump, addr!(5)))
+        .add_label(1)
+        // The existing declaration 'k' receives the values
+        .add(op!(GetIterator, reg!(2), reg!(0)))
+        .add(op!(LoadNum, reg!(4), short_num!(0)))
+        .add(op!(TryFinally, reg!(5), addr!(69)))
+        .add_label(5)
+        .add(op!(IteratorNext, reg!(3), reg!(1), reg!(2)))
+        .add(op!(JumpCond, reg!(3), addr!(63)))
+        .add(op!(Jump, addr!(43))) // continue
+        .add(op!(Jump, addr!(43)))
+        .add_label(6)
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(72)))
+        .add_label(7)
+        .add(op!(LoadNum, reg!(4), short_num!(1)))
+        .add_label(8)
+        .add_label(4)
+        // The iterator is closed unless it is done...
+        .add(op!(JumpCond, reg!(3), addr!(110)))
+        .add(op!(JumpTable, reg!(4), long_num!(1), short_num!(1), addr!(96)))
+        .add(op!(IteratorClose, reg!(2)))
+        .add(op!(Jump, addr!(110)))
+        .add_label(10)
+        // ...and its errors are ignored if the loop is left by an exception
+        .add(op!(Try, reg!(202), addr!(110)))
+        .add(op!(IteratorClose, reg!(2)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(110)))
+        .add_label(11)
+        .add_label(12)
+        .add_label(9)
+        .add(op!(JumpTable, reg!(4), long_num!(1), short_num!(2), addr!(130), addr!(132)))
+        .add(op!(Jump, addr!(132)))
+        .add_label(14)
+        .add(op!(Throw, reg!(5)))
+        .add_label(13)
+    );
+}
+
 #[test]
 fn test_assigmnet_expr() {
     let mut compiler = BytecodeCompiler::new();
@@ -365,11 +528,205 @@ fn test_assigmnet_expr() {
     assert!(compiler.add_var_decl("b".into()).is_ok());
 
     run_test("a+=b;", compiler.clone(), Bytecode::new()
-        .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(1)]))
+        .add(Operation::new(Instruction::Add, vec![Operand::Reg(252), Operand::Reg(252), Operand::Reg(251)]))
     );
 
     run_test("a*=b;", compiler.clone(), Bytecode::new()
-        .add(Operation::new(Instruction::Mul, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(1)]))
+        .add(Operation::new(Instruction::Mul, vec![Operand::Reg(252), Operand::Reg(252), Operand::Reg(251)]))
+    );
+
+    // Assignments result in the assigned value
+    run_test("var c = 0; c = a = b; var o = {}; c = (o.x = a); c = (a -= b);", compiler.clone(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(255)))
+        .add(op!(Copy, reg!(252), reg!(251)))
+        .add(op!(Copy, reg!(0), reg!(252)))
+        .add(op!(CreateObject, reg!(1)))
+        .add(op!(LoadString, reg!(2), string!("x")))
+        .add(op!(PropertySet, reg!(1), reg!(2), reg!(252)))
+        .add(op!(Copy, reg!(0), reg!(252)))
+        .add(op!(Minus, reg!(252), reg!(252), reg!(251)))
+        .add(op!(Copy, reg!(0), reg!(252)))
+    );
+
+    run_test("a%=b; a<<=b; a>>=b; a>>>=b; a|=b; a^=b; a&=b; a**=b;", compiler.clone(), Bytecode::new()
+        .add(op!(Mod, reg!(252), reg!(252), reg!(251)))
+        .add(op!(LeftShift, reg!(252), reg!(252), reg!(251)))
+        .add(op!(RightShift, reg!(252), reg!(252), reg!(251)))
+        .add(op!(UnsignedRightShift, reg!(252), reg!(252), reg!(251)))
+        .add(op!(BitOr, reg!(252), reg!(252), reg!(251)))
+        .add(op!(BitXOr, reg!(252), reg!(252), reg!(251)))
+        .add(op!(BitAnd, reg!(252), reg!(252), reg!(251)))
+        .add(op!(PowerOf, reg!(252), reg!(252), reg!(251)))
+    );
+
+    // Left operands are read before the right side assigns their declarations
+    run_test("var u = 1; u = u + (u = 5); u += (u = 2);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(Copy, reg!(1), reg!(0)))
+        .add(op!(LoadNum, reg!(0), short_num!(5)))
+        .add(op!(Copy, reg!(2), reg!(0)))
+        .add(op!(Add, reg!(0), reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(1), reg!(0)))
+        .add(op!(LoadNum, reg!(0), short_num!(2)))
+        .add(op!(Copy, reg!(2), reg!(0)))
+        .add(op!(Add, reg!(1), reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+    );
+
+    run_test("var p = {}; p.x = p = 5; p = {q: p};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(Copy, reg!(1), reg!(0)))
+        .add(op!(LoadString, reg!(2), string!("x")))
+        .add(op!(LoadNum, reg!(0), short_num!(5)))
+        .add(op!(Copy, reg!(3), reg!(0)))
+        .add(op!(PropertySet, reg!(1), reg!(2), reg!(3)))
+        // The object is created in a temporary register, since its value is the old object
+        .add(op!(LoadString, reg!(1), string!("q")))
+        .add(op!(CreateObject, reg!(2)))
+        .add(op!(DefineProperty, reg!(2), reg!(1), reg!(0)))
+        .add(op!(Copy, reg!(0), reg!(2)))
+    );
+}
+
+#[test]
+fn test_destructuring() {
+    // The declarations are hoisted, thus their registers precede the temporary ones
+    run_test("var o = {}; var {a, b: [c, , d = 1], ...e} = o;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(Copy, reg!(5), reg!(0)))
+        .add(op!(LoadString, reg!(6), string!("a")))
+        .add(op!(PropAccess, reg!(7), reg!(5), reg!(6)))
+        .add(op!(Copy, reg!(1), reg!(7)))
+        .add(op!(LoadString, reg!(8), string!("b")))
+        .add(op!(PropAccess, reg!(9), reg!(5), reg!(8)))
+        .add(op!(GetIterator, reg!(10), reg!(9)))
+        .add(op!(Copy, reg!(11), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(11), reg!(10)))
+        .add(op!(Copy, reg!(2), reg!(11)))
+        // The hole skips a value
+        .add(op!(IteratorNext, reg!(202), reg!(202), reg!(10)))
+        .add(op!(Copy, reg!(12), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(12), reg!(10)))
+        .add(op!(CompStrictEqual, reg!(13), reg!(12), reg!(253)))
+        .add(op!(JumpCondNeg, reg!(13), addr!(63)))
+        .add(op!(LoadNum, reg!(12), short_num!(1)))
+        .add_label(0)
+        .add(op!(Copy, reg!(3), reg!(12)))
+        .add(op!(ObjectRest, reg!(14), reg!(5), reg_arr!(6, 8)))
+        .add(op!(Copy, reg!(4), reg!(14)))
+    );
+
+    // The parser drops the rest marker of declarations, it is restored from the source
+    run_test("var o = []; var [a, ...r] = o;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadArray, reg!(0), reg_arr!()))
+        .add(op!(Copy, reg!(3), reg!(0)))
+        .add(op!(GetIterator, reg!(4), reg!(3)))
+        .add(op!(Copy, reg!(5), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(5), reg!(4)))
+        .add(op!(Copy, reg!(1), reg!(5)))
+        .add(op!(IteratorRest, reg!(6), reg!(4)))
+        .add(op!(Copy, reg!(2), reg!(6)))
+    );
+
+    run_test("var x = 1, y = 2; [x, y] = [y, x];", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadNum, reg!(1), short_num!(2)))
+        .add(op!(LoadArray, reg!(2), reg_arr!(1, 0)))
+        .add(op!(GetIterator, reg!(3), reg!(2)))
+        .add(op!(Copy, reg!(4), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(4), reg!(3)))
+        .add(op!(Copy, reg!(0), reg!(4)))
+        .add(op!(Copy, reg!(5), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(5), reg!(3)))
+        .add(op!(Copy, reg!(1), reg!(5)))
+    );
+
+    // The pattern argument is passed in register 1 and destructured by the prologue
+    run_test("function f(a, {b}) { return b; } f(1, {});", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(CallBytecodeFunc, addr!(15), reg!(202), reg_arr!(0, 254, 1, 0), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(LoadString, reg!(2), string!("b")))
+        .add(op!(PropAccess, reg!(3), reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(4), reg!(3)))
+        .add(op!(ReturnBytecodeFunc, reg!(4)))
+    );
+
+    run_test("try {} catch ({message}) {}", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Try, reg!(1), addr!(12)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(30)))
+        .add_label(0)
+        .add(op!(LoadString, reg!(2), string!("message")))
+        .add(op!(PropAccess, reg!(3), reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(0), reg!(3)))
+        .add_label(1)
+    );
+
+    run_test("var o = []; for (var [k, v] of o) {}", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadArray, reg!(0), reg_arr!()))
+        .add(op!(GetIterator, reg!(3), reg!(0)))
+        .add(op!(LoadNum, reg!(9), short_num!(0)))
+        .add(op!(TryFinally, reg!(10), addr!(59)))
+        .add_label(1)
+        .add(op!(IteratorNext, reg!(4), reg!(5), reg!(3)))
+        .add(op!(JumpCond, reg!(4), addr!(53)))
+        .add(op!(GetIterator, reg!(6), reg!(5)))
+        .add(op!(Copy, reg!(7), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(7), reg!(6)))
+        .add(op!(Copy, reg!(1), reg!(7)))
+        .add(op!(Copy, reg!(8), reg!(253)))
+        .add(op!(IteratorNext, reg!(202), reg!(8), reg!(6)))
+        .add(op!(Copy, reg!(2), reg!(8)))
+        .add(op!(Jump, addr!(15)))
+        .add_label(2)
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(62)))
+        .add_label(3)
+        .add(op!(LoadNum, reg!(9), short_num!(1)))
+        .add_label(4)
+        .add_label(0)
+        .add(op!(JumpCond, reg!(4), addr!(100)))
+        .add(op!(JumpTable, reg!(9), long_num!(1), short_num!(1), addr!(86)))
+        .add(op!(IteratorClose, reg!(3)))
+        .add(op!(Jump, addr!(100)))
+        .add_label(6)
+        .add(op!(Try, reg!(202), addr!(100)))
+        .add(op!(IteratorClose, reg!(3)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(100)))
+        .add_label(7)
+        .add_label(8)
+        .add_label(5)
+        .add(op!(JumpTable, reg!(9), long_num!(1), short_num!(2), addr!(120), addr!(122)))
+        .add(op!(Jump, addr!(122)))
+        .add_label(10)
+        .add(op!(Throw, reg!(10)))
+        .add_label(9)
+    );
+}
+
+#[test]
+fn test_binary_expr() {
+    let mut compiler = BytecodeCompiler::new();
+    assert!(compiler.add_var_decl("a".into()).is_ok());
+    assert!(compiler.add_var_decl("b".into()).is_ok());
+
+    run_test("var c = a % b; c = a << b; c = a >> b; c = a >>> b; c = a | b; c = a ^ b; c = a & b; c = a ** b;",
+             compiler.clone(), Bytecode::new()
+        .add(op!(Mod, reg!(0), reg!(252), reg!(251)))
+        .add(op!(LeftShift, reg!(0), reg!(252), reg!(251)))
+        .add(op!(RightShift, reg!(0), reg!(252), reg!(251)))
+        .add(op!(UnsignedRightShift, reg!(0), reg!(252), reg!(251)))
+        .add(op!(BitOr, reg!(0), reg!(252), reg!(251)))
+        .add(op!(BitXOr, reg!(0), reg!(252), reg!(251)))
+        .add(op!(BitAnd, reg!(0), reg!(252), reg!(251)))
+        .add(op!(PowerOf, reg!(0), reg!(252), reg!(251)))
+    );
+
+    run_test("var c = a in b; c = a instanceof b;", compiler.clone(), Bytecode::new()
+        .add(op!(In, reg!(0), reg!(252), reg!(251)))
+        .add(op!(InstanceOf, reg!(0), reg!(252), reg!(251)))
     );
 }
 
@@ -379,14 +736,15 @@ fn test_member_expr() {
     assert!(compiler.add_var_decl("document".into()).is_ok());
 
     run_test("var t = document.test", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("test".into())]))
-                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(1), Operand::Reg(0), Operand::Reg(2)])));
+                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(1), Operand::String("test".into())]))
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(0), Operand::Reg(252), Operand::Reg(1)])));
 
     run_test("var t = document.test; var a = document.test", compiler.clone(), Bytecode::new()
                 .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("test".into())]))
-                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(1), Operand::Reg(0), Operand::Reg(2)]))
-                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(4), Operand::String("test".into())]))
-                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(3), Operand::Reg(0), Operand::Reg(4)])));
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(0), Operand::Reg(252), Operand::Reg(2)]))
+                // The temporary register of the first statement is reused
+                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("test".into())]))
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(1), Operand::Reg(252), Operand::Reg(2)])));
 
     // Assignment expression 'equal'
     let mut assignments_compiler = BytecodeCompiler::new();
@@ -394,9 +752,27 @@ fn test_member_expr() {
     assert!(assignments_compiler.add_var_decl("foo".into()).is_ok());
 
     run_test("test = 0;", assignments_compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(0)])));
+                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(252), Operand::ShortNum(0)])));
     run_test("test = foo;", assignments_compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::Copy, vec![Operand::Reg(0), Operand::Reg(1)])));
+                .add(Operation::new(Instruction::Copy, vec![Operand::Reg(252), Operand::Reg(251)])));
+
+    // Compound assignments to members
+    run_test("var o = {}; var k = \"y\"; var a = 1; o.x += a; var c = (o[k] *= f());", BytecodeCompiler::new(), Bytecode::new()
+                .add(op!(CreateObject, reg!(0)))
+                .add(op!(LoadString, reg!(1), string!("y")))
+                .add(op!(Copy, reg!(2), reg!(254)))
+                .add(op!(LoadString, reg!(4), string!("x")))
+                .add(op!(PropAccess, reg!(5), reg!(0), reg!(4)))
+                .add(op!(Add, reg!(5), reg!(5), reg!(2)))
+                .add(op!(PropertySet, reg!(0), reg!(4), reg!(5)))
+                // The call might change 'o' or 'k'
+                .add(op!(Copy, reg!(4), reg!(0)))
+                .add(op!(Copy, reg!(5), reg!(1)))
+                .add(op!(PropAccess, reg!(6), reg!(4), reg!(5)))
+                .add(op!(CallFunc, reg!(7), reg!(252), reg!(253), reg_arr![]))
+                .add(op!(Mul, reg!(6), reg!(6), reg!(7)))
+                .add(op!(PropertySet, reg!(4), reg!(5), reg!(6)))
+                .add(op!(Copy, reg!(3), reg!(6))));
 }
 
 #[test]
@@ -407,12 +783,12 @@ fn test_cond_expr() {
     compiler.add_var_decl("b".into()).unwrap();
 
     run_test("var result = (test > 0) ? a : b;", compiler, Bytecode::new()
-        .add(Operation::new(Instruction::CompGreaterThan, vec![Operand::Reg(4), Operand::Reg(0), Operand::Reg(255)]))
-        .add(Operation::new(Instruction::JumpCond, vec![Operand::Reg(4), Operand::LongNum(18)]))
-        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(3), Operand::Reg(1)]))
+        .add(Operation::new(Instruction::CompGreaterThan, vec![Operand::Reg(1), Operand::Reg(252), Operand::Reg(255)]))
+        .add(Operation::new(Instruction::JumpCondNeg, vec![Operand::Reg(1), Operand::LongNum(18)]))
+        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(0), Operand::Reg(251)]))
         .add(Operation::new(Instruction::Jump, vec![Operand::LongNum(21)]))
         .add_label(0)
-        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(3), Operand::Reg(2)]))
+        .add(Operation::new(Instruction::Copy, vec![Operand::Reg(0), Operand::Reg(250)]))
         .add_label(1)
     );
 }
@@ -428,8 +804,74 @@ fn test_unary_expr() {
         .add(Operation::new(Instruction::Add, vec![Operand::Reg(0), Operand::Reg(0), Operand::Reg(254)]))
     );
 
-    // Suffix update expressions
-    check_is_unsupported_error("a++;", BytecodeCompiler::new());
+    let mut compiler = BytecodeCompiler::new();
+    assert!(compiler.add_var_decl("a".into()).is_ok());
+
+    run_test("var b = !a; b = ~a; b = typeof a;", compiler.clone(), Bytecode::new()
+        .add(op!(Not, reg!(0), reg!(252)))
+        .add(op!(BitNot, reg!(0), reg!(252)))
+        .add(op!(TypeOf, reg!(0), reg!(252)))
+    );
+
+    run_test("var b = delete a.x; b = delete a[b]; b = delete 1;", compiler.clone(), Bytecode::new()
+        .add(op!(LoadString, reg!(1), string!("x")))
+        .add(op!(DeleteProperty, reg!(0), reg!(252), reg!(1)))
+        .add(op!(DeleteProperty, reg!(0), reg!(252), reg!(0)))
+        .add(op!(Not, reg!(0), reg!(255)))
+    );
+
+    check_is_unsupported_error("delete a;", compiler.clone());
+
+    // Booleans are real booleans, not the numbers 0 and 1
+    run_test("var b = typeof true; b = !false;", compiler.clone(), Bytecode::new()
+        .add(op!(Not, reg!(1), reg!(255)))
+        .add(op!(TypeOf, reg!(0), reg!(1)))
+        .add(op!(Not, reg!(1), reg!(254)))
+        .add(op!(Not, reg!(0), reg!(1)))
+    );
+
+    // Unary plus converts to a number instead of concatenating strings
+    run_test("var b = +a;", compiler.clone(), Bytecode::new()
+        .add(op!(Minus, reg!(0), reg!(252), reg!(255)))
+    );
+}
+
+#[test]
+fn test_update_expr() {
+    run_test("var a = 0; var b = a++; b = --a; a++;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(255)))
+        // Postfix: b = a - 0; a = b + 1
+        .add(op!(Minus, reg!(1), reg!(0), reg!(255)))
+        .add(op!(Add, reg!(0), reg!(1), reg!(254)))
+        .add(op!(Minus, reg!(0), reg!(0), reg!(254)))
+        .add(op!(Copy, reg!(1), reg!(0)))
+        // The value of an update statement is not used
+        .add(op!(Add, reg!(0), reg!(0), reg!(254)))
+    );
+
+    // 'a = a++' leaves 'a' unchanged
+    run_test("var a = 0; a = a++;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(255)))
+        .add(op!(Minus, reg!(0), reg!(0), reg!(255)))
+    );
+
+    run_test("var o = {}; var b = o.x++; o[b]--; b = ++o.x;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(LoadString, reg!(2), string!("x")))
+        .add(op!(PropAccess, reg!(3), reg!(0), reg!(2)))
+        .add(op!(Minus, reg!(4), reg!(3), reg!(255)))
+        .add(op!(Add, reg!(3), reg!(4), reg!(254)))
+        .add(op!(PropertySet, reg!(0), reg!(2), reg!(3)))
+        .add(op!(Copy, reg!(1), reg!(4)))
+        .add(op!(PropAccess, reg!(2), reg!(0), reg!(1)))
+        .add(op!(Minus, reg!(2), reg!(2), reg!(254)))
+        .add(op!(PropertySet, reg!(0), reg!(1), reg!(2)))
+        .add(op!(LoadString, reg!(2), string!("x")))
+        .add(op!(PropAccess, reg!(3), reg!(0), reg!(2)))
+        .add(op!(Add, reg!(3), reg!(3), reg!(254)))
+        .add(op!(PropertySet, reg!(0), reg!(2), reg!(3)))
+        .add(op!(Copy, reg!(1), reg!(3)))
+    );
 }
 
 #[test]
@@ -439,6 +881,554 @@ fn test_array_expr() {
         .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("String".into())]))
         .add(Operation::new(Instruction::LoadArray, vec![Operand::Reg(0), Operand::RegistersArray(vec![255, 1, 2])]))
     );
+
+    // Holes and spread elements are appended to the loaded array
+    run_test_deps("var a = [1, , ...b, 2];", &["b"], Bytecode::new()
+        .add(op!(LoadArray, reg!(1), reg_arr!(254)))
+        .add(op!(LoadString, reg!(2), string!("length")))
+        .add(op!(PropAccess, reg!(3), reg!(1), reg!(2)))
+        .add(op!(Add, reg!(3), reg!(3), reg!(254)))
+        .add(op!(PropertySet, reg!(1), reg!(2), reg!(3)))
+        .add(op!(ArraySpread, reg!(1), reg!(252)))
+        .add(op!(LoadNum, reg!(2), short_num!(2)))
+        .add(op!(LoadArray, reg!(3), reg_arr!(2)))
+        .add(op!(ArraySpread, reg!(1), reg!(3)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+    );
+
+    // Long arrays are loaded in chunks
+    run_test(&format!("var a = 1; var b = [{}];", vec!["a"; 17].join(", ")), BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadArray, reg!(2), Operand::RegistersArray(vec![0; 16])))
+        .add(op!(LoadArray, reg!(3), reg_arr!(0)))
+        .add(op!(ArraySpread, reg!(2), reg!(3)))
+        .add(op!(Copy, reg!(1), reg!(2)))
+    );
+}
+
+#[test]
+fn test_large_exprs() {
+    // The registers of operands are reused once their values are consumed
+    let compile = |js_code: String| BytecodeCompiler::new().compile(&JSSourceCode::new(js_code)).map(|_| ());
+
+    let properties: Vec<String> = (0..150).map(|i| format!("p{}: {}", i, i)).collect();
+    assert!(compile(format!("var o = {{{}}};", properties.join(", "))).is_ok());
+
+    let terms: Vec<String> = (0..180).map(|i| format!("'s{}'", i)).collect();
+    assert!(compile(format!("var s = {};", terms.join(" + "))).is_ok());
+
+    let decls: Vec<String> = (0..190).map(|i| format!("var v{} = {};", i, i)).collect();
+    let terms: Vec<String> = (0..20).map(|i| format!("v{}", 170 + i)).collect();
+    assert!(compile(format!("{} var s = {};", decls.join(" "), terms.join(" * "))).is_ok());
+
+    let elements: Vec<String> = (0..300).map(|i| i.to_string()).collect();
+    assert!(compile(format!("var a = [{}];", elements.join(", "))).is_ok());
+    assert!(compile(format!("function f() {{}} f({}); Math.max({}); new Array({});",
+                            elements.join(", "), elements.join(", "), elements.join(", "))).is_ok());
+
+    let template: String = (0..300).map(|i| format!("s{}${{{}}}", i, i)).collect();
+    assert!(compile(format!("var s = `{}`;", template)).is_ok());
+    let template: String = (0..150).map(|i| format!("s{}${{{}}}", i, i)).collect();
+    assert!(compile(format!("var t = String.raw`{}`;", template)).is_ok());
+}
+
+#[test]
+fn test_spread_arguments() {
+    let mut compiler = BytecodeCompiler::new();
+    assert!(compiler.add_var_decl("b".into()).is_ok());
+    assert!(compiler.add_var_decl("c".into()).is_ok());
+    assert!(compiler.add_var_decl("C".into()).is_ok());
+
+    run_test("function f(a) {} f(...b); c.d(...b); new C(...b);", compiler, Bytecode::new()
+        .add(op!(LoadArray, reg!(0), reg_arr!()))
+        .add(op!(ArraySpread, reg!(0), reg!(252)))
+        // Bytecode functions are applied as callbacks
+        .add(op!(BytecodeFuncCallback, reg!(1), addr!(57), reg_arr!(0), short_num!(0)))
+        .add(op!(CallFuncApply, reg!(202), reg!(1), reg!(253), reg!(0)))
+        // Spreading might run an iterator, which could assign the receiver and the constructor
+        .add(op!(Copy, reg!(1), reg!(251)))
+        .add(op!(LoadString, reg!(2), string!("d")))
+        .add(op!(PropAccess, reg!(0), reg!(1), reg!(2)))
+        .add(op!(LoadArray, reg!(3), reg_arr!()))
+        .add(op!(ArraySpread, reg!(3), reg!(252)))
+        .add(op!(CallFuncApply, reg!(202), reg!(0), reg!(1), reg!(3)))
+        .add(op!(Copy, reg!(0), reg!(250)))
+        .add(op!(LoadArray, reg!(1), reg_arr!()))
+        .add(op!(ArraySpread, reg!(1), reg!(252)))
+        .add(op!(ConstructApply, reg!(202), reg!(0), reg!(1)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+}
+
+#[test]
+fn test_operand_copies() {
+    // Operands are copied if the following ones might assign their declarations
+    run_test("var i = 0; f(i, i++); var a = [i, i += 2];", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(255)))
+        .add(op!(Copy, reg!(2), reg!(252)))
+        .add(op!(Copy, reg!(3), reg!(0)))
+        .add(op!(Minus, reg!(4), reg!(0), reg!(255)))
+        .add(op!(Add, reg!(0), reg!(4), reg!(254)))
+        .add(op!(CallFunc, reg!(202), reg!(2), reg!(253), reg_arr!(3, 4)))
+        .add(op!(Copy, reg!(2), reg!(0)))
+        .add(op!(LoadNum, reg!(4), short_num!(2)))
+        .add(op!(Add, reg!(0), reg!(0), reg!(4)))
+        .add(op!(Copy, reg!(3), reg!(0)))
+        .add(op!(LoadArray, reg!(1), reg_arr!(2, 3)))
+    );
+
+    run_test("var i = 0; f(i, i);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(255)))
+        .add(op!(CallFunc, reg!(202), reg!(252), reg!(253), reg_arr!(0, 0)))
+    );
+}
+
+#[test]
+fn test_sequence_expr() {
+    // Only the last expression is kept, expressions without side effects are skipped
+    run_test("var x = 10; var y = (x, x += 10, x == 20);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadNum, reg!(0), short_num!(10)))
+        .add(op!(LoadNum, reg!(2), short_num!(10)))
+        .add(op!(Add, reg!(0), reg!(0), reg!(2)))
+        .add(op!(LoadNum, reg!(3), short_num!(20)))
+        .add(op!(CompEqual, reg!(1), reg!(0), reg!(3)))
+    );
+}
+
+#[test]
+fn test_template_literal() {
+    run_test("var s = `a\\tb`;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadString, reg!(0), string!("a\tb")))
+    );
+
+    // Substitutions are converted to strings before they are concatenated
+    run_test("var a = 1; var s = `x${a}y${a + 1}`;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadString, reg!(2), string!("x")))
+        .add(op!(ToString, reg!(3), reg!(0)))
+        .add(op!(Add, reg!(2), reg!(2), reg!(3)))
+        .add(op!(LoadString, reg!(3), string!("y")))
+        .add(op!(Add, reg!(2), reg!(2), reg!(3)))
+        .add(op!(Add, reg!(4), reg!(0), reg!(254)))
+        .add(op!(ToString, reg!(3), reg!(4)))
+        .add(op!(Add, reg!(2), reg!(2), reg!(3)))
+        .add(op!(Copy, reg!(1), reg!(2)))
+    );
+}
+
+#[test]
+fn test_tagged_template() {
+    let mut compiler = BytecodeCompiler::new();
+    assert!(compiler.add_var_decl("f".into()).is_ok());
+
+    // The cooked string of an invalid escape sequence is undefined
+    run_test("var a = 1; var t = f`x${a}\\u`;", compiler, Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadString, reg!(4), string!("x")))
+        .add(op!(LoadArray, reg!(2), reg_arr!(4, 253)))
+        .add(op!(LoadString, reg!(4), string!("x")))
+        .add(op!(LoadString, reg!(5), string!("\\u")))
+        .add(op!(LoadArray, reg!(3), reg_arr!(4, 5)))
+        .add(op!(TemplateObject, reg!(4), reg!(2), reg!(3)))
+        .add(op!(CallFunc, reg!(1), reg!(252), reg!(253), reg_arr!(4, 0)))
+    );
+}
+
+#[test]
+fn test_object_expr() {
+    run_test("var o = {};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+    );
+
+    run_test("var b = 1; var o = {a: 10, b, [b]: \"x\"};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadString, reg!(2), string!("a")))
+        .add(op!(LoadNum, reg!(3), short_num!(10)))
+        .add(op!(LoadString, reg!(4), string!("b")))
+        .add(op!(LoadString, reg!(5), string!("x")))
+        .add(op!(CreateObject, reg!(1)))
+        .add(op!(DefineProperty, reg!(1), reg!(2), reg!(3)))
+        .add(op!(DefineProperty, reg!(1), reg!(4), reg!(0)))
+        .add(op!(DefineProperty, reg!(1), reg!(0), reg!(5)))
+    );
+
+    run_test("var o = {get x() { return 1; }, set x(v) {}, m() {}};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(LoadString, reg!(1), string!("x")))
+        .add(op!(BytecodeFuncCallback, reg!(2), long_num!(55), reg_arr!(), short_num!(0)))
+        .add(op!(LoadString, reg!(3), string!("x")))
+        .add(op!(BytecodeFuncCallback, reg!(4), long_num!(57), reg_arr!(0), short_num!(0)))
+        .add(op!(LoadString, reg!(5), string!("m")))
+        .add(op!(BytecodeFuncCallback, reg!(6), long_num!(59), reg_arr!(), short_num!(0)))
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(DefineGetter, reg!(0), reg!(1), reg!(2)))
+        .add(op!(DefineSetter, reg!(0), reg!(3), reg!(4)))
+        .add(op!(DefineProperty, reg!(0), reg!(5), reg!(6)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(254)))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+
+    // Unless it is computed, the '__proto__' property sets the prototype of the object
+    run_test("var p = {}; var o = {__proto__: p, [\"__proto__\"]: 1};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateObject, reg!(0)))
+        .add(op!(LoadString, reg!(2), string!("__proto__")))
+        .add(op!(CreateObject, reg!(1)))
+        .add(op!(SetPrototype, reg!(1), reg!(0)))
+        .add(op!(DefineProperty, reg!(1), reg!(2), reg!(254)))
+    );
+
+    check_is_unsupported_error("var a = {}; var o = {...a};", BytecodeCompiler::new());
+}
+
+#[test]
+fn test_new_expr() {
+    run_test_deps("var d = new Date(2020, 1);", &["Date"], Bytecode::new()
+        .add(op!(LoadLongNum, reg!(1), long_num!(2020)))
+        .add(op!(Construct, reg!(0), reg!(252), reg_arr!(1, 254)))
+    );
+
+    run_test("function P(a) {} var p = new P(10);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(18), reg_arr!(0), short_num!(0)))
+        .add(op!(LoadNum, reg!(2), short_num!(10)))
+        .add(op!(Construct, reg!(0), reg!(1), reg_arr!(2)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+}
+
+#[test]
+fn test_this_expr() {
+    run_test("var t = this; function P(a) { this.a = a; } new P(t);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(18), reg_arr!(0), short_num!(0)))
+        .add(op!(Copy, reg!(0), reg!(203)))
+        .add(op!(Construct, reg!(202), reg!(1), reg_arr!(0)))
+        .add(op!(Exit,))
+        .add(op!(LoadString, reg!(1), string!("a")))
+        .add(op!(PropertySet, reg!(203), reg!(1), reg!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+}
+
+#[test]
+fn test_func_expr() {
+    run_test("var f = function(a) { return a; }; var g = (b) => this;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(0), long_num!(19), reg_arr!(0), short_num!(0)))
+        .add(op!(BytecodeArrowFuncCallback, reg!(1), long_num!(21), reg_arr!(0), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(203)))
+    );
+
+    // The name of a named function expression is bound inside its body
+    run_test("var f = function g() { return g; };", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(0), long_num!(9), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(BytecodeFuncCallback, reg!(0), long_num!(9), reg_arr!(), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+}
+
+#[test]
+fn test_closure_capture() {
+    run_test("function counter() { var c = 0; return function() { c = c + 1; return c; }; }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Exit,))
+        .add(op!(CreateEnvironment,))
+        .add(op!(Copy, reg!(0), reg!(255)))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(0)))
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(19), reg_arr!(), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+        .add(op!(LoadCaptured, reg!(1), short_num!(0), short_num!(0)))
+        .add(op!(Add, reg!(0), reg!(1), reg!(254)))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(0)))
+        .add(op!(LoadCaptured, reg!(0), short_num!(0), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+
+    // Declarations of outer functions are reached by walking up the environments
+    run_test("function f(a) { return function(b) { return () => a + b; }; }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Exit,))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(0)))
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(17), reg_arr!(0), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(0)))
+        .add(op!(BytecodeArrowFuncCallback, reg!(1), long_num!(32), reg_arr!(), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+        .add(op!(LoadCaptured, reg!(1), short_num!(1), short_num!(0)))
+        .add(op!(LoadCaptured, reg!(2), short_num!(0), short_num!(0)))
+        .add(op!(Add, reg!(0), reg!(1), reg!(2)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+}
+
+#[test]
+fn test_lexical_decls() {
+    run_test("let a = 1; { let a = 2; a; } a;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(LoadNum, reg!(1), short_num!(2)))
+        .add(op!(Copy, reg!(202), reg!(1)))
+        .add(op!(Copy, reg!(202), reg!(0)))
+    );
+
+    // Reading a declaration before its initialization always throws
+    run_test("{ x; let x; }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(202), reg!(0)))
+        .add(op!(CheckInitialized, reg!(206)))
+        .add(op!(Copy, reg!(0), reg!(253)))
+    );
+
+    // Each iteration copies the captured loop variable into a new environment
+    run_test("var fs = []; for (let i = 0; i < 2; i++) { fs.push(() => i); }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateEnvironment,))
+        .add(op!(LoadArray, reg!(0), reg_arr!()))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(206)))
+        .add(op!(Copy, reg!(1), reg!(255)))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(1)))
+        .add(op!(LoadCaptured, reg!(2), short_num!(0), short_num!(0)))
+        .add(op!(PropAccess, reg!(204), reg!(204), reg!(255)))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(2)))
+        .add_label(2)
+        .add(op!(LoadCaptured, reg!(4), short_num!(0), short_num!(0)))
+        .add(op!(LoadNum, reg!(5), short_num!(2)))
+        .add(op!(CompLessThan, reg!(3), reg!(4), reg!(5)))
+        .add(op!(JumpCondNeg, reg!(3), addr!(102)))
+        .add(op!(LoadString, reg!(6), string!("push")))
+        .add(op!(PropAccess, reg!(5), reg!(0), reg!(6)))
+        .add(op!(BytecodeArrowFuncCallback, reg!(7), long_num!(107), reg_arr!(), short_num!(0)))
+        .add(op!(CallFunc, reg!(202), reg!(5), reg!(0), reg_arr!(7)))
+        .add_label(0)
+        .add(op!(LoadCaptured, reg!(2), short_num!(0), short_num!(0)))
+        .add(op!(PropAccess, reg!(204), reg!(204), reg!(255)))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(2)))
+        .add(op!(LoadCaptured, reg!(4), short_num!(0), short_num!(0)))
+        .add(op!(Add, reg!(4), reg!(4), reg!(254)))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(4)))
+        .add(op!(Jump, addr!(29)))
+        .add_label(1)
+        .add(op!(PropAccess, reg!(204), reg!(204), reg!(255)))
+        .add(op!(Exit,))
+        .add(op!(LoadCaptured, reg!(0), short_num!(0), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+
+    let mut compiler = BytecodeCompiler::new();
+    let error = compiler.compile(&JSSourceCode::new("const c = 1; c += 1;".into())).err().unwrap();
+    assert!(!error.is_unsupported_feature());
+    assert_eq!(error.to_string(), "Assignment to the constant declaration 'c'");
+}
+
+#[test]
+fn test_hoisting() {
+    // Functions can be called before their definition
+    run_test("f(); function f() {}", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CallBytecodeFunc, long_num!(9), reg!(202), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+
+    // Mutually recursive functions call each other directly
+    run_test("function a(n) { return b(n); } function b(n) { return a(n); }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateEnvironment,))
+        .add(op!(Exit,))
+        .add(op!(CallBytecodeFunc, long_num!(14), reg!(1), reg_arr!(0, 0), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+        .add(op!(CallBytecodeFunc, long_num!(2), reg!(1), reg_arr!(0, 0), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+    );
+
+    // 'var' declarations belong to the whole script and can be accessed before their definition
+    run_test("x; { var x = 1; } x;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Copy, reg!(202), reg!(0)))
+        .add(op!(Copy, reg!(0), reg!(254)))
+        .add(op!(Copy, reg!(202), reg!(0)))
+    );
+
+    // A function declared later is bound to its declaration before any other code runs
+    run_test("var g = f; function f(a) { return a; }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(13), reg_arr!(0), short_num!(0)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+}
+
+#[test]
+fn test_nested_func_decls() {
+    // Each 'h' is only visible in its enclosing function
+    run_test("function a() { function h() { return 1; } return h(); } \
+              function b() { function h() { return 2; } return h(); } a();", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CallBytecodeFunc, long_num!(9), reg!(202), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(CallBytecodeFunc, long_num!(29), reg!(0), reg_arr!(), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+        .add(op!(CallBytecodeFunc, long_num!(31), reg!(0), reg_arr!(), short_num!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(254)))
+        .add(op!(LoadNum, reg!(0), short_num!(2)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+
+    // The parameter shadows the function declaration
+    run_test("function h() {} function f(h) { return h(); }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+        .add(op!(CallFunc, reg!(1), reg!(0), reg!(253), reg_arr!()))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+    );
+
+    // All references share the function bound when its scope is entered, calls are still direct
+    run_test("function P() {} P.p = P === P; P();", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(0), long_num!(33), reg_arr!(), short_num!(0)))
+        // The object is copied, since it is assigned after the right side is evaluated
+        .add(op!(Copy, reg!(1), reg!(0)))
+        .add(op!(LoadString, reg!(2), string!("p")))
+        .add(op!(CompStrictEqual, reg!(3), reg!(0), reg!(0)))
+        .add(op!(PropertySet, reg!(1), reg!(2), reg!(3)))
+        .add(op!(CallBytecodeFunc, long_num!(33), reg!(202), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+
+    // Calls of a reassigned function use the value of its declaration
+    run_test("function f() {} f = 1; f();", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(0), long_num!(17), reg_arr!(), short_num!(0)))
+        .add(op!(LoadNum, reg!(0), short_num!(1)))
+        .add(op!(CallFunc, reg!(202), reg!(0), reg!(253), reg_arr!()))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+
+    // As in sloppy mode, a function declared in a block is assigned to a var declaration of its function
+    run_test("{ function g() {} } g();", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeFuncCallback, reg!(1), long_num!(17), reg_arr!(), short_num!(0)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+        .add(op!(CallFunc, reg!(202), reg!(0), reg!(253), reg_arr!()))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+}
+
+#[test]
+fn test_classes() {
+    run_test("class A { m() { return 1; } static get s() { return 2; } }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeClassCallback, reg!(2), long_num!(75), reg_arr!(), short_num!(0), short_num!(0)))
+        .add(op!(LoadString, reg!(3), string!("A")))
+        .add(op!(DefineClassName, reg!(2), reg!(3)))
+        .add(op!(LoadString, reg!(4), string!("prototype")))
+        .add(op!(PropAccess, reg!(3), reg!(2), reg!(4)))
+        .add(op!(LoadString, reg!(5), string!("m")))
+        .add(op!(BytecodeFuncCallback, reg!(6), long_num!(77), reg_arr!(), short_num!(0)))
+        .add(op!(DefineMethod, reg!(3), reg!(5), reg!(6)))
+        .add(op!(LoadString, reg!(5), string!("s")))
+        .add(op!(BytecodeFuncCallback, reg!(6), long_num!(79), reg_arr!(), short_num!(0)))
+        .add(op!(DefineMethodGetter, reg!(2), reg!(5), reg!(6)))
+        .add(op!(Copy, reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(0), reg!(2)))
+        .add(op!(Exit,))
+        .add(op!(ReturnConstructor, reg!(253)))
+        .add(op!(ReturnBytecodeFunc, reg!(254)))
+        .add(op!(LoadNum, reg!(0), short_num!(2)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+
+    // 'super' is resolved through the class binding of the method's home object
+    run_test("class B extends A { constructor() { super(); } m() { return super.m(); } }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(CreateEnvironment,))
+        .add(op!(CreateEnvironment,))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(206)))
+        .add(op!(BytecodeClassCallback, reg!(3), long_num!(78), reg_arr!(), short_num!(0), short_num!(1)))
+        .add(op!(ExtendClass, reg!(3), reg!(252)))
+        .add(op!(LoadString, reg!(4), string!("B")))
+        .add(op!(DefineClassName, reg!(3), reg!(4)))
+        .add(op!(LoadString, reg!(5), string!("prototype")))
+        .add(op!(PropAccess, reg!(4), reg!(3), reg!(5)))
+        .add(op!(LoadString, reg!(6), string!("m")))
+        .add(op!(BytecodeFuncCallback, reg!(7), long_num!(91), reg_arr!(), short_num!(0)))
+        .add(op!(DefineMethod, reg!(4), reg!(6), reg!(7)))
+        .add(op!(Copy, reg!(1), reg!(3)))
+        .add(op!(Copy, reg!(2), reg!(3)))
+        .add(op!(StoreCaptured, short_num!(0), short_num!(0), reg!(2)))
+        .add(op!(PropAccess, reg!(204), reg!(204), reg!(255)))
+        .add(op!(Copy, reg!(0), reg!(3)))
+        .add(op!(Exit,))
+        .add(op!(LoadArray, reg!(0), reg_arr!()))
+        .add(op!(LoadCaptured, reg!(1), short_num!(0), short_num!(0)))
+        .add(op!(SuperCall, reg!(202), reg!(1), reg!(0)))
+        .add(op!(ReturnConstructor, reg!(253)))
+        .add(op!(LoadCaptured, reg!(2), short_num!(0), short_num!(0)))
+        .add(op!(LoadString, reg!(3), string!("prototype")))
+        .add(op!(PropAccess, reg!(2), reg!(2), reg!(3)))
+        .add(op!(LoadString, reg!(4), string!("m")))
+        .add(op!(SuperPropAccess, reg!(1), reg!(2), reg!(4)))
+        .add(op!(CallFunc, reg!(0), reg!(1), reg!(203), reg_arr!()))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+    );
+
+    // Anonymous classes are named after the identifier they are assigned to
+    run_test("var C; C = class {};", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeClassCallback, reg!(1), long_num!(21), reg_arr!(), short_num!(0), short_num!(0)))
+        .add(op!(LoadString, reg!(2), string!("C")))
+        .add(op!(DefineClassName, reg!(1), reg!(2)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+        .add(op!(Exit,))
+        .add(op!(ReturnConstructor, reg!(253)))
+    );
+
+    // Static members are found in the bodies of arrow functions as well
+    run_test("var f = () => class { static s() {} };", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeArrowFuncCallback, reg!(0), long_num!(9), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(BytecodeClassCallback, reg!(1), long_num!(40), reg_arr!(), short_num!(0), short_num!(0)))
+        .add(op!(LoadString, reg!(3), string!("s")))
+        .add(op!(BytecodeFuncCallback, reg!(4), long_num!(42), reg_arr!(), short_num!(0)))
+        .add(op!(DefineMethod, reg!(1), reg!(3), reg!(4)))
+        .add(op!(Copy, reg!(0), reg!(1)))
+        .add(op!(ReturnBytecodeFunc, reg!(0)))
+        .add(op!(ReturnConstructor, reg!(253)))
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+
+    let mut compiler = BytecodeCompiler::new();
+    let error = compiler.compile(&JSSourceCode::new("class A { constructor() { super(); } }".into())).err().unwrap();
+    assert!(!error.is_unsupported_feature());
+    assert_eq!(error.to_string(), "'super' can only be called in constructors of derived classes");
+}
+
+#[test]
+fn test_generators() {
+    // Calling a generator function creates a generator object instead of running its body
+    run_test("function* g(a) { var b = yield a; return yield* b; } var it = g(1);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeGeneratorCallback, reg!(1), long_num!(16), reg_arr!(0), short_num!(0)))
+        .add(op!(CallFunc, reg!(0), reg!(1), reg!(253), reg_arr!(254)))
+        .add(op!(Exit,))
+        .add(op!(Yield, reg!(1), reg!(0)))
+        .add(op!(YieldDelegate, reg!(2), reg!(1)))
+        .add(op!(ReturnBytecodeFunc, reg!(2)))
+    );
+}
+
+#[test]
+fn test_async_functions() {
+    // The parser returns async function declarations as expression statements
+    run_test("async function f(a) { return await a; } var p = f(1);", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeAsyncFuncCallback, reg!(1), long_num!(16), reg_arr!(0), short_num!(0)))
+        .add(op!(CallFunc, reg!(0), reg!(1), reg!(253), reg_arr!(254)))
+        .add(op!(Exit,))
+        .add(op!(Await, reg!(1), reg!(0)))
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+    );
+
+    run_test("var f = async () => 1;", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(BytecodeAsyncArrowFuncCallback, reg!(0), long_num!(9), reg_arr!(), short_num!(0)))
+        .add(op!(Exit,))
+        .add(op!(ReturnBytecodeFunc, reg!(254)))
+    );
 }
 
 #[test]
@@ -447,32 +1437,32 @@ fn test_compile_js_func_call() {
     assert!(compiler.add_var_decl("test".into()).is_ok());
 
     run_test("test();", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0),
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(252),
                                                               Operand::Reg(253), Operand::RegistersArray(vec![])]))
             );
 
     run_test("test(1);", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0),
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(252),
                                                               Operand::Reg(253),Operand::RegistersArray(vec![254])]))
             );
 
     run_test("test(10);test(10);", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(1), Operand::ShortNum(10)]))
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0),
-                                                              Operand::Reg(253),Operand::RegistersArray(vec![1])]))
-                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(2), Operand::ShortNum(10)]))
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0),
-                                                              Operand::Reg(253),Operand::RegistersArray(vec![2])]))
+                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(252),
+                                                              Operand::Reg(253),Operand::RegistersArray(vec![0])]))
+                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(10)]))
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(252),
+                                                              Operand::Reg(253),Operand::RegistersArray(vec![0])]))
             );
 
     run_test("test(1, 20);", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(1), Operand::ShortNum(20)]))
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0),
-                                                              Operand::Reg(253),Operand::RegistersArray(vec![254, 1])]))
+                .add(Operation::new(Instruction::LoadNum, vec![Operand::Reg(0), Operand::ShortNum(20)]))
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(252),
+                                                              Operand::Reg(253),Operand::RegistersArray(vec![254, 0])]))
             );
 
     run_test("var a = test(1);", compiler.clone(), Bytecode::new()
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(1), Operand::Reg(0),
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(0), Operand::Reg(252),
                                                               Operand::Reg(253),Operand::RegistersArray(vec![254])]))
             );
 
@@ -483,9 +1473,17 @@ fn test_compile_js_func_call() {
     // assert!(compiler1.add_var_decl("document.test".into()).is_ok());
 
     run_test("document.test();", compiler_doc, Bytecode::new()
-                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(2), Operand::String("test".into())]))
-                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(1), Operand::Reg(0), Operand::Reg(2)]))
-                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(1), Operand::Reg(0), Operand::RegistersArray(vec![])]))
+                .add(Operation::new(Instruction::LoadString, vec![Operand::Reg(1), Operand::String("test".into())]))
+                .add(Operation::new(Instruction::PropAccess, vec![Operand::Reg(0), Operand::Reg(252), Operand::Reg(1)]))
+                .add(Operation::new(Instruction::CallFunc, vec![Operand::Reg(202), Operand::Reg(0), Operand::Reg(252), Operand::RegistersArray(vec![])]))
+            );
+
+    // The receiver of a method is evaluated only once
+    run_test("test().then(test);", compiler.clone(), Bytecode::new()
+                .add(op!(CallFunc, reg!(1), reg!(252), reg!(253), reg_arr!()))
+                .add(op!(LoadString, reg!(2), string!("then")))
+                .add(op!(PropAccess, reg!(0), reg!(1), reg!(2)))
+                .add(op!(CallFunc, reg!(202), reg!(0), reg!(1), reg_arr!(252)))
             );
 }
 
@@ -529,75 +1527,183 @@ fn test_try_throw() {
              finally{ console.log(x); }",
              compiler_with_json.clone(),
              Bytecode::new()
-                .add(op!(Try, reg!(7), long_num!(49), long_num!(58)))
-                .add(op!(LoadString, reg!(4), string!("{\"x\": 100}")))
-                .add(op!(LoadString, reg!(6), string!("parse")))
-                .add(op!(PropAccess, reg!(5), reg!(1), reg!(6)))
-                .add(op!(CallFunc, reg!(3), reg!(5), reg!(1), reg_arr!(4)))
-                .add(op!(LoadLongNum, reg!(200), long_num!(81)))
-                .add_label(0)
-                .add(op!(Copy, reg!(3), reg!(2)))
-                .add(op!(LoadLongNum, reg!(200), long_num!(81)))
+                .add(op!(LoadNum, reg!(2), short_num!(0)))
+                .add(op!(TryFinally, reg!(3), addr!(63)))
+                .add(op!(Try, reg!(4), addr!(54)))
+                .add(op!(LoadString, reg!(1), string!("{\"x\": 100}")))
+                .add(op!(LoadString, reg!(5), string!("parse")))
+                .add(op!(PropAccess, reg!(4), reg!(251), reg!(5)))
+                .add(op!(CallFunc, reg!(0), reg!(4), reg!(251), reg_arr!(1)))
+                .add(op!(EndTry,))
+                .add(op!(Jump, addr!(57)))
                 .add_label(1)
-                .add(op!(LoadString, reg!(8), string!("log")))
-                .add(op!(PropAccess, reg!(7), reg!(0), reg!(8)))
-                .add(op!(CallFunc, reg!(202), reg!(7), reg!(0), reg_arr!(3)))
-                .add(op!(LoadLongNum, reg!(200), long_num!(81)))
+                .add(op!(Copy, reg!(0), reg!(250)))
+                .add_label(2)
+                .add(op!(EndTry,))
+                .add(op!(Jump, addr!(66)))
+                // Exceptions enter the finally block with the 'throw' completion kind...
+                .add_label(3)
+                .add(op!(LoadNum, reg!(2), short_num!(1)))
+                .add_label(4)
+                // ...which is continued after the finally block
+                .add_label(0)
+                .add(op!(LoadString, reg!(6), string!("log")))
+                .add(op!(PropAccess, reg!(5), reg!(252), reg!(6)))
+                .add(op!(CallFunc, reg!(202), reg!(5), reg!(252), reg_arr!(0)))
+                .add(op!(JumpTable, reg!(2), long_num!(1), short_num!(2), addr!(103), addr!(105)))
+                .add(op!(Jump, addr!(105)))
+                .add_label(6)
+                .add(op!(Throw, reg!(3)))
+                .add_label(5)
     );
 
     run_test("var x = 10; try { throw x*2; }", BytecodeCompiler::new(), Bytecode::new()
         .add(op!(LoadNum, reg!(0), short_num!(10)))
-        .add(op!(Try, reg!(202), addr!(28), addr!(34)))
+        .add(op!(Try, reg!(202), addr!(24)))
         .add(op!(LoadNum, reg!(2), short_num!(2)))
         .add(op!(Mul, reg!(1), reg!(0), reg!(2)))
         .add(op!(Throw, reg!(1)))
-        .add(op!(LoadLongNum, reg!(200), long_num!(40)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(24)))
         .add_label(0)
-        .add(op!(LoadLongNum, reg!(200), long_num!(40)))
         .add_label(1)
-        .add(op!(LoadLongNum, reg!(200), long_num!(40)))
     );
 }
 
 #[test]
-fn test_unsupported_exprs() {
-    // Arrow functions
-    check_is_unsupported_error("() => 0;", BytecodeCompiler::new());
-    // Arrow function placeholder
-    check_is_unsupported_error("_ => 0;", BytecodeCompiler::new());
-
-    // Await, FIXME, this seems to be buggy in RESSA
-    // check_is_unsupported_error("var x = await something();", BytecodeCompiler::new());
-
-    // Class expressions
-    check_is_unsupported_error("var x = class X {};", BytecodeCompiler::new());
-    // Function expressions
-    check_is_unsupported_error("var x = function X() {};", BytecodeCompiler::new());
+fn test_spilled_decls() {
+    let decls_count = 170;
+    let js_code = (0..decls_count).map(|i| format!("var a{} = {};", i, i + 2)).collect::<Vec<String>>().join(" ")
+                    + " var b = a169 + a0;";
+
+    // The last declarations do not fit into the frame registers anymore
+    let frame_decls_count = 168;
+    let bytecode = (0..frame_decls_count).fold(Bytecode::new(), |bc, i|
+                        bc.add(op!(LoadNum, reg!(i as u8), short_num!(i as u8 + 2))))
+                .add(op!(LoadNum, reg!(168), short_num!(170)))
+                .add(op!(StoreSpilled, long_num!(0), reg!(168)))
+                .add(op!(LoadNum, reg!(168), short_num!(171)))
+                .add(op!(StoreSpilled, long_num!(1), reg!(168)))
+                .add(op!(LoadSpilled, reg!(169), long_num!(1)))
+                .add(op!(Add, reg!(168), reg!(169), reg!(0)))
+                .add(op!(StoreSpilled, long_num!(2), reg!(168)));
+
+    run_test(&js_code, BytecodeCompiler::new(), bytecode);
+
+    // Spilled lexical declarations do not keep their temporary registers either
+    let decls_count = 210;
+    let js_code = (0..decls_count).map(|i| format!("const a{} = {};", i, i + 2)).collect::<Vec<String>>().join(" ")
+                    + " const b = a209 + a0;";
+
+    let bytecode = (0..frame_decls_count).fold(Bytecode::new(), |bc, i|
+                        bc.add(op!(LoadNum, reg!(i as u8), short_num!(i as u8 + 2))))
+                .add_bytecode((frame_decls_count..decls_count).fold(Bytecode::new(), |bc, i|
+                        bc.add(op!(LoadNum, reg!(168), short_num!(i as u8 + 2)))
+                          .add(op!(StoreSpilled, long_num!(i - frame_decls_count), reg!(168)))))
+                .add(op!(LoadSpilled, reg!(168), long_num!(41)))
+                .add(op!(Add, reg!(168), reg!(168), reg!(0)))
+                .add(op!(StoreSpilled, long_num!(42), reg!(168)));
+
+    run_test(&js_code, BytecodeCompiler::new(), bytecode);
+}
 
+#[test]
+fn test_unsupported_exprs() {
     // Object related stuff
-    check_is_unsupported_error("var x = new X();", BytecodeCompiler::new());
-    check_is_unsupported_error("var x = {};", BytecodeCompiler::new());
-    check_is_unsupported_error("var x = this;", BytecodeCompiler::new());
 
-    // yield, FIXME
-    // check_is_unsupported_error("var index; while (index < 2) { yield index++; }", BytecodeCompiler::new());
-
-    // Spread
-    check_is_unsupported_error("const nums = [1, 2, 3]; func(...nums);", BytecodeCompiler::new());
-
-    // Seqeunce
-    check_is_unsupported_error("var x = 10; var b = (x+=10, x==20);", BytecodeCompiler::new());
-
-    // TaggedTemplate, super, meta properties
+    // super, meta properties
+    check_is_unsupported_error("class C extends B { m() { super.x = 1; } }", BytecodeCompiler::new());
+    check_is_unsupported_error("class C extends B { constructor() { (() => super())(); } }", BytecodeCompiler::new());
 }
 
 #[test]
 fn test_unsupported_stmts() {
-    check_is_unsupported_error("switch (x) { case 0: ;}", BytecodeCompiler::new());
-
-    check_is_unsupported_error("for (x in X) {}", BytecodeCompiler::new());
-    check_is_unsupported_error("for (x of X) {}", BytecodeCompiler::new());
-
     check_is_unsupported_error("with(x) {}", BytecodeCompiler::new());
     check_is_unsupported_error("debugger;", BytecodeCompiler::new());
-}
\ No newline at end of file
+}
+#[test]
+fn test_try_finally_completions() {
+    run_test("var a = true; while(a){ try { break; } finally { a = false; } }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Not, reg!(0), reg!(255)))
+        .add_label(0)
+        .add(op!(JumpCondNeg, reg!(0), addr!(75)))
+        .add(op!(LoadNum, reg!(1), short_num!(0)))
+        .add(op!(TryFinally, reg!(2), addr!(33)))
+        // 'break' enters the finally block with its own completion kind...
+        .add(op!(LoadNum, reg!(1), short_num!(3)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(36)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(36)))
+        .add_label(3)
+        .add(op!(LoadNum, reg!(1), short_num!(1)))
+        .add_label(4)
+        .add_label(2)
+        .add(op!(Not, reg!(0), reg!(254)))
+        // ...which is dispatched to the loop exit afterwards
+        .add(op!(JumpTable, reg!(1), long_num!(1), short_num!(3), addr!(63), addr!(70), addr!(65)))
+        .add(op!(Jump, addr!(70)))
+        .add_label(6)
+        .add(op!(Throw, reg!(2)))
+        .add_label(7)
+        .add(op!(Jump, addr!(75)))
+        .add_label(5)
+        .add(op!(Jump, addr!(3)))
+        .add_label(1)
+    );
+
+    run_test("var a = true; while(a){ try { continue; } finally { a = false; } }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Not, reg!(0), reg!(255)))
+        .add_label(0)
+        .add(op!(JumpCondNeg, reg!(0), addr!(75)))
+        .add(op!(LoadNum, reg!(1), short_num!(0)))
+        .add(op!(TryFinally, reg!(2), addr!(33)))
+        .add(op!(LoadNum, reg!(1), short_num!(3)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(36)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(36)))
+        .add_label(3)
+        .add(op!(LoadNum, reg!(1), short_num!(1)))
+        .add_label(4)
+        .add_label(2)
+        .add(op!(Not, reg!(0), reg!(254)))
+        .add(op!(JumpTable, reg!(1), long_num!(1), short_num!(3), addr!(63), addr!(70), addr!(65)))
+        .add(op!(Jump, addr!(70)))
+        .add_label(6)
+        .add(op!(Throw, reg!(2)))
+        .add_label(7)
+        .add(op!(Jump, addr!(3)))
+        .add_label(5)
+        .add(op!(Jump, addr!(3)))
+        .add_label(1)
+    );
+
+    run_test("function f() { try { return 1; } finally { return 2; } }", BytecodeCompiler::new(), Bytecode::new()
+        .add(op!(Exit,))
+        .add(op!(LoadNum, reg!(0), short_num!(0)))
+        .add(op!(TryFinally, reg!(1), addr!(28)))
+        // The returned value is kept until the finally block has run...
+        .add(op!(Copy, reg!(1), reg!(254)))
+        .add(op!(LoadNum, reg!(0), short_num!(2)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(31)))
+        .add(op!(EndTry,))
+        .add(op!(Jump, addr!(31)))
+        .add_label(1)
+        .add(op!(LoadNum, reg!(0), short_num!(1)))
+        .add_label(2)
+        .add_label(0)
+        // ...but a 'return' inside of it takes precedence
+        .add(op!(LoadNum, reg!(2), short_num!(2)))
+        .add(op!(ReturnBytecodeFunc, reg!(2)))
+        .add(op!(JumpTable, reg!(0), long_num!(1), short_num!(2), addr!(56), addr!(58)))
+        .add(op!(Jump, addr!(60)))
+        .add_label(4)
+        .add(op!(Throw, reg!(1)))
+        .add_label(5)
+        .add(op!(ReturnBytecodeFunc, reg!(1)))
+        .add_label(3)
+        .add(op!(ReturnBytecodeFunc, reg!(253)))
+    );
+}
@@ -83,6 +83,15 @@ const testDataSet = [
       [150, "Hello World"]
     ],
   },
+  {
+    name: "Load regular expression",
+    bytecode: [
+      OP.LOAD_REGEXP, 150, ...encodeString("\\d+"), ...encodeString("g")
+    ],
+    expected_registers: [
+      [150, /\d+/g]
+    ],
+  },
   {
     name: "Load array",
     bytecode: [
@@ -127,6 +136,63 @@ const testDataSet = [
       [150, 6]
     ],
   },
+  {
+    name: "Modulo, bitwise and relational operators",
+    init_regeisters: [
+      [160, {x: 1}],
+      [161, "x"],
+      [162, Object]
+    ],
+    bytecode: [
+      OP.LOAD_NUM, 150, 13,
+      OP.LOAD_NUM, 151, 3,
+      OP.MOD, 152, 150, 151,
+      OP.POWER_OF, 153, 151, 151,
+      OP.LEFT_SHIFT, 154, 150, 151,
+      OP.RIGHT_SHIFT, 155, 150, 151,
+      OP.MINUS, 156, REGS.NUM_0, 150,
+      OP.UNSIGNED_RIGHT_SHIFT, 156, 156, 151,
+      OP.BIT_OR, 157, 150, 151,
+      OP.BIT_XOR, 158, 150, 151,
+      OP.BIT_AND, 159, 150, 151,
+      OP.IN, 163, 161, 160,
+      OP.INSTANCE_OF, 164, 160, 162
+    ],
+    expected_registers: [
+      [152, 1],
+      [153, 27],
+      [154, 104],
+      [155, 1],
+      [156, 536870910],
+      [157, 15],
+      [158, 14],
+      [159, 1],
+      [163, true],
+      [164, true]
+    ],
+  },
+  {
+    name: "Unary operators and delete",
+    init_regeisters: [
+      [160, {x: 1, y: 2}],
+      [161, "x"]
+    ],
+    bytecode: [
+      OP.LOAD_NUM, 150, 5,
+      OP.NOT, 151, 150,
+      OP.BIT_NOT, 152, 150,
+      OP.TYPE_OF, 153, 160,
+      OP.DELETE_PROPERTY, 154, 160, 161,
+      OP.IN, 155, 161, 160
+    ],
+    expected_registers: [
+      [151, false],
+      [152, -6],
+      [153, "object"],
+      [154, true],
+      [155, false]
+    ],
+  },
   {
     name: "Call member function",
     init: function() {
@@ -172,13 +238,58 @@ const testDataSet = [
       [152, ""]
     ]
   },
+  {
+    name: "Define object property",
+    bytecode: [
+      OP.LOAD_STRING, 150, ...encodeString("a"),
+      OP.LOAD_NUM, 151, 66,
+      OP.CREATE_OBJECT, 152,
+      OP.DEFINE_PROP, 152, 150, 151
+    ],
+    expected_registers: [
+      [152, {a: 66}]
+    ]
+  },
+  {
+    name: "Define object getter",
+    bytecode: [
+      OP.LOAD_STRING, 150, ...encodeString("a"),
+      // 24 is the offset of the getter below
+      OP.BCFUNC_CALLBACK, 151, ...encodeLongNum(24), ...encodeRegistersArray([]), 0,
+      OP.CREATE_OBJECT, 152,
+      OP.DEFINE_GETTER, 152, 150, 151,
+      OP.PROPACCESS, 153, 152, 150,
+      OP.EXIT,
+
+      // The getter: function() { return 66; }
+      OP.LOAD_NUM, 154, 66,
+      OP.RETURN_BCFUNC, 154
+    ],
+    expected_registers: [
+      [153, 66]
+    ]
+  },
+  {
+    name: "Construct object",
+    bytecode: [
+      OP.LOAD_STRING, 150, ...encodeString("String"),
+      OP.PROPACCESS, 151, REGS.WINDOW, 150,
+      OP.LOAD_NUM, 152, 66,
+      OP.CONSTRUCT, 153, 151, ...encodeRegistersArray([152]),
+      OP.LOAD_STRING, 154, ...encodeString("length"),
+      OP.PROPACCESS, 155, 153, 154
+    ],
+    expected_registers: [
+      [155, 2]
+    ]
+  },
   {
     name: "Call bytecode function",
     bytecode: [
       OP.LOAD_NUM, 150, 60,
       OP.LOAD_NUM, 151, 6,
-      // 22 is the offset of the bytecode function below
-      OP.CALL_BCFUNC, ...encodeLongNum(22), 160, ...encodeRegistersArray([152, 150, 153, 151]),
+      // 23 is the offset of the bytecode function below
+      OP.CALL_BCFUNC, ...encodeLongNum(23), 160, ...encodeRegistersArray([152, 150, 153, 151]), 0,
       OP.ADD, 160, 160, 150,
       OP.EXIT,
 
@@ -187,7 +298,7 @@ const testDataSet = [
       // b: 153
       OP.ADD, 152, 152, 153,
       OP.MUL, 152, 152, 152,
-      OP.RETURN_BCFUNC, 152, ...encodeRegistersArray([])
+      OP.RETURN_BCFUNC, 152
     ],
     expected_registers: [
       [150, 60],
@@ -195,6 +306,76 @@ const testDataSet = [
       [160, 4416]
     ]
   },
+  {
+    name: "Call bytecode function callback with 'this'",
+    init: function() {
+      window.callWithThis = function(func) { return func.call({x: 66}); };
+    },
+    bytecode: [
+      OP.LOAD_STRING, 150, ...encodeString("callWithThis"),
+      OP.PROPACCESS, 151, REGS.WINDOW, 150,
+      // 35 is the offset of the bytecode function below
+      OP.BCFUNC_CALLBACK, 152, ...encodeLongNum(35), ...encodeRegistersArray([]), 0,
+      OP.FUNC_CALL, 153, 151, REGS.WINDOW, ...encodeRegistersArray([152]),
+      OP.EXIT,
+
+      // The function: function() { return this.x; }
+      OP.LOAD_STRING, 154, ...encodeString("x"),
+      OP.PROPACCESS, 155, REGS.THIS, 154,
+      OP.RETURN_BCFUNC, 155
+    ],
+    expected_registers: [
+      [153, 66]
+    ]
+  },
+  {
+    name: "Call bytecode arrow function callback with lexical 'this'",
+    init: function() {
+      window.callWithThis = function(func) { return func.call({x: 0}); };
+      window.outerThis = {x: 66};
+    },
+    bytecode: [
+      OP.LOAD_STRING, 150, ...encodeString("outerThis"),
+      OP.PROPACCESS, REGS.THIS, REGS.WINDOW, 150,
+      OP.LOAD_STRING, 150, ...encodeString("callWithThis"),
+      OP.PROPACCESS, 151, REGS.WINDOW, 150,
+      // 52 is the offset of the bytecode function below
+      OP.BCARROWFUNC_CALLBACK, 152, ...encodeLongNum(52), ...encodeRegistersArray([]), 0,
+      OP.FUNC_CALL, 153, 151, REGS.WINDOW, ...encodeRegistersArray([152]),
+      OP.EXIT,
+
+      // The function: () => this.x
+      OP.LOAD_STRING, 154, ...encodeString("x"),
+      OP.PROPACCESS, 155, REGS.THIS, 154,
+      OP.RETURN_BCFUNC, 155
+    ],
+    expected_registers: [
+      [153, 66]
+    ]
+  },
+  {
+    name: "Closure capturing an environment slot",
+    bytecode: [
+      OP.CREATE_ENV,
+      OP.LOAD_NUM, 150, 5,
+      OP.STORE_CAPTURED, 0, 0, 150,
+      // 27 is the offset of the bytecode function below
+      OP.BCFUNC_CALLBACK, 151, ...encodeLongNum(27), ...encodeRegistersArray([]), 0,
+      OP.FUNC_CALL, 152, 151, REGS.VOID, ...encodeRegistersArray([]),
+      OP.FUNC_CALL, 153, 151, REGS.VOID, ...encodeRegistersArray([]),
+      OP.EXIT,
+
+      // The function: function() { c = c + 1; return c; }
+      OP.LOAD_CAPTURED, 160, 0, 0,
+      OP.ADD, 160, 160, REGS.NUM_1,
+      OP.STORE_CAPTURED, 0, 0, 160,
+      OP.RETURN_BCFUNC, 160
+    ],
+    expected_registers: [
+      [152, 6],
+      [153, 7]
+    ]
+  },
   {
     name: "Load and call custom function",
     bytecode: [
@@ -242,18 +423,17 @@ const testDataSet = [
       [2, {}]
     ],
     bytecode: [
-      OP.TRY, 7, ...encodeLongNum(49), ...encodeLongNum(58),
+      OP.TRY, 7, ...encodeLongNum(45),
       OP.LOAD_STRING, 4, ...encodeString('{"x": 100}'),
       OP.LOAD_STRING, 6, ...encodeString("parse"),
       OP.PROPACCESS, 5, 1, 6,
       OP.FUNC_CALL, 3, 5, 1, ...encodeRegistersArray([4]),
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(81),
+      OP.END_TRY,
+      OP.JUMP, ...encodeLongNum(48),
       OP.COPY, 3, 2,
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(81),
       OP.LOAD_STRING, 8, ...encodeString("log"),
       OP.PROPACCESS, 7, 0, 8,
-      OP.FUNC_CALL, 202, 7, 0, ...encodeRegistersArray([3]),
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(81)
+      OP.FUNC_CALL, 202, 7, 0, ...encodeRegistersArray([3])
     ],
     expected_registers: [
       [3, {x: 100}]
@@ -267,22 +447,502 @@ const testDataSet = [
       [2, {}]
     ],
     bytecode: [
-      OP.TRY, 7, ...encodeLongNum(57), ...encodeLongNum(66),
+      OP.TRY, 7, ...encodeLongNum(53),
       OP.LOAD_STRING, 4, ...encodeString('{invalid, invalid}'),
       OP.LOAD_STRING, 6, ...encodeString("parse"),
       OP.PROPACCESS, 5, 1, 6,
       OP.FUNC_CALL, 3, 5, 1, ...encodeRegistersArray([4]),
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(89),
+      OP.END_TRY,
+      OP.JUMP, ...encodeLongNum(56),
       OP.COPY, 3, 2,
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(89),
       OP.LOAD_STRING, 8, ...encodeString("log"),
       OP.PROPACCESS, 7, 0, 8,
-      OP.FUNC_CALL, 202, 7, 0, ...encodeRegistersArray([3]),
-      OP.LOAD_LONG_NUM, 200, ...encodeLongNum(89)
+      OP.FUNC_CALL, 202, 7, 0, ...encodeRegistersArray([3])
     ],
     expected_registers: [
       [3, {}]
     ]
+  },
+  {
+    name: "Recursive bytecode function call",
+    bytecode: [
+      OP.LOAD_NUM, 150, 10,
+      // 14 is the offset of the bytecode function below
+      OP.CALL_BCFUNC, ...encodeLongNum(14), 151, ...encodeRegistersArray([0, 150]), 0,
+      OP.EXIT,
+
+      // The function: function fib(n) { if(n < 2) { return n; } return fib(n-1) + fib(n-2); }
+      // Every call has its own frame, thus 'n' is always in register 0
+      OP.LOAD_NUM, 1, 2,
+      OP.COMP_LESS_THAN, 1, 0, 1,
+      OP.JUMP_COND_NEG, 1, ...encodeLongNum(29),
+      OP.RETURN_BCFUNC, 0,
+      OP.MINUS, 2, 0, REGS.NUM_1,
+      OP.CALL_BCFUNC, ...encodeLongNum(14), 3, ...encodeRegistersArray([0, 2]), 0,
+      OP.LOAD_NUM, 1, 2,
+      OP.MINUS, 2, 0, 1,
+      OP.CALL_BCFUNC, ...encodeLongNum(14), 4, ...encodeRegistersArray([0, 2]), 0,
+      OP.ADD, 3, 3, 4,
+      OP.RETURN_BCFUNC, 3
+    ],
+    expected_registers: [
+      [150, 10],
+      [151, 55]
+    ]
+  },
+  {
+    name: "Throw across bytecode function frames",
+    bytecode: [
+      OP.TRY, 150, ...encodeLongNum(19),
+      // 23 is the offset of the bytecode function below
+      OP.CALL_BCFUNC, ...encodeLongNum(23), 151, ...encodeRegistersArray([]), 0,
+      OP.END_TRY,
+      OP.LOAD_NUM, 152, 1,
+      OP.EXIT,
+      OP.LOAD_NUM, 152, 2,
+      OP.EXIT,
+
+      // The function: function() { throw 66; }
+      OP.LOAD_NUM, 0, 66,
+      OP.THROW, 0
+    ],
+    expected_registers: [
+      [150, 66],
+      [152, 2]
+    ]
+  },
+  {
+    name: "Rethrow across bytecode function frames",
+    bytecode: [
+      OP.TRY, 150, ...encodeLongNum(16),
+      // 27 is the offset of the first bytecode function below
+      OP.CALL_BCFUNC, ...encodeLongNum(27), 151, ...encodeRegistersArray([]), 0,
+      OP.END_TRY,
+      OP.EXIT,
+      // A later exception is still caught by its own handler
+      OP.TRY, 152, ...encodeLongNum(26),
+      OP.THROW, 150,
+      OP.END_TRY,
+      OP.EXIT,
+      OP.EXIT,
+
+      // The function: function() { try { g(); } catch(e) { throw e; } }
+      OP.TRY, 0, ...encodeLongNum(42),
+      OP.CALL_BCFUNC, ...encodeLongNum(44), 1, ...encodeRegistersArray([]), 0,
+      OP.END_TRY,
+      OP.THROW, 0,
+
+      // The function g: function() { throw 66; }
+      OP.LOAD_NUM, 0, 66,
+      OP.THROW, 0
+    ],
+    expected_registers: [
+      [150, 66],
+      [152, 66]
+    ]
+  },
+  {
+    name: "Uninitialized lexical declarations",
+    bytecode: [
+      OP.LOAD_NUM, 154, 3,
+      OP.CHECK_INITIALIZED, 154,
+      // try { { x; let x; } } catch(e) {}, where the block has its own environment
+      OP.TRY, 150, ...encodeLongNum(24),
+      OP.CREATE_ENV,
+      OP.STORE_CAPTURED, 0, 0, REGS.UNINITIALIZED,
+      OP.LOAD_CAPTURED, 151, 0, 0,
+      OP.CHECK_INITIALIZED, 151,
+      OP.END_TRY,
+      OP.EXIT,
+      // The exception handler restores the environment of the try statement
+      OP.COPY, 152, REGS.ENV,
+      OP.LOAD_STRING, 153, ...encodeString("name"),
+      OP.PROPACCESS, 153, 150, 153
+    ],
+    expected_registers: [
+      [152, undefined],
+      [153, "ReferenceError"]
+    ]
+  },
+  {
+    name: "Arguments of bytecode functions",
+    bytecode: [
+      OP.LOAD_NUM, 150, 1,
+      OP.LOAD_NUM, 151, 2,
+      // The surplus argument is passed to the trash register, 19 is the offset of the function below
+      OP.CALL_BCFUNC, ...encodeLongNum(19), 152, ...encodeRegistersArray([0, 150, 202, 151]), 0,
+      OP.EXIT,
+
+      // The function: function(a, ...rest) { return [a, rest, arguments.length]; }
+      OP.LOAD_REST_ARGUMENTS, 1, 1,
+      OP.LOAD_ARGUMENTS, 2,
+      OP.LOAD_STRING, 3, ...encodeString("length"),
+      OP.PROPACCESS, 4, 2, 3,
+      OP.LOAD_ARRAY, 5, ...encodeRegistersArray([0, 1, 4]),
+      OP.RETURN_BCFUNC, 5
+    ],
+    expected_registers: [
+      [152, [1, [2], 2]]
+    ]
+  },
+  {
+    name: "Spread arguments",
+    init_regeisters: [
+      [150, Math],
+      [151, new Set([3, 7])],
+      [152, Array]
+    ],
+    bytecode: [
+      // Math.max(1, ...set)
+      OP.LOAD_ARRAY, 153, ...encodeRegistersArray([REGS.NUM_1]),
+      OP.ARRAY_SPREAD, 153, 151,
+      OP.LOAD_STRING, 154, ...encodeString("max"),
+      OP.PROPACCESS, 155, 150, 154,
+      OP.FUNC_CALL_APPLY, 156, 155, 150, 153,
+      // new Array(...[1, 3, 7])
+      OP.CONSTRUCT_APPLY, 157, 152, 153
+    ],
+    expected_registers: [
+      [153, [1, 3, 7]],
+      [156, 7],
+      [157, [1, 3, 7]]
+    ]
+  },
+  {
+    name: "Template strings",
+    bytecode: [
+      // The strings array is created once per call site
+      OP.CALL_BCFUNC, ...encodeLongNum(24), 150, ...encodeRegistersArray([]), 0,
+      OP.CALL_BCFUNC, ...encodeLongNum(24), 151, ...encodeRegistersArray([]), 0,
+      OP.COMP_STRICT_EQUAL, 152, 150, 151,
+      OP.TO_STRING, 153, REGS.NUM_1,
+      OP.EXIT,
+
+      OP.LOAD_STRING, 0, ...encodeString("a"),
+      OP.LOAD_ARRAY, 2, ...encodeRegistersArray([0]),
+      OP.LOAD_ARRAY, 3, ...encodeRegistersArray([0]),
+      OP.TEMPLATE_OBJECT, 1, 2, 3,
+      OP.RETURN_BCFUNC, 1
+    ],
+    expected_registers: [
+      [150, ["a"]],
+      [152, true],
+      [153, "1"]
+    ]
+  },
+  {
+    name: "Spilled declarations per frame",
+    bytecode: [
+      OP.LOAD_NUM, 150, 7,
+      OP.STORE_SPILLED, ...encodeLongNum(0), 150,
+      // 24 is the offset of the bytecode function below
+      OP.CALL_BCFUNC, ...encodeLongNum(24), 151, ...encodeRegistersArray([]), 0,
+      OP.LOAD_SPILLED, 152, ...encodeLongNum(0),
+      OP.EXIT,
+
+      // The function overwrites its own spilled declaration only
+      OP.LOAD_NUM, 0, 9,
+      OP.STORE_SPILLED, ...encodeLongNum(0), 0,
+      OP.LOAD_SPILLED, 1, ...encodeLongNum(0),
+      OP.RETURN_BCFUNC, 1
+    ],
+    expected_registers: [
+      [151, 9],
+      [152, 7]
+    ]
+  },
+  {
+    name: "Jump table",
+    init_regeisters: [
+      [152, "2"]
+    ],
+    bytecode: [
+      // Only numbers are looked up in the table
+      OP.JUMP_TABLE, 152, ...encodeLongNum(2), 1, ...encodeLongNum(34),
+      OP.LOAD_NUM, 150, 2,
+      OP.JUMP_TABLE, 150, ...encodeLongNum(1), 3, ...encodeLongNum(34), ...encodeLongNum(38), ...encodeLongNum(42),
+      OP.EXIT,
+      OP.LOAD_NUM, 151, 1,
+      OP.EXIT,
+      OP.LOAD_NUM, 151, 2,
+      OP.EXIT,
+      OP.LOAD_NUM, 151, 3,
+      OP.EXIT
+    ],
+    expected_registers: [
+      [151, 2]
+    ]
+  },
+  {
+    name: "Iterate over keys and values",
+    init_regeisters: [
+      [150, {a: 1, b: 2}],
+      [151, ""]
+    ],
+    bytecode: [
+      // for(key in obj) { str += key; }
+      OP.GET_KEYS_ITERATOR, 152, 150,
+      OP.ITERATOR_NEXT, 153, 154, 152,
+      OP.COND_JUMP, 153, ...encodeLongNum(22),
+      OP.ADD, 151, 151, 154,
+      OP.JUMP, ...encodeLongNum(3),
+      // for(value of [key1, key2]) { str += value; }
+      OP.LOAD_ARRAY, 155, ...encodeRegistersArray([154, 154]),
+      OP.GET_ITERATOR, 152, 155,
+      OP.ITERATOR_NEXT, 153, 156, 152,
+      OP.COND_JUMP, 153, ...encodeLongNum(49),
+      OP.ADD, 151, 151, 156,
+      OP.JUMP, ...encodeLongNum(30)
+    ],
+    expected_registers: [
+      [151, "abbb"],
+      [154, "b"]
+    ]
+  },
+  {
+    name: "Skip keys deleted during the iteration",
+    init_regeisters: [
+      [150, {a: 1, b: 2, c: 3}],
+      [151, ""],
+      [155, "b"]
+    ],
+    bytecode: [
+      // for(key in obj) { delete obj.b; str += key; }
+      OP.GET_KEYS_ITERATOR, 152, 150,
+      OP.ITERATOR_NEXT, 153, 154, 152,
+      OP.COND_JUMP, 153, ...encodeLongNum(26),
+      OP.DELETE_PROPERTY, 156, 150, 155,
+      OP.ADD, 151, 151, 154,
+      OP.JUMP, ...encodeLongNum(3)
+    ],
+    expected_registers: [
+      [151, "ac"]
+    ]
+  },
+  {
+    name: "Close iterators",
+    init: function() {
+      window.iter = (function*() { try { yield 1; } finally { window.closed = true; } })();
+      window.iter.next();
+    },
+    bytecode: [
+      OP.LOAD_STRING, 152, ...encodeString("iter"),
+      OP.PROPACCESS, 150, REGS.WINDOW, 152,
+      OP.ITERATOR_CLOSE, 150,
+      OP.LOAD_STRING, 152, ...encodeString("closed"),
+      OP.PROPACCESS, 151, REGS.WINDOW, 152
+    ],
+    expected_registers: [
+      [151, true]
+    ]
+  },
+  {
+    name: "Rest of iterators and objects",
+    init_regeisters: [
+      [150, [1, 2, 3]],
+      [151, {a: 1, b: 2, c: 3}],
+      [152, "a"]
+    ],
+    bytecode: [
+      // var [first, ...rest] = arr;
+      OP.GET_ITERATOR, 153, 150,
+      OP.ITERATOR_NEXT, 154, 155, 153,
+      OP.ITERATOR_REST, 156, 153,
+      // var {a, ...others} = obj;
+      OP.OBJECT_REST, 157, 151, ...encodeRegistersArray([152])
+    ],
+    expected_registers: [
+      [155, 1],
+      [156, [2, 3]],
+      [157, {b: 2, c: 3}]
+    ]
+  },
+  {
+    name: "Prototypes of object literals",
+    init_regeisters: [
+      [150, {p: 1}]
+    ],
+    bytecode: [
+      OP.CREATE_OBJECT, 151,
+      OP.SET_PROTOTYPE, 151, 150,
+      OP.LOAD_STRING, 152, ...encodeString("p"),
+      OP.PROPACCESS, 153, 151, 152,
+      // Values which are neither objects nor null are ignored
+      OP.CREATE_OBJECT, 154,
+      OP.SET_PROTOTYPE, 154, REGS.NUM_1,
+      OP.PROPACCESS, 155, 154, 152
+    ],
+    expected_registers: [
+      [153, 1],
+      [155, undefined]
+    ]
+  },
+  {
+    name: "Class names",
+    init_regeisters: [
+      [150, function() {}]
+    ],
+    bytecode: [
+      OP.LOAD_STRING, 151, ...encodeString("A"),
+      OP.DEFINE_CLASS_NAME, 150, 151,
+      OP.LOAD_STRING, 152, ...encodeString("name"),
+      OP.PROPACCESS, 153, 150, 152
+    ],
+    expected_registers: [
+      [153, "A"]
+    ]
+  },
+  {
+    name: "Derived classes",
+    bytecode: [
+      OP.CREATE_ENV,
+      // class Base { constructor(x) { this.x = x; } get() { return this.x; } }
+      OP.BCCLASS_CALLBACK, 150, ...encodeLongNum(86), ...encodeRegistersArray([151]), 0, 0,
+      OP.LOAD_STRING, 151, ...encodeString("prototype"),
+      OP.PROPACCESS, 152, 150, 151,
+      OP.LOAD_STRING, 153, ...encodeString("get"),
+      OP.BCFUNC_CALLBACK, 154, ...encodeLongNum(97), ...encodeRegistersArray([]), 0,
+      OP.DEFINE_METHOD, 152, 153, 154,
+      // class Derived extends Base { constructor() { super(66); this.y = super.get(); } }
+      OP.BCCLASS_CALLBACK, 155, ...encodeLongNum(108), ...encodeRegistersArray([]), 0, 1,
+      OP.EXTEND_CLASS, 155, 150,
+      OP.STORE_CAPTURED, 0, 0, 155,
+      OP.CONSTRUCT, 156, 155, ...encodeRegistersArray([]),
+      OP.PROPACCESS, 158, 156, 153,
+      OP.FUNC_CALL, 157, 158, 156, ...encodeRegistersArray([]),
+      OP.LOAD_STRING, 158, ...encodeString("y"),
+      OP.PROPACCESS, 159, 156, 158,
+      OP.EXIT,
+
+      // The constructor of Base
+      OP.LOAD_STRING, 160, ...encodeString("x"),
+      OP.PROPSET, REGS.THIS, 160, 151,
+      OP.RETURN_CONSTRUCTOR, REGS.VOID,
+
+      // Base.prototype.get
+      OP.LOAD_STRING, 160, ...encodeString("x"),
+      OP.PROPACCESS, 161, REGS.THIS, 160,
+      OP.RETURN_BCFUNC, 161,
+
+      // The constructor of Derived
+      OP.LOAD_NUM, 160, 66,
+      OP.LOAD_ARRAY, 161, ...encodeRegistersArray([160]),
+      OP.LOAD_CAPTURED, 162, 0, 0,
+      OP.SUPER_CALL, REGS.TRASH_REG, 162, 161,
+      OP.LOAD_STRING, 163, ...encodeString("prototype"),
+      OP.PROPACCESS, 163, 162, 163,
+      OP.LOAD_STRING, 164, ...encodeString("get"),
+      OP.SUPER_PROPACCESS, 165, 163, 164,
+      OP.FUNC_CALL, 166, 165, REGS.THIS, ...encodeRegistersArray([]),
+      OP.LOAD_STRING, 167, ...encodeString("y"),
+      OP.PROPSET, REGS.THIS, 167, 166,
+      OP.RETURN_CONSTRUCTOR, REGS.VOID
+    ],
+    expected_registers: [
+      [157, 66],
+      [159, 66]
+    ]
+  },
+  {
+    name: "Resume generators",
+    bytecode: [
+      // function* g(a) { try { var b = yield a; yield b + 1; } catch(e) { yield e; } }
+      OP.BCGENERATOR_CALLBACK, 150, ...encodeLongNum(64), ...encodeRegistersArray([151]), 0,
+      OP.LOAD_NUM, 152, 5,
+      OP.FUNC_CALL, 153, 150, REGS.VOID, ...encodeRegistersArray([152]),
+      OP.LOAD_STRING, 154, ...encodeString("next"),
+      OP.PROPACCESS, 155, 153, 154,
+      OP.FUNC_CALL, 156, 155, 153, ...encodeRegistersArray([]),
+      OP.LOAD_NUM, 157, 10,
+      OP.FUNC_CALL, 158, 155, 153, ...encodeRegistersArray([157]),
+      OP.LOAD_STRING, 154, ...encodeString("throw"),
+      OP.PROPACCESS, 155, 153, 154,
+      OP.FUNC_CALL, 159, 155, 153, ...encodeRegistersArray([157]),
+      OP.EXIT,
+
+      // The generator function
+      OP.TRY, 160, ...encodeLongNum(83),
+      OP.YIELD, 161, 151,
+      OP.ADD, 162, 161, REGS.NUM_1,
+      OP.YIELD, REGS.TRASH_REG, 162,
+      OP.END_TRY,
+      OP.RETURN_BCFUNC, REGS.VOID,
+      OP.YIELD, REGS.TRASH_REG, 160,
+      OP.RETURN_BCFUNC, REGS.VOID
+    ],
+    expected_registers: [
+      [156, {value: 5, done: false}],
+      [158, {value: 11, done: false}],
+      [159, {value: 10, done: false}]
+    ]
+  },
+  {
+    name: "Return generators through finally blocks",
+    bytecode: [
+      // function* g() { try { try { yield 1; } catch(e) { yield e; } } finally { yield 2; } }
+      OP.BCGENERATOR_CALLBACK, 150, ...encodeLongNum(59), ...encodeRegistersArray([]), 0,
+      OP.FUNC_CALL, 151, 150, REGS.VOID, ...encodeRegistersArray([]),
+      OP.LOAD_STRING, 152, ...encodeString("next"),
+      OP.PROPACCESS, 153, 151, 152,
+      OP.FUNC_CALL, 154, 153, 151, ...encodeRegistersArray([]),
+      OP.LOAD_STRING, 152, ...encodeString("return"),
+      OP.PROPACCESS, 155, 151, 152,
+      OP.LOAD_NUM, 156, 7,
+      OP.FUNC_CALL, 157, 155, 151, ...encodeRegistersArray([156]),
+      OP.FUNC_CALL, 158, 153, 151, ...encodeRegistersArray([]),
+      OP.EXIT,
+
+      // The generator function
+      OP.TRY_FINALLY, 160, ...encodeLongNum(83),
+      OP.TRY, 161, ...encodeLongNum(78),
+      OP.YIELD, REGS.TRASH_REG, REGS.NUM_1,
+      OP.END_TRY,
+      OP.END_TRY,
+      OP.RETURN_BCFUNC, REGS.VOID,
+      // The catch block is skipped when the generator is returned...
+      OP.YIELD, REGS.TRASH_REG, 161,
+      OP.RETURN_BCFUNC, REGS.VOID,
+      // ...but the finally block runs and continues the return afterwards
+      OP.LOAD_NUM, 162, 2,
+      OP.YIELD, REGS.TRASH_REG, 162,
+      OP.THROW, 160
+    ],
+    expected_registers: [
+      [154, {value: 1, done: false}],
+      [157, {value: 2, done: false}],
+      [158, {value: 7, done: true}]
+    ]
+  },
+  {
+    name: "Await promises",
+    init: function() {
+      window.resolved = Promise.resolve(41);
+      window.rejected = Promise.reject(1);
+    },
+    bytecode: [
+      // async function f(p) { try { return await p; } catch(e) { return e + 1; } }
+      OP.BCASYNCFUNC_CALLBACK, 150, ...encodeLongNum(54), ...encodeRegistersArray([151]), 0,
+      OP.LOAD_STRING, 152, ...encodeString("resolved"),
+      OP.PROPACCESS, 153, REGS.WINDOW, 152,
+      OP.FUNC_CALL, 154, 150, REGS.VOID, ...encodeRegistersArray([153]),
+      OP.LOAD_STRING, 152, ...encodeString("rejected"),
+      OP.PROPACCESS, 153, REGS.WINDOW, 152,
+      OP.FUNC_CALL, 155, 150, REGS.VOID, ...encodeRegistersArray([153]),
+      OP.EXIT,
+
+      // The async function
+      OP.TRY, 160, ...encodeLongNum(66),
+      OP.AWAIT, 161, 151,
+      OP.END_TRY,
+      OP.RETURN_BCFUNC, 161,
+      OP.ADD, 162, 160, REGS.NUM_1,
+      OP.RETURN_BCFUNC, 162
+    ],
+    expected_registers: [],
+    expected_resolved: [
+      [154, 41],
+      [155, 2]
+    ]
   }
 ]
 
@@ -316,6 +976,14 @@ function runVMTests(testData) {
                 "Expected register " + regData[0] +  " to be " + regData[1] +
                 " but it is " + vm.getReg(regData[0]));
   }
+
+  // Registers holding promises are checked once they are settled
+  if(typeof testData.expected_resolved !== "undefined") {
+    return Promise.all(testData.expected_resolved.map(regData => vm.getReg(regData[0]).then(value => {
+      assert.deepEqual(value, regData[1], "Expected register " + regData[0] + " to resolve to " + regData[1] +
+                       " but it resolved to " + value);
+    })));
+  }
 }
 
 
@@ -1,5 +1,8 @@
 var FutureDeclerationsPlaceHolder = {}
 
+// Registers below this one are local to the frame of a function call
+const FRAME_SIZE = 200;
+
 const REGS = {
   // External dependencies
   WINDOW: 100, // This is only for testing
@@ -8,6 +11,10 @@ const REGS = {
   BYTECODE_PTR: 200,
   BCFUNC_RETURN: 201,
   TRASH_REG: 202,
+  THIS: 203,
+  ENV: 204,
+  FRAME_PTR: 205,
+  UNINITIALIZED: 206,
 
   // Common literals
   // EMPTY_OBJ: 252,
@@ -23,6 +30,8 @@ const OP = {
   LOAD_FLOAT: 3,
   LOAD_LONG_NUM: 4,
   LOAD_ARRAY: 5,
+  CREATE_OBJECT: 6,
+  LOAD_REGEXP: 7,
 
   // Misc
   PROPACCESS: 10,
@@ -39,6 +48,32 @@ const OP = {
   PROPSET: 21,
   TRY: 22,
   THROW: 23,
+  DEFINE_PROP: 24,
+  DEFINE_GETTER: 25,
+  DEFINE_SETTER: 26,
+  CONSTRUCT: 27,
+  BCARROWFUNC_CALLBACK: 28,
+  CREATE_ENV: 29,
+  LOAD_CAPTURED: 30,
+  STORE_CAPTURED: 31,
+  END_TRY: 32,
+  LOAD_SPILLED: 33,
+  STORE_SPILLED: 34,
+  JUMP_TABLE: 35,
+  GET_KEYS_ITERATOR: 36,
+  GET_ITERATOR: 37,
+  ITERATOR_NEXT: 38,
+  DELETE_PROPERTY: 39,
+  ITERATOR_REST: 40,
+  OBJECT_REST: 41,
+  LOAD_ARGUMENTS: 42,
+  LOAD_REST_ARGUMENTS: 43,
+  ARRAY_SPREAD: 44,
+  FUNC_CALL_APPLY: 45,
+  CONSTRUCT_APPLY: 46,
+  TEMPLATE_OBJECT: 47,
+  TO_STRING: 48,
+  CHECK_INITIALIZED: 49,
 
   // Comparisons
   COMP_EQUAL: 50,
@@ -49,12 +84,80 @@ const OP = {
   COMP_GREATHER_THAN: 55,
   COMP_LESS_THAN_EQUAL: 56,
   COMP_GREATHER_THAN_EQUAL: 57,
+  IN: 58,
+  INSTANCE_OF: 59,
+
+  // Classes
+  BCCLASS_CALLBACK: 60,
+  EXTEND_CLASS: 61,
+  DEFINE_METHOD: 62,
+  DEFINE_METHOD_GETTER: 63,
+  DEFINE_METHOD_SETTER: 64,
+  SUPER_CALL: 65,
+  SUPER_PROPACCESS: 66,
+  LOAD_NEW_TARGET: 67,
+  RETURN_CONSTRUCTOR: 68,
+  DEFINE_CLASS_NAME: 69,
+
+  // Generators and async functions
+  BCGENERATOR_CALLBACK: 70,
+  YIELD: 71,
+  YIELD_DELEGATE: 72,
+  BCASYNCFUNC_CALLBACK: 73,
+  BCASYNCARROWFUNC_CALLBACK: 74,
+  AWAIT: 75,
+  TRY_FINALLY: 76,
+  ITERATOR_CLOSE: 77,
+  SET_PROTOTYPE: 78,
 
   // Math
   ADD: 100,
   MUL: 101,
   MINUS: 102,
-  DIV: 103
+  DIV: 103,
+  MOD: 104,
+  POWER_OF: 105,
+  LEFT_SHIFT: 106,
+  RIGHT_SHIFT: 107,
+  UNSIGNED_RIGHT_SHIFT: 108,
+  BIT_OR: 109,
+  BIT_XOR: 110,
+  BIT_AND: 111,
+  NOT: 112,
+  BIT_NOT: 113,
+  TYPE_OF: 114
+};
+
+// The suspended frames of generator objects
+const generatorStates = new WeakMap();
+
+// Thrown where a generator is suspended when it is returned, only finally blocks handle it
+const GeneratorReturn = class {
+  constructor(value) {
+    this.value = value;
+  }
+};
+
+// Only constants and the VM itself are kept when the VM is composed with bytecode
+const BytecodeGenerator = class {
+  next(value) {
+    var state = generatorStates.get(this);
+    return state.vm._resumeGenerator(state, "next", value);
+  }
+
+  return(value) {
+    var state = generatorStates.get(this);
+    return state.vm._resumeGenerator(state, "return", value);
+  }
+
+  throw(error) {
+    var state = generatorStates.get(this);
+    return state.vm._resumeGenerator(state, "throw", error);
+  }
+
+  [Symbol.iterator]() {
+    return this;
+  }
 };
 
 class VM {
@@ -62,8 +165,20 @@ class VM {
     this.regs =  [];
     this.bytecode = [];
     this.ops = [];
-    this.reg_backups = [];
-    this.modified_regs = [];
+    this.frames = [];
+    this.handlers = [];
+    // Declarations which do not fit into the registers of the current frame
+    this.spills = [];
+    // Arguments the current bytecode function was called with
+    this.args = [];
+    // The constructor 'new' was applied to, if the current bytecode function was called by it
+    this.newTarget = void 0;
+    // The state of the generator object the current frame belongs to
+    this.generator = void 0;
+    // Strings arrays of tagged templates by their call site
+    this.templateObjects = [];
+    // The registers of the main frame are placed behind the shared registers
+    this.regs[REGS.FRAME_PTR] = 256;
     try {
       this.atob = window.atob;
     } catch(e) {}
@@ -93,6 +208,16 @@ class VM {
       vm.setReg(dst, array);
     };
 
+    this.ops[OP.CREATE_OBJECT] = function(vm) {
+      var dst = vm.getByte();
+      vm.setReg(dst, {});
+    };
+
+    this.ops[OP.LOAD_REGEXP] = function(vm) {
+      var dst = vm.getByte(), pattern = vm._loadString(), flags = vm._loadString();
+      vm.setReg(dst, new RegExp(pattern, flags));
+    };
+
     this.ops[OP.PROPACCESS] = function(vm) {
       var dst = vm.getByte(), obj = vm.getByte(), prop = vm.getByte();
       obj = vm.getReg(obj); prop = vm.getReg(prop);
@@ -109,19 +234,52 @@ class VM {
       dstObj[dstProp] = val;
     };
 
-    this.ops[OP.TRY] = function(vm) {
-      var catchBlockExceptReg = vm.getByte();
-      var catchBlockOffset = vm._loadLongNum();
-      var finallyBlockOffset = vm._loadLongNum();
+    this.ops[OP.DEFINE_PROP] = function(vm) {
+      var dstObj = vm.getByte(), dstProp = vm.getByte(), val = vm.getByte();
+      dstObj = vm.getReg(dstObj);
+      dstProp = vm.getReg(dstProp);
+      val = vm.getReg(val);
 
-      try {
-        vm.run();
-      } catch(e) {
-        vm.setReg(catchBlockExceptReg, e);
-        vm.runAt(catchBlockOffset);
-      } finally {
-        vm.runAt(finallyBlockOffset);
+      Object.defineProperty(dstObj, dstProp, {value: val, writable: true, enumerable: true, configurable: true});
+    };
+
+    this.ops[OP.DEFINE_GETTER] = function(vm) {
+      var dstObj = vm.getByte(), dstProp = vm.getByte(), getter = vm.getByte();
+      dstObj = vm.getReg(dstObj);
+      dstProp = vm.getReg(dstProp);
+      getter = vm.getReg(getter);
+
+      Object.defineProperty(dstObj, dstProp, {get: getter, enumerable: true, configurable: true});
+    };
+
+    this.ops[OP.DEFINE_SETTER] = function(vm) {
+      var dstObj = vm.getByte(), dstProp = vm.getByte(), setter = vm.getByte();
+      dstObj = vm.getReg(dstObj);
+      dstProp = vm.getReg(dstProp);
+      setter = vm.getReg(setter);
+
+      Object.defineProperty(dstObj, dstProp, {set: setter, enumerable: true, configurable: true});
+    };
+
+    this.ops[OP.SET_PROTOTYPE] = function(vm) {
+      var dstObj = vm.getReg(vm.getByte()), proto = vm.getReg(vm.getByte());
+
+      // Like '__proto__' in object literals, other values are ignored
+      if(proto === null || typeof proto == "object" || typeof proto == "function") {
+        Object.setPrototypeOf(dstObj, proto);
       }
+    };
+
+    this.ops[OP.TRY] = function(vm) {
+      vm._pushHandler(false);
+    }
+
+    this.ops[OP.TRY_FINALLY] = function(vm) {
+      vm._pushHandler(true);
+    }
+
+    this.ops[OP.END_TRY] = function(vm) {
+      vm.handlers.pop();
     }
 
     this.ops[OP.THROW] = function(vm) {
@@ -138,6 +296,60 @@ class VM {
       vm.setReg(dst, func.apply(funcThis, args));
     }
 
+    this.ops[OP.CONSTRUCT] = function(vm) {
+      var dst = vm.getByte(), func = vm.getByte(), args = vm._loadArrayFromRegister();
+      func = vm.getReg(func);
+
+      vm.setReg(dst, new func(...args));
+    }
+
+    this.ops[OP.FUNC_CALL_APPLY] = function(vm) {
+      var dst = vm.getByte(), func = vm.getReg(vm.getByte()), funcThis = vm.getReg(vm.getByte()),
+          args = vm.getReg(vm.getByte());
+
+      vm.setReg(dst, func.apply(funcThis, args));
+    }
+
+    this.ops[OP.CONSTRUCT_APPLY] = function(vm) {
+      var dst = vm.getByte(), func = vm.getReg(vm.getByte()), args = vm.getReg(vm.getByte());
+
+      vm.setReg(dst, new func(...args));
+    }
+
+    this.ops[OP.ARRAY_SPREAD] = function(vm) {
+      var array = vm.getReg(vm.getByte()), iterable = vm.getReg(vm.getByte());
+
+      for(let value of iterable) {
+        array.push(value);
+      }
+    }
+
+    this.ops[OP.TEMPLATE_OBJECT] = function(vm) {
+      // The address of the instruction identifies the call site
+      var site = vm.getReg(REGS.BYTECODE_PTR);
+      var dst = vm.getByte(), cooked = vm.getReg(vm.getByte()), raw = vm.getReg(vm.getByte());
+
+      if(!vm.templateObjects[site]) {
+        Object.defineProperty(cooked, "raw", {value: Object.freeze(raw)});
+        vm.templateObjects[site] = Object.freeze(cooked);
+      }
+
+      vm.setReg(dst, vm.templateObjects[site]);
+    }
+
+    this.ops[OP.TO_STRING] = function(vm) {
+      var dst = vm.getByte(), src = vm.getByte();
+      vm.setReg(dst, `${vm.getReg(src)}`);
+    }
+
+    this.ops[OP.CHECK_INITIALIZED] = function(vm) {
+      var value = vm.getReg(vm.getByte());
+      if(value === vm.getReg(REGS.UNINITIALIZED)) {
+        // The names of declarations are not part of the bytecode
+        throw new ReferenceError("Cannot access a lexical declaration before its initialization");
+      }
+    }
+
     this.ops[OP.EVAL] = function(vm) {
       var dst = vm.getByte(), str = vm.getByte();
       str = vm.getReg(str);
@@ -149,10 +361,18 @@ class VM {
       var funcOffset = vm._loadLongNum();
       var returnReg = vm.getByte();
       var argsArray = vm._loadRegistersArray();
-      vm.reg_backups.push([vm.regs.slice(), returnReg]);
+      var funcEnv = vm._getEnv(vm.getByte());
+      var args = [];
 
       for(let i = 0; i < argsArray.length; i+=2) {
-        vm.setReg(argsArray[i], vm.getReg(argsArray[i+1]));
+        args.push(vm.getReg(argsArray[i+1]));
+      }
+
+      // Bytecode functions are only called directly when they are not member functions
+      vm._pushFrame(returnReg, void 0, funcEnv, args, void 0);
+
+      for(let i = 0; i < argsArray.length; i+=2) {
+        vm.setReg(argsArray[i], args[i/2]);
       }
 
       vm.setReg(REGS.BYTECODE_PTR, funcOffset);
@@ -160,24 +380,7 @@ class VM {
 
     this.ops[OP.RETURN_BCFUNC] = function(vm) {
       var returnFromReg = vm.getByte();
-      var exceptedRegs = vm._loadRegistersArray();
-      var returnData = vm.reg_backups.pop();
-      var regBackups = returnData[0];
-      let returnToReg = returnData[1];
-
-      vm.modified_regs = [...new Set([...vm.modified_regs, ...exceptedRegs])];
-
-      regBackups[returnToReg] = vm.getReg(returnFromReg);
-
-      for(let exceptedReg of vm.modified_regs) {
-        regBackups[exceptedReg] = vm.getReg(exceptedReg);
-      }
-
-      if(!vm.reg_backups.length) {
-        vm.modified_regs = [];
-      }
-
-      vm.regs = regBackups;
+      vm._popFrame(vm.getReg(returnFromReg));
     }
 
     this.ops[OP.COPY] = function(vm) {
@@ -216,14 +419,299 @@ class VM {
 
     this.ops[OP.BCFUNC_CALLBACK] = function(vm) {
       var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte());
+      vm.setReg(dst, function() {
+        return vm.runFuncAt(func_offset, arg_regs, arguments, this, funcEnv, new.target);
+      });
+    }
+
+    this.ops[OP.BCARROWFUNC_CALLBACK] = function(vm) {
+      var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte());
+      var lexicalThis = vm.getReg(REGS.THIS), lexicalNewTarget = vm.newTarget;
+      vm.setReg(dst, (...args) => vm.runFuncAt(func_offset, arg_regs, args, lexicalThis, funcEnv, lexicalNewTarget));
+    }
+
+    this.ops[OP.BCCLASS_CALLBACK] = function(vm) {
+      var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte()), isDerived = vm.getByte();
+      vm.setReg(dst, function() {
+        if(new.target === void 0) {
+          throw new TypeError("Class constructors cannot be invoked without 'new'");
+        }
+        // Derived constructors receive their instance from the parent constructor
+        var instance = isDerived ? vm.getReg(REGS.UNINITIALIZED) : Object.create(new.target.prototype);
+        return vm.runFuncAt(func_offset, arg_regs, arguments, instance, funcEnv, new.target);
+      });
+    }
+
+    this.ops[OP.EXTEND_CLASS] = function(vm) {
+      var cls = vm.getReg(vm.getByte()), parent = vm.getReg(vm.getByte());
+
+      if(parent === null) {
+        Object.setPrototypeOf(cls.prototype, null);
+      } else if(typeof parent != "function") {
+        throw new TypeError("Class extends value " + String(parent) + " is not a constructor or null");
+      } else {
+        Object.setPrototypeOf(cls, parent);
+        Object.setPrototypeOf(cls.prototype, parent.prototype);
+      }
+    }
+
+    this.ops[OP.DEFINE_METHOD] = function(vm) {
+      var dstObj = vm.getReg(vm.getByte()), dstProp = vm.getReg(vm.getByte()), method = vm.getReg(vm.getByte());
+      Object.defineProperty(dstObj, dstProp, {value: method, writable: true, enumerable: false, configurable: true});
+    }
+
+    this.ops[OP.DEFINE_METHOD_GETTER] = function(vm) {
+      var dstObj = vm.getReg(vm.getByte()), dstProp = vm.getReg(vm.getByte()), getter = vm.getReg(vm.getByte());
+      Object.defineProperty(dstObj, dstProp, {get: getter, enumerable: false, configurable: true});
+    }
+
+    this.ops[OP.DEFINE_METHOD_SETTER] = function(vm) {
+      var dstObj = vm.getReg(vm.getByte()), dstProp = vm.getReg(vm.getByte()), setter = vm.getReg(vm.getByte());
+      Object.defineProperty(dstObj, dstProp, {set: setter, enumerable: false, configurable: true});
+    }
+
+    this.ops[OP.SUPER_CALL] = function(vm) {
+      var dst = vm.getByte(), cls = vm.getReg(vm.getByte()), args = vm.getReg(vm.getByte());
+      var instance = Reflect.construct(Object.getPrototypeOf(cls), args, vm.newTarget);
+
+      if(vm.getReg(REGS.THIS) !== vm.getReg(REGS.UNINITIALIZED)) {
+        throw new ReferenceError("Super constructor may only be called once");
+      }
+      vm.setReg(REGS.THIS, instance);
+      vm.setReg(dst, instance);
+    }
+
+    this.ops[OP.SUPER_PROPACCESS] = function(vm) {
+      var dst = vm.getByte(), home = vm.getReg(vm.getByte()), prop = vm.getReg(vm.getByte());
+      vm.setReg(dst, Reflect.get(Object.getPrototypeOf(home), prop, vm.getReg(REGS.THIS)));
+    }
+
+    this.ops[OP.LOAD_NEW_TARGET] = function(vm) {
+      var dst = vm.getByte();
+      vm.setReg(dst, vm.newTarget);
+    }
+
+    this.ops[OP.RETURN_CONSTRUCTOR] = function(vm) {
+      var value = vm.getReg(vm.getByte()), instance = vm.getReg(REGS.THIS);
+
+      // Constructors return their instance, unless they return another object
+      if(value === null || (typeof value != "object" && typeof value != "function")) {
+        if(instance === vm.getReg(REGS.UNINITIALIZED)) {
+          throw new ReferenceError("Must call super constructor in derived class before returning from derived constructor");
+        }
+        value = instance;
+      }
+      vm._popFrame(value);
+    }
+
+    this.ops[OP.DEFINE_CLASS_NAME] = function(vm) {
+      var cls = vm.getReg(vm.getByte()), name = vm.getReg(vm.getByte());
+      Object.defineProperty(cls, "name", {value: name, writable: false, enumerable: false, configurable: true});
+    }
+
+    this.ops[OP.BCGENERATOR_CALLBACK] = function(vm) {
+      var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte());
+      vm.setReg(dst, function() {
+        if(new.target !== void 0) {
+          throw new TypeError("Generator functions are not constructors");
+        }
+        // The body does not run until the generator is resumed for the first time
+        var generator = new BytecodeGenerator();
+        generatorStates.set(generator, vm._newSuspendedFrame(func_offset, arg_regs, arguments, this, funcEnv, void 0));
+        return generator;
+      });
+    }
+
+    this.ops[OP.YIELD] = function(vm) {
+      var dst = vm.getByte(), value = vm.getReg(vm.getByte());
+      vm._suspendGenerator(dst);
+      vm._popFrame(value);
+    }
+
+    this.ops[OP.YIELD_DELEGATE] = function(vm) {
+      var dst = vm.getByte(), iterable = vm.getReg(vm.getByte());
+      var delegate = iterable[Symbol.iterator](), result = delegate.next();
+
+      if(result.done) {
+        vm.setReg(dst, result.value);
+        return;
+      }
+
+      // The generator is resumed by the delegate until it is done
+      vm.generator.delegate = delegate;
+      vm._suspendGenerator(dst);
+      vm._popFrame(result.value);
+    }
+
+    this.ops[OP.BCASYNCFUNC_CALLBACK] = function(vm) {
+      var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte());
       vm.setReg(dst, function() {
-        for(let i = 0; i<arg_regs.length; ++i) {
-          vm.setReg(arg_regs[i], arguments[i]);
+        if(new.target !== void 0) {
+          throw new TypeError("Async functions are not constructors");
+        }
+        return vm._runAsyncFunc(vm._newSuspendedFrame(func_offset, arg_regs, arguments, this, funcEnv, void 0));
+      });
+    }
+
+    this.ops[OP.BCASYNCARROWFUNC_CALLBACK] = function(vm) {
+      var dst = vm.getByte(), func_offset = vm._loadLongNum(), arg_regs = vm._loadRegistersArray();
+      var funcEnv = vm._getEnv(vm.getByte());
+      var lexicalThis = vm.getReg(REGS.THIS), lexicalNewTarget = vm.newTarget;
+      vm.setReg(dst, (...args) => vm._runAsyncFunc(vm._newSuspendedFrame(func_offset, arg_regs, args, lexicalThis,
+                                                                         funcEnv, lexicalNewTarget)));
+    }
+
+    this.ops[OP.AWAIT] = function(vm) {
+      // Async functions are suspended just like generators, their promise reaction resumes them
+      var dst = vm.getByte(), value = vm.getReg(vm.getByte());
+      vm._suspendGenerator(dst);
+      vm._popFrame(value);
+    }
+
+    this.ops[OP.CREATE_ENV] = function(vm) {
+      // The first slot of an environment links to its parent environment
+      vm.setReg(REGS.ENV, [vm.getReg(REGS.ENV)]);
+    }
+
+    this.ops[OP.LOAD_CAPTURED] = function(vm) {
+      var dst = vm.getByte(), env = vm._getEnv(vm.getByte()), index = vm.getByte();
+      vm.setReg(dst, env[index + 1]);
+    }
+
+    this.ops[OP.STORE_CAPTURED] = function(vm) {
+      var env = vm._getEnv(vm.getByte()), index = vm.getByte(), src = vm.getByte();
+      env[index + 1] = vm.getReg(src);
+    }
+
+    this.ops[OP.JUMP_TABLE] = function(vm) {
+      var value = vm.getReg(vm.getByte()), min = vm._loadLongNum(), count = vm.getByte();
+      var offsets = [];
+
+      for(let i = 0; i < count; i++) {
+        offsets.push(vm._loadLongNum());
+      }
+
+      // Values outside of the table continue with the next instruction
+      var idx = value - min;
+      if(typeof value == "number" && Number.isInteger(idx) && idx >= 0 && idx < count) {
+        vm.setReg(REGS.BYTECODE_PTR, offsets[idx]);
+      }
+    }
+
+    this.ops[OP.GET_KEYS_ITERATOR] = function(vm) {
+      var dst = vm.getByte(), obj = vm.getReg(vm.getByte());
+      var keys = [], index = 0;
+
+      for(let key in obj) {
+        keys.push(key);
+      }
+
+      // Keys which are deleted during the loop are not visited anymore
+      vm.setReg(dst, {
+        next: function() {
+          while(index < keys.length) {
+            var key = keys[index++];
+            if(key in Object(obj)) {
+              return {value: key, done: false};
+            }
+          }
+          return {value: void 0, done: true};
         }
-        vm.runFuncAt(func_offset)
       });
     }
 
+    this.ops[OP.GET_ITERATOR] = function(vm) {
+      var dst = vm.getByte(), obj = vm.getReg(vm.getByte());
+      vm.setReg(dst, obj[Symbol.iterator]());
+    }
+
+    this.ops[OP.ITERATOR_NEXT] = function(vm) {
+      var done = vm.getByte(), dst = vm.getByte(), iterator = vm.getReg(vm.getByte());
+      // Iterators whose 'next' method throws are not closed
+      vm.setReg(done, true);
+      var result = iterator.next();
+
+      vm.setReg(done, result.done);
+      // The loop variable keeps its last value
+      if(!result.done) {
+        vm.setReg(dst, result.value);
+      }
+    }
+
+    this.ops[OP.ITERATOR_CLOSE] = function(vm) {
+      var iterator = vm.getReg(vm.getByte());
+      var returnMethod = iterator.return;
+
+      if(returnMethod !== void 0 && returnMethod !== null) {
+        var result = returnMethod.call(iterator);
+        if(Object(result) !== result) {
+          throw new TypeError("The iterator result is not an object");
+        }
+      }
+    }
+
+        this.ops[OP.DELETE_PROPERTY] = function(vm) {
+      var dst = vm.getByte(), obj = vm.getByte(), prop = vm.getByte();
+      obj = vm.getReg(obj);
+      prop = vm.getReg(prop);
+
+      vm.setReg(dst, delete obj[prop]);
+    }
+
+    this.ops[OP.ITERATOR_REST] = function(vm) {
+      var dst = vm.getByte(), iterator = vm.getReg(vm.getByte());
+      var rest = [];
+
+      for(let result = iterator.next(); !result.done; result = iterator.next()) {
+        rest.push(result.value);
+      }
+
+      vm.setReg(dst, rest);
+    }
+
+    this.ops[OP.OBJECT_REST] = function(vm) {
+      var dst = vm.getByte(), obj = vm.getReg(vm.getByte()), excluded = vm._loadArrayFromRegister();
+      var rest = {};
+
+      if(obj === null || obj === undefined) {
+        throw new TypeError("Cannot destructure '" + obj + "'");
+      }
+
+      excluded = excluded.map(key => typeof key == "symbol" ? key : String(key));
+      for(let key of Reflect.ownKeys(Object(obj))) {
+        if(!excluded.includes(key) && Object.prototype.propertyIsEnumerable.call(obj, key)) {
+          rest[key] = obj[key];
+        }
+      }
+
+      vm.setReg(dst, rest);
+    }
+
+    this.ops[OP.LOAD_SPILLED] = function(vm) {
+      var dst = vm.getByte(), slot = vm._loadLongNum();
+      vm.setReg(dst, vm.spills[slot]);
+    }
+
+    this.ops[OP.STORE_SPILLED] = function(vm) {
+      var slot = vm._loadLongNum(), src = vm.getByte();
+      vm.spills[slot] = vm.getReg(src);
+    }
+
+    this.ops[OP.LOAD_ARGUMENTS] = function(vm) {
+      var dst = vm.getByte();
+      vm.setReg(dst, (function() { return arguments; }).apply(void 0, vm.args));
+    }
+
+    this.ops[OP.LOAD_REST_ARGUMENTS] = function(vm) {
+      var dst = vm.getByte(), start = vm.getByte();
+      vm.setReg(dst, vm.args.slice(start));
+    }
+
     this.ops[OP.COMP_EQUAL] = function(vm) {
       var dst = vm.getByte(), left = vm.getByte(), right = vm.getByte();
       left = vm.getReg(left);
@@ -288,6 +776,22 @@ class VM {
       vm.setReg(dst, left >= right);
     }
 
+    this.ops[OP.IN] = function(vm) {
+      var dst = vm.getByte(), left = vm.getByte(), right = vm.getByte();
+      left = vm.getReg(left);
+      right = vm.getReg(right);
+
+      vm.setReg(dst, left in right);
+    }
+
+    this.ops[OP.INSTANCE_OF] = function(vm) {
+      var dst = vm.getByte(), left = vm.getByte(), right = vm.getByte();
+      left = vm.getReg(left);
+      right = vm.getReg(right);
+
+      vm.setReg(dst, left instanceof right);
+    }
+
     this.ops[OP.ADD] = function(vm) {
       var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
       vm.setReg(dst, vm.getReg(src0) + vm.getReg(src1));
@@ -295,26 +799,87 @@ class VM {
 
     this.ops[OP.MUL] = function(vm) {
       var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
-      vm.setReg(dst, vm.regs[src0] * vm.regs[src1]);
+      vm.setReg(dst, vm.getReg(src0) * vm.getReg(src1));
     }
 
     this.ops[OP.MINUS] = function(vm) {
       var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
-      vm.setReg(dst, vm.regs[src0] - vm.regs[src1]);
+      vm.setReg(dst, vm.getReg(src0) - vm.getReg(src1));
     }
 
     this.ops[OP.DIV] = function(vm) {
       var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
-      vm.setReg(dst, vm.regs[src0] / vm.regs[src1]);
+      vm.setReg(dst, vm.getReg(src0) / vm.getReg(src1));
+    }
+
+    this.ops[OP.MOD] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) % vm.getReg(src1));
+    }
+
+    this.ops[OP.POWER_OF] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) ** vm.getReg(src1));
+    }
+
+    this.ops[OP.LEFT_SHIFT] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) << vm.getReg(src1));
+    }
+
+    this.ops[OP.RIGHT_SHIFT] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) >> vm.getReg(src1));
+    }
+
+    this.ops[OP.UNSIGNED_RIGHT_SHIFT] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) >>> vm.getReg(src1));
+    }
+
+    this.ops[OP.BIT_OR] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) | vm.getReg(src1));
+    }
+
+    this.ops[OP.BIT_XOR] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) ^ vm.getReg(src1));
+    }
+
+    this.ops[OP.BIT_AND] = function(vm) {
+      var dst = vm.getByte(), src0 = vm.getByte(), src1 = vm.getByte();
+      vm.setReg(dst, vm.getReg(src0) & vm.getReg(src1));
+    }
+
+    this.ops[OP.NOT] = function(vm) {
+      var dst = vm.getByte(), src = vm.getByte();
+      vm.setReg(dst, !vm.getReg(src));
+    }
+
+    this.ops[OP.BIT_NOT] = function(vm) {
+      var dst = vm.getByte(), src = vm.getByte();
+      vm.setReg(dst, ~vm.getReg(src));
+    }
+
+    this.ops[OP.TYPE_OF] = function(vm) {
+      var dst = vm.getByte(), src = vm.getByte();
+      vm.setReg(dst, typeof vm.getReg(src));
     }
 
   }
 
   setReg(reg, value) {
+    if(reg < FRAME_SIZE) {
+      reg += this.regs[REGS.FRAME_PTR];
+    }
     this.regs[reg] = value;
   }
 
   getReg(reg) {
+    if(reg < FRAME_SIZE) {
+      reg += this.regs[REGS.FRAME_PTR];
+    }
     return this.regs[reg];
   }
 
@@ -322,29 +887,38 @@ class VM {
     return this.bytecode[this.regs[REGS.BYTECODE_PTR]++];
   }
 
-  run() {
-    while(this.regs[REGS.BYTECODE_PTR] < this.bytecode.length) {
+  run(baseDepth = 0) {
+    // A run started by runFuncAt ends when its function frame returns
+    while(this.regs[REGS.BYTECODE_PTR] < this.bytecode.length && this.frames.length >= baseDepth) {
       var op_code = this.getByte();
       var op = this.ops[op_code];
 
       try {
         op(this);
       } catch(e) {
-        console.log("Current stack ptr: ", this.regs[REGS.BYTECODE_PTR], "op code: ", op_code);
-        throw e;
+        this._handleException(e, op_code, baseDepth);
       }
     }
     return 0;
   }
 
-  runFuncAt(offset) {
-    this.reg_backups.push([this.regs.slice(), REGS.BCFUNC_RETURN]);
-    this.runAt(offset);
+  runFuncAt(offset, argRegs, args, funcThis, funcEnv, newTarget) {
+    // The function might be called while the VM is running (e.g. by a getter or Array.map).
+    // Thus the function gets its own frame and is run until it returns from it.
+    this._pushFrame(REGS.BCFUNC_RETURN, funcThis, funcEnv, Array.from(args), newTarget);
+
+    for(let i = 0; i<argRegs.length; ++i) {
+      this.setReg(argRegs[i], args[i]);
+    }
+
+    this.runAt(offset, this.frames.length);
+
+    return this.getReg(REGS.BCFUNC_RETURN);
   }
 
-  runAt(offset) {
+  runAt(offset, baseDepth = 0) {
     this.setReg(REGS.BYTECODE_PTR, offset);
-    this.run();
+    this.run(baseDepth);
   }
 
   init(bytecode) {
@@ -354,10 +928,240 @@ class VM {
     this.setReg(REGS.NUM_0, 0);
     this.setReg(REGS.NUM_1, 1);
     this.setReg(REGS.VOID, void 0);
+    this.setReg(REGS.THIS, typeof globalThis !== "undefined" ? globalThis : void 0);
+    // Lexical declarations hold this value until they are initialized
+    this.setReg(REGS.UNINITIALIZED, {});
 
     this.setReg(FutureDeclerationsPlaceHolder, 0);
   }
 
+  _pushFrame(returnReg, funcThis, funcEnv, funcArgs, newTarget) {
+    this.frames.push({
+      returnReg: returnReg,
+      framePtr: this.regs[REGS.FRAME_PTR],
+      bytecodePtr: this.regs[REGS.BYTECODE_PTR],
+      this: this.getReg(REGS.THIS),
+      env: this.getReg(REGS.ENV),
+      spills: this.spills,
+      args: this.args,
+      newTarget: this.newTarget,
+      generator: this.generator,
+      handlersCount: this.handlers.length
+    });
+
+    this.regs[REGS.FRAME_PTR] += FRAME_SIZE;
+    this.spills = [];
+    this.args = funcArgs;
+    this.newTarget = newTarget;
+    this.generator = void 0;
+    this.setReg(REGS.THIS, funcThis);
+    this.setReg(REGS.ENV, funcEnv);
+  }
+
+  _popFrame(returnValue) {
+    var frame = this.frames.pop();
+
+    // Drop the registers of the returning frame
+    this.regs.length = this.regs[REGS.FRAME_PTR];
+    this.regs[REGS.FRAME_PTR] = frame.framePtr;
+    this.regs[REGS.BYTECODE_PTR] = frame.bytecodePtr;
+    this.setReg(REGS.THIS, frame.this);
+    this.setReg(REGS.ENV, frame.env);
+    this.spills = frame.spills;
+    this.args = frame.args;
+    this.newTarget = frame.newTarget;
+    this.generator = frame.generator;
+    this.handlers.length = frame.handlersCount;
+
+    this.setReg(frame.returnReg, returnValue);
+  }
+
+  _newSuspendedFrame(funcOffset, argRegs, args, funcThis, funcEnv, newTarget) {
+    var regs = [];
+    for(let i = 0; i < argRegs.length; ++i) {
+      regs[argRegs[i]] = args[i];
+    }
+
+    return {
+      vm: this,
+      regs: regs,
+      bytecodePtr: funcOffset,
+      this: funcThis,
+      env: funcEnv,
+      spills: [],
+      args: Array.from(args),
+      newTarget: newTarget,
+      handlers: [],
+      resumeReg: void 0,
+      delegate: void 0,
+      running: false,
+      suspended: false,
+      done: false
+    };
+  }
+
+  _runAsyncFunc(state) {
+    return new Promise((resolve, reject) => {
+      var step = (mode, value) => {
+        var result;
+        try {
+          result = this._resumeGenerator(state, mode, value);
+        } catch(e) {
+          reject(e);
+          return;
+        }
+
+        if(result.done) {
+          resolve(result.value);
+        } else {
+          // Rejections are thrown where the function awaits them
+          Promise.resolve(result.value).then(value => step("next", value), error => step("throw", error));
+        }
+      };
+      step("next", void 0);
+    });
+  }
+
+  _suspendGenerator(resumeReg) {
+    var state = this.generator, framePtr = this.regs[REGS.FRAME_PTR];
+    var frame = this.frames[this.frames.length - 1];
+
+    // Everything the frame needs to continue at the current bytecode pointer is saved
+    state.regs = this.regs.slice(framePtr, framePtr + FRAME_SIZE);
+    state.bytecodePtr = this.regs[REGS.BYTECODE_PTR];
+    state.env = this.getReg(REGS.ENV);
+    state.spills = this.spills;
+    state.handlers = this.handlers.slice(frame.handlersCount);
+    state.resumeReg = resumeReg;
+    state.suspended = true;
+  }
+
+  _resumeGenerator(state, mode, value) {
+    if(state.running) {
+      throw new TypeError("Generator is already running");
+    }
+
+    if(state.delegate !== void 0) {
+      var delegate = state.delegate, method = delegate[mode];
+      state.delegate = void 0;
+
+      if(method !== void 0) {
+        var result;
+        try {
+          result = method.call(delegate, value);
+        } catch(e) {
+          return this._resumeGenerator(state, "throw", e);
+        }
+
+        if(!result.done) {
+          state.delegate = delegate;
+          return {value: result.value, done: false};
+        }
+        // The return value of the delegate is the result of yield*
+        mode = mode == "return" ? mode : "next";
+        value = result.value;
+      } else if(mode == "throw") {
+        if(delegate.return !== void 0) {
+          delegate.return();
+        }
+        value = new TypeError("The iterator does not provide a 'throw' method");
+      }
+    }
+
+    // Generators which are done or have not been started yet do not run their frame again
+    if(state.done || (state.resumeReg === void 0 && mode != "next")) {
+      state.done = true;
+      if(mode == "throw") {
+        throw value;
+      }
+      return {value: mode == "return" ? value : void 0, done: true};
+    }
+
+    this._pushFrame(REGS.BCFUNC_RETURN, state.this, state.env, state.args, state.newTarget);
+    this.generator = state;
+    this.spills = state.spills;
+
+    var framePtr = this.regs[REGS.FRAME_PTR], baseDepth = this.frames.length;
+    state.regs.forEach((regValue, reg) => { this.regs[framePtr + reg] = regValue; });
+    for(let handler of state.handlers) {
+      this.handlers.push(Object.assign({}, handler, {frameDepth: baseDepth}));
+    }
+    this.setReg(REGS.BYTECODE_PTR, state.bytecodePtr);
+
+    state.running = true;
+    state.suspended = false;
+    try {
+      if(mode == "throw") {
+        // The exception is thrown where the generator was suspended
+        this._handleException(value, OP.YIELD, baseDepth);
+      } else if(mode == "return") {
+        // The finally blocks around the suspended yield run before the generator returns
+        this._handleException(new GeneratorReturn(value), OP.YIELD, baseDepth);
+      } else if(state.resumeReg !== void 0) {
+        this.setReg(state.resumeReg, value);
+      }
+      this.run(baseDepth);
+    } catch(e) {
+      state.done = true;
+      if(e instanceof GeneratorReturn) {
+        return {value: e.value, done: true};
+      }
+      throw e;
+    } finally {
+      state.running = false;
+    }
+
+    state.done = !state.suspended;
+    return {value: this.getReg(REGS.BCFUNC_RETURN), done: state.done};
+  }
+
+  _pushHandler(isFinally) {
+    var exceptReg = this.getByte();
+    var offset = this._loadLongNum();
+
+    this.handlers.push({
+      exceptReg: exceptReg,
+      offset: offset,
+      frameDepth: this.frames.length,
+      env: this.getReg(REGS.ENV),
+      isFinally: isFinally
+    });
+  }
+
+  _handleException(e, op_code, baseDepth) {
+    var handler = this.handlers[this.handlers.length - 1];
+
+    if(e instanceof GeneratorReturn) {
+      // Returning generators leave catch blocks without running them
+      while(handler && handler.frameDepth >= baseDepth && !handler.isFinally) {
+        this.handlers.pop();
+        handler = this.handlers[this.handlers.length - 1];
+      }
+    }
+
+    if(!handler || handler.frameDepth < baseDepth) {
+      // The exception is not caught within this run, thus its frames are left
+      if(!(e instanceof GeneratorReturn)) {
+        console.log("Current stack ptr: ", this.regs[REGS.BYTECODE_PTR], "op code: ", op_code);
+      }
+      while(baseDepth > 0 && this.frames.length >= baseDepth) {
+        this._popFrame(void 0);
+      }
+      throw e;
+    }
+
+    // Leaving the frames restores their handler counts, thus the stack is cut afterwards
+    while(this.frames.length > handler.frameDepth) {
+      this._popFrame(void 0);
+    }
+    this.handlers.length = this.handlers.indexOf(handler);
+
+    // Blocks left by the exception might have entered their own environments
+    this.setReg(REGS.ENV, handler.env);
+    this.setReg(handler.exceptReg, e);
+    this.setReg(REGS.BYTECODE_PTR, handler.offset);
+  }
+
   _decodeBytecode(encodedBytecode) {
     var bytecode = this.atob(encodedBytecode);
     var bytes = [];
@@ -438,6 +1242,14 @@ class VM {
     return num;
   }
 
+  _getEnv(hops) {
+    var env = this.getReg(REGS.ENV);
+    for(let i = 0; i < hops; ++i) {
+      env = env[0];
+    }
+    return env;
+  }
+
   _loadRegistersArray() {
     var arrayLength = this.getByte();
     var registers_array = [];